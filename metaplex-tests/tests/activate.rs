@@ -3,7 +3,7 @@ mod utils;
 use metaplex_nft_packs::{
     error::NFTPacksError,
     instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::{PackDistributionType, PackSetState},
+    state::{PackDistributionType, PackSetState, RandomnessSource},
 };
 use num_traits::FromPrimitive;
 use solana_program::{instruction::InstructionError, system_instruction};
@@ -47,6 +47,10 @@ async fn setup() -> (
                 allowed_amount_to_redeem: 10,
                 redeem_start_date: None,
                 redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -126,6 +130,7 @@ async fn setup() -> (
                 max_supply: 5,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
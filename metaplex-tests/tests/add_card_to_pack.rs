@@ -3,7 +3,7 @@ mod utils;
 use metaplex_nft_packs::{
     error::NFTPacksError,
     instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::{AccountType, PackDistributionType},
+    state::{AccountType, PackDistributionType, RandomnessSource},
 };
 use num_traits::FromPrimitive;
 use solana_program::instruction::InstructionError;
@@ -46,6 +46,10 @@ async fn setup(
                 allowed_amount_to_redeem: 10,
                 redeem_start_date: None,
                 redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -106,6 +110,7 @@ async fn success() {
                 max_supply: 5,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -133,6 +138,7 @@ async fn fail_invalid_index() {
                 max_supply: 5,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -152,6 +158,7 @@ async fn fail_invalid_index() {
                 max_supply: 5,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await;
@@ -182,6 +189,7 @@ async fn fail_wrong_probability() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await;
@@ -211,6 +219,7 @@ async fn fail_unlimited_probability() {
                 max_supply: 0,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await;
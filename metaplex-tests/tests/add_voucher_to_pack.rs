@@ -2,7 +2,7 @@ mod utils;
 
 use metaplex_nft_packs::{
     instruction::InitPackSetArgs,
-    state::{AccountType, PackDistributionType},
+    state::{AccountType, PackDistributionType, RandomnessSource},
 };
 use solana_program::{instruction::InstructionError, system_instruction};
 use solana_program_test::*;
@@ -45,6 +45,10 @@ async fn setup() -> (
                 allowed_amount_to_redeem: 10,
                 redeem_start_date: None,
                 redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
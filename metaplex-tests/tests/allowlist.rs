@@ -0,0 +1,262 @@
+mod utils;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_wallet_record_program_address,
+    instruction::{
+        NFTPacksInstruction, OpenPackArgs, RequestCardToRedeemArgs, RequestCardsToRedeemArgs,
+    },
+    state::{AllowlistProof, WalletRecord},
+};
+use num_traits::FromPrimitive;
+use solana_program::{
+    hash::hashv, instruction::InstructionError, program_pack::Pack, pubkey::Pubkey,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    signer::Signer,
+    transaction::TransactionError,
+    transport::{self, TransportError},
+};
+use utils::*;
+
+// Merkle root of allowlist leaves and proof for every leaf, odd node is moved to the next level as is
+fn build_allowlist(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let mut proofs = vec![Vec::new(); leaves.len()];
+    let mut positions: Vec<usize> = (0..leaves.len()).collect();
+    let mut level = leaves.to_vec();
+
+    while level.len() > 1 {
+        for (leaf, position) in positions.iter_mut().enumerate() {
+            if let Some(sibling) = level.get(*position ^ 1) {
+                proofs[leaf].push(*sibling);
+            }
+            *position /= 2;
+        }
+
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] if left <= right => hashv(&[left, right]).to_bytes(),
+                [left, right] => hashv(&[right, left]).to_bytes(),
+                [node] => *node,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    (level[0], proofs)
+}
+
+// Allowlist with voucher owner and a few other wallets, returns root and proof of voucher owner
+fn allowlist(wallet: &Pubkey, quota: Option<u32>) -> ([u8; 32], AllowlistProof) {
+    let mut leaves: Vec<[u8; 32]> = (0..4)
+        .map(|i| AllowlistProof::leaf(&Pubkey::new_unique(), Some(i)))
+        .collect();
+    leaves.insert(1, AllowlistProof::leaf(wallet, quota));
+
+    let (root, proofs) = build_allowlist(&leaves);
+
+    (
+        root,
+        AllowlistProof {
+            quota,
+            proof: proofs[1].clone(),
+        },
+    )
+}
+
+async fn request_card(
+    test_pack: &mut TestPack,
+    voucher: usize,
+    allowlist_proof: Option<AllowlistProof>,
+) -> transport::Result<()> {
+    let voucher_edition = &test_pack.voucher_editions[voucher];
+
+    test_pack
+        .test_pack_set
+        .request_card_for_redeem_with_allowlist_proof(
+            &mut test_pack.context,
+            &test_pack.test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &test_pack.edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &test_pack.test_randomness_oracle.keypair.pubkey(),
+            1,
+            allowlist_proof,
+        )
+        .await
+}
+
+#[test]
+fn verify_allowlist_proof() {
+    let wallets: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+    let leaves: Vec<[u8; 32]> = wallets
+        .iter()
+        .map(|wallet| AllowlistProof::leaf(wallet, Some(2)))
+        .collect();
+    let (root, proofs) = build_allowlist(&leaves);
+
+    for (wallet, proof) in wallets.iter().zip(proofs) {
+        let allowlist_proof = AllowlistProof {
+            quota: Some(2),
+            proof,
+        };
+        assert!(allowlist_proof.verify(&root, wallet));
+        assert!(!allowlist_proof.verify(&root, &Pubkey::new_unique()));
+
+        // Quota is a part of the leaf and can't be changed
+        let unlimited_proof = AllowlistProof {
+            quota: None,
+            ..allowlist_proof
+        };
+        assert!(!unlimited_proof.verify(&root, wallet));
+    }
+}
+
+#[test]
+fn decode_args_without_allowlist_proof() {
+    let args = RequestCardToRedeemArgs {
+        index: 1,
+        allowlist_proof: None,
+    };
+    let mut data = NFTPacksInstruction::RequestCardForRedeem(args.clone())
+        .try_to_vec()
+        .unwrap();
+
+    // Clients built before allowlist was added don't send the proof at all
+    assert_eq!(data.pop(), Some(0));
+    match NFTPacksInstruction::try_from_slice(&data).unwrap() {
+        NFTPacksInstruction::RequestCardForRedeem(decoded) => assert_eq!(decoded, args),
+        _ => panic!("wrong instruction"),
+    }
+
+    let data = [1u32.to_le_bytes(), 2u32.to_le_bytes()].concat();
+    let args = RequestCardsToRedeemArgs::try_from_slice(&data).unwrap();
+    assert_eq!(args.count, 2);
+    assert_eq!(args.allowlist_proof, None);
+
+    let args = OpenPackArgs::try_from_slice(&1u32.to_le_bytes()).unwrap();
+    assert_eq!(args.allowlist_proof, None);
+
+    // Proof is still decoded when it's passed
+    let args = OpenPackArgs {
+        index: 1,
+        allowlist_proof: Some(AllowlistProof {
+            quota: Some(2),
+            proof: vec![[3; 32]],
+        }),
+    };
+    let data = args.try_to_vec().unwrap();
+    assert_eq!(OpenPackArgs::try_from_slice(&data).unwrap(), args);
+}
+
+#[tokio::test]
+async fn success_quota() {
+    let mut test_pack = TestPack::new().await;
+
+    let (root, allowlist_proof) = allowlist(&test_pack.edition_authority.pubkey(), Some(1));
+    test_pack
+        .test_pack_set
+        .set_allowlist_root(&mut test_pack.context, Some(root))
+        .await
+        .unwrap();
+
+    let pack_set = test_pack
+        .test_pack_set
+        .get_data(&mut test_pack.context)
+        .await;
+    assert_eq!(pack_set.allowlist_root, Some(root));
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    request_card(&mut test_pack, 0, Some(allowlist_proof.clone()))
+        .await
+        .unwrap();
+
+    let (wallet_record_key, _) = find_wallet_record_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &test_pack.edition_authority.pubkey(),
+    );
+    let wallet_record_data = get_account(&mut test_pack.context, &wallet_record_key).await;
+    let wallet_record = WalletRecord::unpack_from_slice(&wallet_record_data.data).unwrap();
+    assert_eq!(wallet_record.allowlist_vouchers, 1);
+
+    let result = request_card(&mut test_pack, 1, Some(allowlist_proof)).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::AllowlistQuotaReached, 0);
+}
+
+#[tokio::test]
+async fn success_without_quota() {
+    let mut test_pack = TestPack::new().await;
+
+    let (root, allowlist_proof) = allowlist(&test_pack.edition_authority.pubkey(), None);
+    test_pack
+        .test_pack_set
+        .set_allowlist_root(&mut test_pack.context, Some(root))
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    request_card(&mut test_pack, 0, Some(allowlist_proof.clone()))
+        .await
+        .unwrap();
+    request_card(&mut test_pack, 1, Some(allowlist_proof))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fail_not_in_allowlist() {
+    let mut test_pack = TestPack::new().await;
+
+    let (root, allowlist_proof) = allowlist(&Pubkey::new_unique(), None);
+    test_pack
+        .test_pack_set
+        .set_allowlist_root(&mut test_pack.context, Some(root))
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = request_card(&mut test_pack, 0, Some(allowlist_proof)).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotInAllowlist, 0);
+
+    let result = request_card(&mut test_pack, 0, None).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotInAllowlist, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_activation() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = test_pack
+        .test_pack_set
+        .set_allowlist_root(&mut test_pack.context, Some([1; 32]))
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
@@ -0,0 +1,215 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_treasury_program_address,
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackDistributionType, RandomnessSource},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const PRICE: u64 = 1000000;
+
+async fn setup(
+    price: Option<u64>,
+) -> (
+    ProgramTestContext,
+    TestPackSet,
+    TestPackVoucher,
+    TestMetadata,
+    TestMasterEditionV2,
+    Keypair,
+) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let redeem_start_date = Some(clock.unix_timestamp as u64);
+    let redeem_end_date = None;
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date,
+                redeem_end_date,
+                price,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let buyer = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &buyer.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &buyer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    // Voucher should have at least one edition to be added to the pack
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+    voucher_edition
+        .create(
+            &mut context,
+            &buyer,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply: 5,
+                weight: 100,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher_in_custody(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.activate(&mut context).await.unwrap();
+
+    (
+        context,
+        test_pack_set,
+        test_pack_voucher,
+        voucher_metadata,
+        voucher_master_edition,
+        buyer,
+    )
+}
+
+#[tokio::test]
+async fn success() {
+    let (
+        mut context,
+        test_pack_set,
+        test_pack_voucher,
+        voucher_metadata,
+        voucher_master_edition,
+        buyer,
+    ) = setup(Some(PRICE)).await;
+
+    let new_mint = Keypair::new();
+    let new_mint_token_acc = Keypair::new();
+
+    test_pack_set
+        .buy_pack(
+            &mut context,
+            &buyer,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &new_mint,
+            &new_mint_token_acc,
+            &None,
+        )
+        .await
+        .unwrap();
+
+    let (treasury, _) =
+        find_treasury_program_address(&metaplex_nft_packs::id(), &test_pack_set.keypair.pubkey());
+    let treasury_account = get_account(&mut context, &treasury).await;
+    assert_eq!(treasury_account.lamports, PRICE);
+
+    let voucher_master_edition = voucher_master_edition.get_data(&mut context).await;
+    assert_eq!(voucher_master_edition.supply, 2);
+
+    let voucher_edition = TestEdition::new(&new_mint.pubkey());
+    assert!(!is_empty_account(&mut context, &voucher_edition.pubkey).await);
+}
+
+#[tokio::test]
+async fn fail_not_for_sale() {
+    let (
+        mut context,
+        test_pack_set,
+        test_pack_voucher,
+        voucher_metadata,
+        voucher_master_edition,
+        buyer,
+    ) = setup(None).await;
+
+    let new_mint = Keypair::new();
+    let new_mint_token_acc = Keypair::new();
+
+    let result = test_pack_set
+        .buy_pack(
+            &mut context,
+            &buyer,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &new_mint,
+            &new_mint_token_acc,
+            &None,
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PackNotForSale, 0);
+}
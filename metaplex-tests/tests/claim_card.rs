@@ -6,7 +6,7 @@ use metaplex_nft_packs::{
     instruction::{
         claim_pack, AddCardToPackArgs, ClaimPackArgs, InitPackSetArgs, NFTPacksInstruction,
     },
-    state::{PackDistributionType, ProvingProcess},
+    state::{PackDistributionType, ProvingProcess, RandomnessSource},
 };
 use num_traits::FromPrimitive;
 use solana_program::{
@@ -25,39 +25,6 @@ use solana_sdk::{
 };
 use utils::*;
 
-async fn create_master_edition(
-    context: &mut ProgramTestContext,
-    test_pack_set: &TestPackSet,
-) -> (TestMetadata, TestMasterEditionV2, User) {
-    let test_metadata = TestMetadata::new();
-    let test_master_edition = TestMasterEditionV2::new(&test_metadata);
-
-    let user_token_acc = Keypair::new();
-    let master_token_holder = User {
-        owner: Keypair::new(),
-        token_account: user_token_acc.pubkey(),
-    };
-
-    test_metadata
-        .create(
-            context,
-            "Test".to_string(),
-            "TST".to_string(),
-            "uri".to_string(),
-            None,
-            10,
-            false,
-            &user_token_acc,
-            &test_pack_set.authority.pubkey(),
-        )
-        .await
-        .unwrap();
-
-    test_master_edition.create(context, Some(10)).await.unwrap();
-
-    (test_metadata, test_master_edition, master_token_holder)
-}
-
 #[tokio::test]
 async fn success_fixed_probability() {
     let mut context = nft_packs_program_test().start_with_context().await;
@@ -89,16 +56,20 @@ async fn success_fixed_probability() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -143,6 +114,7 @@ async fn success_fixed_probability() {
                 max_supply: card_max_supply,
                 weight: card_weight,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -162,7 +134,6 @@ async fn success_fixed_probability() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
     let new_mint = Keypair::new();
     let new_mint_token_acc = Keypair::new();
 
@@ -186,8 +157,6 @@ async fn success_fixed_probability() {
     // do wrap to update state
     context.warp_to_slot(5).unwrap();
 
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     test_pack_set
         .claim_pack(
             &mut context,
@@ -257,16 +226,20 @@ async fn success_max_supply_probability() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -310,6 +283,7 @@ async fn success_max_supply_probability() {
                 max_supply: card_max_supply,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -329,7 +303,6 @@ async fn success_max_supply_probability() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -418,6 +391,10 @@ async fn success_claim_two_same_cards() {
                 allowed_amount_to_redeem: 2,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -425,10 +402,10 @@ async fn success_claim_two_same_cards() {
 
     // Create first card master edition
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
     let edition_authority = Keypair::new();
@@ -473,6 +450,7 @@ async fn success_claim_two_same_cards() {
                 max_supply: card_max_supply,
                 weight: card_weight,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -492,8 +470,6 @@ async fn success_claim_two_same_cards() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let new_mint = Keypair::new();
     let new_mint_token_acc = Keypair::new();
 
@@ -519,8 +495,6 @@ async fn success_claim_two_same_cards() {
         .unwrap();
 
     context.warp_to_slot(5).unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     test_pack_set
         .request_card_for_redeem(
             &mut context,
@@ -610,6 +584,10 @@ async fn success_claim_decrement_redeem_cards() {
                 allowed_amount_to_redeem: 2,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -617,10 +595,10 @@ async fn success_claim_decrement_redeem_cards() {
 
     // Create first card master edition
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
     let edition_authority = Keypair::new();
@@ -665,6 +643,7 @@ async fn success_claim_decrement_redeem_cards() {
                 max_supply: card_max_supply,
                 weight: card_weight,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -684,8 +663,6 @@ async fn success_claim_decrement_redeem_cards() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let new_mint = Keypair::new();
     let new_mint_token_acc = Keypair::new();
 
@@ -711,7 +688,6 @@ async fn success_claim_decrement_redeem_cards() {
         .unwrap();
 
     context.warp_to_slot(5).unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
     let (proving_process_key, _) = find_proving_process_program_address(
         &metaplex_nft_packs::id(),
         &test_pack_set.keypair.pubkey(),
@@ -817,6 +793,10 @@ async fn success_claim_two_indexes() {
                 allowed_amount_to_redeem: 2,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -824,14 +804,14 @@ async fn success_claim_two_indexes() {
 
     // Create first card master edition
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     // Create second card master edition
     let (card_metadata1, card_master_edition1, card_master_token_holder1) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
     let edition_authority = Keypair::new();
@@ -876,6 +856,7 @@ async fn success_claim_two_indexes() {
                 max_supply: card_max_supply,
                 weight: card_weight,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -896,6 +877,7 @@ async fn success_claim_two_indexes() {
                 max_supply: card_max_supply,
                 weight: card_weight,
                 index: test_pack_card1.index,
+                tier: None,
             },
         )
         .await
@@ -915,8 +897,6 @@ async fn success_claim_two_indexes() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let new_mint = Keypair::new();
     let new_mint_token_acc = Keypair::new();
 
@@ -939,8 +919,6 @@ async fn success_claim_two_indexes() {
         .unwrap();
 
     context.warp_to_slot(5).unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     test_pack_set
         .request_card_for_redeem(
             &mut context,
@@ -1027,16 +1005,20 @@ async fn success_claim_after_redeem_end_date() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
     let edition_authority = Keypair::new();
@@ -1079,6 +1061,7 @@ async fn success_claim_after_redeem_end_date() {
                 max_supply: card_max_supply,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -1097,8 +1080,6 @@ async fn success_claim_after_redeem_end_date() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -1185,16 +1166,20 @@ async fn fail_wrong_user_wallet() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -1238,6 +1223,7 @@ async fn fail_wrong_user_wallet() {
                 max_supply: card_max_supply,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -1257,7 +1243,6 @@ async fn fail_wrong_user_wallet() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -1432,16 +1417,20 @@ async fn fail_claim_twice() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -1485,6 +1474,7 @@ async fn fail_claim_twice() {
                 max_supply: card_max_supply,
                 weight: 0,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -1504,8 +1494,6 @@ async fn fail_claim_twice() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
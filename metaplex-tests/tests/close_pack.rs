@@ -2,7 +2,7 @@ mod utils;
 
 use metaplex_nft_packs::{
     instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::{PackDistributionType, PackSetState},
+    state::{PackDistributionType, PackSetState, RandomnessSource},
 };
 use solana_program::system_instruction;
 use solana_program_test::*;
@@ -45,6 +45,10 @@ async fn setup() -> (
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -124,6 +128,7 @@ async fn setup() -> (
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
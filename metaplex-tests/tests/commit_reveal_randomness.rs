@@ -0,0 +1,640 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_proving_process_program_address, find_randomness_round_program_address,
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackDistributionType, ProvingProcess, RandomnessRound, RandomnessSource},
+};
+use metaplex_token_metadata::state::Metadata;
+use num_traits::FromPrimitive;
+use solana_program::{
+    borsh::try_from_slice_unchecked, hash::hashv, instruction::InstructionError,
+    program_pack::Pack, pubkey::Pubkey, system_instruction,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+const CARD_MAX_SUPPLY: u32 = 5;
+const ALLOWED_AMOUNT_TO_REDEEM: u32 = 3;
+const SECRET: [u8; 32] = [42; 32];
+const NEXT_SECRET: [u8; 32] = [43; 32];
+
+async fn setup(
+    card_max_supply: u32,
+) -> (
+    ProgramTestContext,
+    TestPackSet,
+    TestPackCard,
+    TestEditionMarker,
+    Keypair,
+) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let redeem_start_date = Some(clock.unix_timestamp as u64);
+    let redeem_end_date = Some(redeem_start_date.unwrap() + 100);
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: ALLOWED_AMOUNT_TO_REDEEM,
+                redeem_start_date,
+                redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::CommitReveal,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply: card_max_supply,
+                weight: 100,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    (
+        context,
+        test_pack_set,
+        test_pack_card,
+        voucher_edition,
+        edition_authority,
+    )
+}
+
+async fn commit_and_activate(context: &mut ProgramTestContext, test_pack_set: &TestPackSet) {
+    test_pack_set
+        .commit_randomness(context, hashv(&[&SECRET]).to_bytes())
+        .await
+        .unwrap();
+    test_pack_set.activate(context).await.unwrap();
+}
+
+async fn request_cards(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    voucher_edition: &TestEditionMarker,
+    edition_authority: &Keypair,
+    count: u32,
+) -> transport::Result<()> {
+    // Oracle account isn't used by packs with commit reveal randomness
+    test_pack_set
+        .request_cards_for_redeem(
+            context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &Pubkey::new_unique(),
+            1,
+            count,
+        )
+        .await
+}
+
+fn get_proving_process_key(
+    test_pack_set: &TestPackSet,
+    voucher_edition: &TestEditionMarker,
+    edition_authority: &Keypair,
+) -> Pubkey {
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+
+    proving_process_key
+}
+
+async fn get_proving_process(
+    context: &mut ProgramTestContext,
+    proving_process_key: &Pubkey,
+) -> ProvingProcess {
+    let proving_process_data = get_account(context, proving_process_key).await;
+    ProvingProcess::unpack_from_slice(&proving_process_data.data).unwrap()
+}
+
+async fn get_randomness_round(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    index: u32,
+) -> RandomnessRound {
+    let (randomness_round_key, _) = find_randomness_round_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        index,
+    );
+    let randomness_round_data = get_account(context, &randomness_round_key).await;
+    RandomnessRound::unpack_from_slice(&randomness_round_data.data).unwrap()
+}
+
+async fn reveal(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    index: u32,
+    secret: [u8; 32],
+) {
+    test_pack_set
+        .reveal_randomness(context, index, secret)
+        .await
+        .unwrap();
+
+    // Hash of reveal slot gets to SlotHashes once the slot is over
+    let randomness_round = get_randomness_round(context, test_pack_set, index).await;
+    context
+        .warp_to_slot(randomness_round.reveal_slot + 2)
+        .unwrap();
+}
+
+async fn claim(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    test_pack_card: &TestPackCard,
+    voucher_edition: &TestEditionMarker,
+    edition_authority: &Keypair,
+) -> transport::Result<()> {
+    let pack_card = test_pack_card.get_data(context).await;
+    let metadata_account = get_account(context, &pack_card.metadata).await;
+    let metadata: Metadata = try_from_slice_unchecked(&metadata_account.data).unwrap();
+
+    // Oracle account isn't used by packs with commit reveal randomness
+    test_pack_set
+        .claim_pack(
+            context,
+            edition_authority,
+            &voucher_edition.mint.pubkey(),
+            &test_pack_card.token_account.pubkey(),
+            &pack_card.master,
+            &Keypair::new(),
+            &Keypair::new(),
+            edition_authority,
+            &pack_card.metadata,
+            &metadata.mint,
+            &Pubkey::new_unique(),
+            test_pack_card.index,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_pack_set, test_pack_card, voucher_edition, edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        2,
+    )
+    .await
+    .unwrap();
+
+    let proving_process_key =
+        get_proving_process_key(&test_pack_set, &voucher_edition, &edition_authority);
+    let proving_process = get_proving_process(&mut context, &proving_process_key).await;
+    assert_eq!(proving_process.pending_draws, 2);
+    assert_eq!(proving_process.pending_round, 0);
+    assert!(proving_process.cards_to_redeem.is_empty());
+
+    // Editions are reserved on request
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.total_editions, (CARD_MAX_SUPPLY - 2) as u64);
+
+    reveal(&mut context, &test_pack_set, 0, SECRET).await;
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.randomness_commitment, None);
+    assert_eq!(pack_set.randomness_rounds, 1);
+
+    let randomness_round = get_randomness_round(&mut context, &test_pack_set, 0).await;
+    assert_eq!(randomness_round.commitment, hashv(&[&SECRET]).to_bytes());
+    assert_eq!(randomness_round.secret, Some(SECRET));
+    assert_eq!(randomness_round.seed, None);
+
+    test_pack_set
+        .resolve_draws(
+            &mut context,
+            &proving_process_key,
+            &edition_authority.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+
+    let randomness_round = get_randomness_round(&mut context, &test_pack_set, 0).await;
+    assert!(randomness_round.seed.is_some());
+    assert_ne!(randomness_round.seed, Some(SECRET));
+
+    let proving_process = get_proving_process(&mut context, &proving_process_key).await;
+    assert_eq!(proving_process.pending_draws, 0);
+    assert_eq!(proving_process.draw_counter, 2);
+    assert_eq!(
+        proving_process.cards_to_redeem.get(&test_pack_card.index),
+        Some(&2)
+    );
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.total_editions, (CARD_MAX_SUPPLY - 2) as u64);
+}
+
+#[tokio::test]
+async fn success_claim() {
+    let (mut context, test_pack_set, test_pack_card, voucher_edition, edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await
+    .unwrap();
+
+    reveal(&mut context, &test_pack_set, 0, SECRET).await;
+
+    let proving_process_key =
+        get_proving_process_key(&test_pack_set, &voucher_edition, &edition_authority);
+    test_pack_set
+        .resolve_draws(
+            &mut context,
+            &proving_process_key,
+            &edition_authority.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+
+    claim(
+        &mut context,
+        &test_pack_set,
+        &test_pack_card,
+        &voucher_edition,
+        &edition_authority,
+    )
+    .await
+    .unwrap();
+
+    let proving_process = get_proving_process(&mut context, &proving_process_key).await;
+    assert_eq!(proving_process.cards_redeemed, 1);
+    assert_eq!(
+        proving_process.cards_to_redeem.get(&test_pack_card.index),
+        Some(&0)
+    );
+}
+
+#[tokio::test]
+async fn success_next_round() {
+    let (mut context, test_pack_set, test_pack_card, voucher_edition, edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    let proving_process_key =
+        get_proving_process_key(&test_pack_set, &voucher_edition, &edition_authority);
+
+    request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await
+    .unwrap();
+
+    reveal(&mut context, &test_pack_set, 0, SECRET).await;
+
+    test_pack_set
+        .commit_randomness(&mut context, hashv(&[&NEXT_SECRET]).to_bytes())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(
+        pack_set.randomness_commitment,
+        Some(hashv(&[&NEXT_SECRET]).to_bytes())
+    );
+    assert_eq!(pack_set.randomness_rounds, 2);
+
+    // Draws of the previous round are resolved with its secret after the next round is open
+    test_pack_set
+        .resolve_draws(
+            &mut context,
+            &proving_process_key,
+            &edition_authority.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+
+    request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await
+    .unwrap();
+
+    let proving_process = get_proving_process(&mut context, &proving_process_key).await;
+    assert_eq!(proving_process.pending_draws, 1);
+    assert_eq!(proving_process.pending_round, 1);
+
+    reveal(&mut context, &test_pack_set, 1, NEXT_SECRET).await;
+
+    test_pack_set
+        .resolve_draws(
+            &mut context,
+            &proving_process_key,
+            &edition_authority.pubkey(),
+            1,
+        )
+        .await
+        .unwrap();
+
+    let proving_process = get_proving_process(&mut context, &proving_process_key).await;
+    assert_eq!(proving_process.pending_draws, 0);
+    assert_eq!(proving_process.draw_counter, 2);
+    assert_eq!(
+        proving_process.cards_to_redeem.get(&test_pack_card.index),
+        Some(&2)
+    );
+}
+
+#[tokio::test]
+async fn fail_activate_without_commitment() {
+    let (mut context, test_pack_set, _test_pack_card, _voucher_edition, _edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    let result = test_pack_set.activate(&mut context).await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::RandomnessNotCommitted,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_commit_while_round_is_open() {
+    let (mut context, test_pack_set, _test_pack_card, _voucher_edition, _edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    let result = test_pack_set
+        .commit_randomness(&mut context, hashv(&[&NEXT_SECRET]).to_bytes())
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::RandomnessAlreadyCommitted,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_wrong_secret() {
+    let (mut context, test_pack_set, _test_pack_card, _voucher_edition, _edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    let result = test_pack_set
+        .reveal_randomness(&mut context, 0, [1; 32])
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRandomnessSecret, 0);
+}
+
+#[tokio::test]
+async fn fail_reveal_twice() {
+    let (mut context, test_pack_set, _test_pack_card, _voucher_edition, _edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    reveal(&mut context, &test_pack_set, 0, SECRET).await;
+
+    let result = test_pack_set
+        .reveal_randomness(&mut context, 0, SECRET)
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::RandomnessAlreadyRevealed,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_request_before_next_commitment() {
+    let (mut context, test_pack_set, _test_pack_card, voucher_edition, edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    reveal(&mut context, &test_pack_set, 0, SECRET).await;
+
+    let result = request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::RandomnessNotCommitted,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_request_with_draws_of_previous_round() {
+    let (mut context, test_pack_set, _test_pack_card, voucher_edition, edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await
+    .unwrap();
+
+    reveal(&mut context, &test_pack_set, 0, SECRET).await;
+
+    test_pack_set
+        .commit_randomness(&mut context, hashv(&[&NEXT_SECRET]).to_bytes())
+        .await
+        .unwrap();
+
+    let result = request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::PendingDrawsNotResolved,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_request_without_editions() {
+    let (mut context, test_pack_set, _test_pack_card, voucher_edition, edition_authority) =
+        setup(ALLOWED_AMOUNT_TO_REDEEM - 1).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    let result = request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        ALLOWED_AMOUNT_TO_REDEEM,
+    )
+    .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::MissingEditionsInPack, 0);
+}
+
+#[tokio::test]
+async fn fail_resolve_before_reveal() {
+    let (mut context, test_pack_set, _test_pack_card, voucher_edition, edition_authority) =
+        setup(CARD_MAX_SUPPLY).await;
+
+    commit_and_activate(&mut context, &test_pack_set).await;
+
+    request_cards(
+        &mut context,
+        &test_pack_set,
+        &voucher_edition,
+        &edition_authority,
+        1,
+    )
+    .await
+    .unwrap();
+
+    let proving_process_key =
+        get_proving_process_key(&test_pack_set, &voucher_edition, &edition_authority);
+
+    let result = test_pack_set
+        .resolve_draws(
+            &mut context,
+            &proving_process_key,
+            &edition_authority.pubkey(),
+            0,
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::RandomnessNotRevealed, 0);
+}
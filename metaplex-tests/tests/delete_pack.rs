@@ -3,7 +3,7 @@ mod utils;
 use metaplex_nft_packs::{
     error::NFTPacksError,
     instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::PackDistributionType,
+    state::{PackDistributionType, RandomnessSource},
 };
 use num_traits::FromPrimitive;
 use solana_program::{instruction::InstructionError, system_instruction};
@@ -52,6 +52,10 @@ async fn setup() -> (
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -158,6 +162,7 @@ async fn fail_invalid_state() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
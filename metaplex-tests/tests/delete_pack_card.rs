@@ -1,12 +1,15 @@
 mod utils;
 
+use borsh::BorshSerialize;
 use metaplex_nft_packs::{
     error::NFTPacksError,
-    instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::PackDistributionType,
+    instruction::{
+        self, AddCardToPackArgs, DeletePackCardArgs, InitPackSetArgs, NFTPacksInstruction,
+    },
+    state::{PackConfig, PackDistributionType, RandomnessSource},
 };
 use num_traits::FromPrimitive;
-use solana_program::{instruction::InstructionError, system_instruction};
+use solana_program::{instruction::InstructionError, program_pack::Pack, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
     signature::Keypair,
@@ -53,6 +56,10 @@ async fn setup() -> (
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -100,6 +107,7 @@ async fn setup() -> (
                 max_supply: 0,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -115,6 +123,59 @@ async fn setup() -> (
     )
 }
 
+async fn add_card(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    index: u32,
+    weight: u16,
+) -> (TestPackCard, TestMasterEditionV2) {
+    let test_metadata = TestMetadata::new();
+    let test_master_edition = TestMasterEditionV2::new(&test_metadata);
+
+    let user_token_acc = Keypair::new();
+    let user = User {
+        owner: Keypair::new(),
+        token_account: user_token_acc.pubkey(),
+    };
+
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            &user_token_acc,
+            &test_pack_set.authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    test_master_edition.create(context, None).await.unwrap();
+
+    let test_pack_card = TestPackCard::new(test_pack_set, index);
+    test_pack_set
+        .add_card(
+            context,
+            &test_pack_card,
+            &test_master_edition,
+            &test_metadata,
+            &user,
+            AddCardToPackArgs {
+                max_supply: 0,
+                weight,
+                index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    (test_pack_card, test_master_edition)
+}
+
 #[tokio::test]
 async fn success() {
     let (mut context, test_pack_set, test_pack_card, test_metadata, _test_master_edition, user) =
@@ -275,3 +336,335 @@ async fn success_delete_before_activated_state() {
     let pack_set = test_pack_set.get_data(&mut context).await;
     assert_eq!(pack_set.pack_cards, 0);
 }
+
+#[tokio::test]
+async fn success_delete_not_last_card() {
+    let (mut context, test_pack_set, test_pack_card, test_metadata, _test_master_edition, user) =
+        setup().await;
+
+    let (_test_pack_card2, _test_master_edition2) =
+        add_card(&mut context, &test_pack_set, 2, 200).await;
+    let (test_pack_card3, test_master_edition3) =
+        add_card(&mut context, &test_pack_set, 3, 300).await;
+
+    let new_token_owner_acc = Keypair::new();
+    create_token_account(
+        &mut context,
+        &new_token_owner_acc,
+        &test_metadata.mint.pubkey(),
+        &test_pack_set.authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    test_pack_set
+        .delete_card(
+            &mut context,
+            &test_pack_card,
+            &user.pubkey(),
+            &new_token_owner_acc.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_cards, 2);
+    assert_eq!(pack_set.total_weight, 500);
+
+    // The last card is moved to index of deleted one
+    let pack_card = test_pack_card.get_data(&mut context).await;
+    assert_eq!(pack_card.master, test_master_edition3.pubkey);
+    assert_eq!(pack_card.weight, 300);
+    assert!(context
+        .banks_client
+        .get_account(test_pack_card3.pubkey)
+        .await
+        .unwrap()
+        .is_none());
+
+    let new_token_owner = get_account(&mut context, &new_token_owner_acc.pubkey()).await;
+    let new_token_owner = spl_token::state::Account::unpack(&new_token_owner.data).unwrap();
+    assert_eq!(new_token_owner.amount, 1);
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(pack_config.cards(), 2);
+    assert_eq!(pack_config.get_card(1).unwrap(), (1, 300, 0));
+    assert_eq!(pack_config.get_card(2).unwrap(), (2, 200, 0));
+    assert!(pack_config.get_card(3).is_err());
+}
+
+/// Fixed pack with two cards of supply 5 and one voucher
+async fn setup_fixed_pack() -> (
+    ProgramTestContext,
+    TestPackSet,
+    Vec<(TestPackCard, TestMetadata)>,
+    TestEditionMarker,
+    Keypair,
+    TestRandomnessOracle,
+) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name: [7; 32],
+                uri: String::from("some link to storage"),
+                description: String::from("Pack description"),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date: Some(clock.unix_timestamp as u64),
+                redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let mut cards = Vec::new();
+    for index in 1..=2 {
+        let (card_metadata, card_master_edition, card_master_token_holder) =
+            create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+        let test_pack_card = TestPackCard::new(&test_pack_set, index);
+        test_pack_set
+            .add_card(
+                &mut context,
+                &test_pack_card,
+                &card_master_edition,
+                &card_metadata,
+                &card_master_token_holder,
+                AddCardToPackArgs {
+                    max_supply: 5,
+                    weight: 100,
+                    index,
+                    tier: None,
+                },
+            )
+            .await
+            .unwrap();
+        cards.push((test_pack_card, card_metadata));
+    }
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    let mut test_randomness_oracle = TestRandomnessOracle::new();
+    test_randomness_oracle.init(&mut context).await.unwrap();
+    test_randomness_oracle.update(&mut context).await.unwrap();
+
+    (
+        context,
+        test_pack_set,
+        cards,
+        voucher_edition,
+        edition_authority,
+        test_randomness_oracle,
+    )
+}
+
+#[tokio::test]
+async fn success_without_index() {
+    let (mut context, test_pack_set, test_pack_card, test_metadata, _test_master_edition, user) =
+        setup().await;
+
+    let new_token_owner_acc = Keypair::new();
+    create_token_account(
+        &mut context,
+        &new_token_owner_acc,
+        &test_metadata.mint.pubkey(),
+        &test_pack_set.authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    test_pack_set.close(&mut context).await.unwrap();
+
+    // instruction data of clients built before cards could be deleted by index
+    let mut data = NFTPacksInstruction::DeletePackCard(DeletePackCardArgs::default())
+        .try_to_vec()
+        .unwrap();
+    data.truncate(1);
+
+    let mut delete_card_ix = instruction::delete_pack_card(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &Some(test_pack_set.pack_config.pubkey()),
+        &test_pack_set.authority.pubkey(),
+        &user.pubkey(),
+        &new_token_owner_acc.pubkey(),
+        &test_pack_card.token_account.pubkey(),
+        test_pack_card.index,
+        DeletePackCardArgs::default(),
+    );
+    delete_card_ix.data = data;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[delete_card_ix],
+        Some(&context.payer.pubkey()),
+        &[&test_pack_set.authority, &context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_cards, 0);
+    assert!(context
+        .banks_client
+        .get_account(test_pack_card.pubkey)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn fail_move_card_of_closed_unlimited_pack() {
+    let (mut context, test_pack_set, test_pack_card, _test_metadata, _test_master_edition, user) =
+        setup().await;
+
+    add_card(&mut context, &test_pack_set, 2, 200).await;
+
+    test_pack_set.close(&mut context).await.unwrap();
+
+    // claims of unlimited pack can't be counted, so draws of moved card could be claimed as another card
+    let result = test_pack_set
+        .delete_card(
+            &mut context,
+            &test_pack_card,
+            &user.pubkey(),
+            &Keypair::new().pubkey(),
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::CardCantBeMoved, 0);
+}
+
+#[tokio::test]
+async fn success_delete_card_with_supply_before_activation() {
+    let (mut context, test_pack_set, cards, _voucher_edition, _edition_authority, _oracle) =
+        setup_fixed_pack().await;
+    let (test_pack_card, test_metadata) = &cards[0];
+    let payer_pubkey = context.payer.pubkey();
+
+    let new_token_owner_acc = Keypair::new();
+    create_token_account(
+        &mut context,
+        &new_token_owner_acc,
+        &test_metadata.mint.pubkey(),
+        &test_pack_set.authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    test_pack_set
+        .delete_card(
+            &mut context,
+            test_pack_card,
+            &payer_pubkey,
+            &new_token_owner_acc.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_cards, 1);
+    assert_eq!(pack_set.total_editions, 5);
+    assert_eq!(pack_set.total_weight, 100);
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(pack_config.get_card(1).unwrap(), (1, 100, 5));
+}
+
+#[tokio::test]
+async fn fail_delete_card_with_unclaimed_draws() {
+    let (mut context, test_pack_set, cards, voucher_edition, edition_authority, oracle) =
+        setup_fixed_pack().await;
+    let (test_pack_card, _test_metadata) = &cards[0];
+    let payer_pubkey = context.payer.pubkey();
+
+    test_pack_set.activate(&mut context).await.unwrap();
+
+    test_pack_set
+        .request_card_for_redeem(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &oracle.keypair.pubkey(),
+            1,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.close(&mut context).await.unwrap();
+
+    // drawn card is either deleted or moved to index of deleted card
+    let result = test_pack_set
+        .delete_card(
+            &mut context,
+            test_pack_card,
+            &payer_pubkey,
+            &Keypair::new().pubkey(),
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::UnclaimedCardDraws, 0);
+}
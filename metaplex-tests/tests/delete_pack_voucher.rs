@@ -1,12 +1,18 @@
 mod utils;
 
+use borsh::BorshSerialize;
 use metaplex_nft_packs::{
     error::NFTPacksError,
-    instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::PackDistributionType,
+    find_voucher_lookup_program_address,
+    instruction::{AddCardToPackArgs, DeletePackVoucherArgs, InitPackSetArgs, NFTPacksInstruction},
+    state::{PackDistributionType, RandomnessSource, VoucherLookup},
 };
 use num_traits::FromPrimitive;
-use solana_program::{instruction::InstructionError, system_instruction};
+use solana_program::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    system_instruction,
+};
 use solana_program_test::*;
 use solana_sdk::{
     signature::Keypair,
@@ -53,6 +59,10 @@ async fn setup() -> (
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -168,6 +178,55 @@ async fn success() {
     assert_eq!(pack_set.pack_vouchers, 0);
 }
 
+#[tokio::test]
+async fn success_without_index() {
+    let (mut context, test_pack_set, test_pack_voucher, _test_metadata, test_master_edition, user) =
+        setup().await;
+
+    test_pack_set.close(&mut context).await.unwrap();
+
+    let (voucher_lookup, _) = find_voucher_lookup_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &test_master_edition.pubkey,
+    );
+    assert!(!is_empty_account(&mut context, &voucher_lookup).await);
+
+    // instruction data of clients built before vouchers could be deleted by index
+    let mut data = NFTPacksInstruction::DeletePackVoucher(DeletePackVoucherArgs::default())
+        .try_to_vec()
+        .unwrap();
+    data.truncate(1);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[Instruction {
+            program_id: metaplex_nft_packs::id(),
+            accounts: vec![
+                AccountMeta::new(test_pack_set.keypair.pubkey(), false),
+                AccountMeta::new(test_pack_voucher.pubkey, false),
+                AccountMeta::new_readonly(test_pack_set.authority.pubkey(), true),
+                AccountMeta::new(user.pubkey(), false),
+                AccountMeta::new(voucher_lookup, false),
+            ],
+            data,
+        }],
+        Some(&context.payer.pubkey()),
+        &[&test_pack_set.authority, &context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_vouchers, 0);
+    assert!(context
+        .banks_client
+        .get_account(test_pack_voucher.pubkey)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(is_empty_account(&mut context, &voucher_lookup).await);
+}
+
 #[tokio::test]
 async fn fail_invalid_state() {
     let (
@@ -224,6 +283,7 @@ async fn fail_invalid_state() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -239,3 +299,126 @@ async fn fail_invalid_state() {
 
     assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
 }
+
+#[tokio::test]
+async fn success_delete_not_last_voucher() {
+    let (mut context, test_pack_set, test_pack_voucher, test_metadata, test_master_edition, user) =
+        setup().await;
+
+    let test_metadata2 = TestMetadata::new();
+    let test_master_edition2 = TestMasterEditionV2::new(&test_metadata2);
+    let user_token_acc2 = Keypair::new();
+    let user2 = User {
+        owner: Keypair::new(),
+        token_account: user_token_acc2.pubkey(),
+    };
+
+    test_metadata2
+        .create(
+            &mut context,
+            "Test2".to_string(),
+            "TST2".to_string(),
+            "uri2".to_string(),
+            None,
+            10,
+            false,
+            &user_token_acc2,
+            &test_pack_set.authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    test_master_edition2
+        .create(&mut context, Some(10))
+        .await
+        .unwrap();
+
+    let edition_authority = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    TestEditionMarker::new(&test_metadata2, &test_master_edition2, 1)
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &user_token_acc2.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher2 = TestPackVoucher::new(&test_pack_set, 2);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher2,
+            &test_master_edition2,
+            &test_metadata2,
+            &user2,
+        )
+        .await
+        .unwrap();
+
+    let new_token_owner_acc = Keypair::new();
+    create_token_account(
+        &mut context,
+        &new_token_owner_acc,
+        &test_metadata.mint.pubkey(),
+        &test_pack_set.authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    test_pack_set.close(&mut context).await.unwrap();
+
+    test_pack_set
+        .delete_voucher(&mut context, &test_pack_voucher, &user.pubkey())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_vouchers, 1);
+
+    // The last voucher is moved to index of deleted one
+    let pack_voucher = test_pack_voucher.get_data(&mut context).await;
+    assert_eq!(pack_voucher.master, test_master_edition2.pubkey);
+    assert!(context
+        .banks_client
+        .get_account(test_pack_voucher2.pubkey)
+        .await
+        .unwrap()
+        .is_none());
+
+    let (voucher_lookup, _) = find_voucher_lookup_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &test_master_edition.pubkey,
+    );
+    assert!(context
+        .banks_client
+        .get_account(voucher_lookup)
+        .await
+        .unwrap()
+        .is_none());
+
+    let (voucher_lookup2, _) = find_voucher_lookup_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &test_master_edition2.pubkey,
+    );
+    let voucher_lookup2 = get_account(&mut context, &voucher_lookup2).await;
+    let voucher_lookup2 = VoucherLookup::unpack(&voucher_lookup2.data).unwrap();
+    assert_eq!(voucher_lookup2.master, test_master_edition2.pubkey);
+    assert_eq!(voucher_lookup2.index, 1);
+}
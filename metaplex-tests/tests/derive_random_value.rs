@@ -0,0 +1,44 @@
+use metaplex_nft_packs::utils::derive_random_value;
+use solana_program::pubkey::Pubkey;
+
+#[test]
+fn known_value() {
+    let oracle_value: Vec<u8> = (0..32).collect();
+    let pack_set = Pubkey::new_from_array([1; 32]);
+    let voucher_mint = Pubkey::new_from_array([2; 32]);
+
+    assert_eq!(
+        derive_random_value(&oracle_value, 123456, &pack_set, &voucher_mint, 3),
+        4545981088918665847
+    );
+    assert_eq!(
+        derive_random_value(&oracle_value, 123456, &pack_set, &voucher_mint, 4),
+        4925991316009767022
+    );
+}
+
+#[test]
+fn depends_on_every_input() {
+    let oracle_value = [7; 32];
+    let pack_set = Pubkey::new_unique();
+    let voucher_mint = Pubkey::new_unique();
+
+    let value = derive_random_value(&oracle_value, 10, &pack_set, &voucher_mint, 0);
+
+    assert_ne!(
+        value,
+        derive_random_value(&[8; 32], 10, &pack_set, &voucher_mint, 0)
+    );
+    assert_ne!(
+        value,
+        derive_random_value(&oracle_value, 11, &pack_set, &voucher_mint, 0)
+    );
+    assert_ne!(
+        value,
+        derive_random_value(&oracle_value, 10, &voucher_mint, &pack_set, 0)
+    );
+    assert_ne!(
+        value,
+        derive_random_value(&oracle_value, 10, &pack_set, &voucher_mint, 1)
+    );
+}
@@ -1,13 +1,19 @@
 mod utils;
 
+use borsh::{BorshDeserialize, BorshSerialize};
 use metaplex_nft_packs::{
-    error::NFTPacksError, instruction::InitPackSetArgs, state::PackDistributionType,
+    error::NFTPacksError,
+    instruction::{AddCardToPackArgs, EditPackSetArgs, InitPackSetArgs, NFTPacksInstruction},
+    state::{PackDistributionType, RandomnessSource, MAX_CARDS_TO_REDEEM},
 };
 use num_traits::FromPrimitive;
-use solana_program::instruction::InstructionError;
+use solana_program::{instruction::InstructionError, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
-    signature::Keypair, signer::Signer, transaction::TransactionError, transport::TransportError,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
 };
 use utils::*;
 
@@ -49,6 +55,10 @@ async fn setup(
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -92,6 +102,130 @@ async fn setup(
     )
 }
 
+// Add card and voucher to activate pack and then deactivate it
+async fn activate_and_deactivate(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    test_metadata: &TestMetadata,
+    test_master_edition: &TestMasterEditionV2,
+    user: &User,
+) {
+    let test_pack_card = TestPackCard::new(test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            context,
+            &test_pack_card,
+            test_master_edition,
+            test_metadata,
+            user,
+            AddCardToPackArgs {
+                max_supply: 5,
+                weight: 100,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let voucher_metadata = TestMetadata::new();
+    let voucher_master_edition = TestMasterEditionV2::new(&voucher_metadata);
+
+    let voucher_token_acc = Keypair::new();
+    let voucher_master_token_holder = User {
+        owner: Keypair::new(),
+        token_account: voucher_token_acc.pubkey(),
+    };
+
+    voucher_metadata
+        .create(
+            context,
+            "Voucher".to_string(),
+            "VCH".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            &voucher_token_acc,
+            &test_pack_set.authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    voucher_master_edition
+        .create(context, Some(10))
+        .await
+        .unwrap();
+
+    let edition_authority = Keypair::new();
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1)
+        .create(
+            context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_token_acc.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.activate(context).await.unwrap();
+    test_pack_set.deactivate(context).await.unwrap();
+}
+
+#[test]
+fn decode_args_without_redeem_schedule() {
+    let args = EditPackSetArgs {
+        name: Some([8; 32]),
+        mutable: Some(false),
+        ..Default::default()
+    };
+    let mut data = NFTPacksInstruction::EditPack(args.clone())
+        .try_to_vec()
+        .unwrap();
+
+    // Clients built before redeem schedule could be edited don't send its fields at all
+    assert_eq!(data.split_off(data.len() - 3), vec![0, 0, 0]);
+    match NFTPacksInstruction::try_from_slice(&data).unwrap() {
+        NFTPacksInstruction::EditPack(decoded) => assert_eq!(decoded, args),
+        _ => panic!("wrong instruction"),
+    }
+
+    // Appended fields are still decoded when they're passed
+    let args = EditPackSetArgs {
+        allowed_amount_to_redeem: Some(2),
+        redeem_end_date: Some(100),
+        ..args
+    };
+    let data = args.try_to_vec().unwrap();
+    assert_eq!(EditPackSetArgs::try_from_slice(&data).unwrap(), args);
+}
+
 #[tokio::test]
 async fn success() {
     let (mut context, test_pack_set, _test_metadata, _test_master_edition, _user) =
@@ -100,7 +234,13 @@ async fn success() {
     assert_eq!(test_pack_set.get_data(&mut context).await.name, [7; 32]);
 
     test_pack_set
-        .edit(&mut context, None, Some([8; 32]), None, None)
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                name: Some([8; 32]),
+                ..Default::default()
+            },
+        )
         .await
         .unwrap();
 
@@ -113,8 +253,215 @@ async fn fail_immutable() {
         setup(false).await;
 
     let result = test_pack_set
-        .edit(&mut context, None, Some([8; 32]), None, None)
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                name: Some([8; 32]),
+                ..Default::default()
+            },
+        )
         .await;
 
     assert_custom_error!(result.unwrap_err(), NFTPacksError::ImmutablePackSet, 0);
 }
+
+#[tokio::test]
+async fn success_redeem_schedule() {
+    let (mut context, test_pack_set, _test_metadata, _test_master_edition, _user) =
+        setup(true).await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+    let redeem_start_date = clock.unix_timestamp as u64 + 100;
+    let redeem_end_date = redeem_start_date + 100;
+
+    test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                allowed_amount_to_redeem: Some(5),
+                redeem_start_date: Some(redeem_start_date),
+                redeem_end_date: Some(redeem_end_date),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.allowed_amount_to_redeem, 5);
+    assert_eq!(pack_set.redeem_start_date, redeem_start_date);
+    assert_eq!(pack_set.redeem_end_date, Some(redeem_end_date));
+}
+
+#[tokio::test]
+async fn fail_redeem_start_date_in_past() {
+    let (mut context, test_pack_set, _test_metadata, _test_master_edition, _user) =
+        setup(true).await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let result = test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                redeem_start_date: Some(clock.unix_timestamp as u64 - 10),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRedeemDate, 0);
+}
+
+#[tokio::test]
+async fn fail_redeem_end_date_before_start_date() {
+    let (mut context, test_pack_set, _test_metadata, _test_master_edition, _user) =
+        setup(true).await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+    let redeem_start_date = clock.unix_timestamp as u64 + 100;
+
+    let result = test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                redeem_start_date: Some(redeem_start_date),
+                redeem_end_date: Some(redeem_start_date - 50),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRedeemDate, 0);
+}
+
+#[tokio::test]
+async fn success_extend_redeem_end_date_deactivated() {
+    let (mut context, test_pack_set, test_metadata, test_master_edition, user) = setup(true).await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+    let redeem_end_date = clock.unix_timestamp as u64 + 1000;
+
+    test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                redeem_end_date: Some(redeem_end_date),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    activate_and_deactivate(
+        &mut context,
+        &test_pack_set,
+        &test_metadata,
+        &test_master_edition,
+        &user,
+    )
+    .await;
+
+    test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                redeem_end_date: Some(redeem_end_date + 1000),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.redeem_end_date, Some(redeem_end_date + 1000));
+}
+
+#[tokio::test]
+async fn fail_shorten_redeem_end_date_deactivated() {
+    let (mut context, test_pack_set, test_metadata, test_master_edition, user) = setup(true).await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+    let redeem_end_date = clock.unix_timestamp as u64 + 1000;
+
+    test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                redeem_end_date: Some(redeem_end_date),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    activate_and_deactivate(
+        &mut context,
+        &test_pack_set,
+        &test_metadata,
+        &test_master_edition,
+        &user,
+    )
+    .await;
+
+    let result = test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                redeem_end_date: Some(redeem_end_date - 500),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRedeemDate, 0);
+}
+
+#[tokio::test]
+async fn fail_allowed_amount_to_redeem_over_max() {
+    let (mut context, test_pack_set, _test_metadata, _test_master_edition, _user) =
+        setup(true).await;
+
+    // Proving process can't hold more drawn cards
+    let result = test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                allowed_amount_to_redeem: Some(MAX_CARDS_TO_REDEEM + 1),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::WrongAllowedAmountToRedeem,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_allowed_amount_to_redeem_deactivated() {
+    let (mut context, test_pack_set, test_metadata, test_master_edition, user) = setup(true).await;
+
+    activate_and_deactivate(
+        &mut context,
+        &test_pack_set,
+        &test_metadata,
+        &test_master_edition,
+        &user,
+    )
+    .await;
+
+    let result = test_pack_set
+        .edit(
+            &mut context,
+            EditPackSetArgs {
+                allowed_amount_to_redeem: Some(5),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
@@ -0,0 +1,408 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    instruction::{self, AddCardToPackArgs, EditPackCardArgs, InitPackSetArgs},
+    state::{PackConfig, PackDistributionType, RandomnessSource},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const CARD_MAX_SUPPLY: u32 = 5;
+const CARD_WEIGHT: u16 = 100;
+
+struct TestPack {
+    context: ProgramTestContext,
+    test_pack_set: TestPackSet,
+    test_pack_card: TestPackCard,
+    card_master_edition: TestMasterEditionV2,
+    voucher_edition: TestEditionMarker,
+    edition_authority: Keypair,
+}
+
+async fn setup(mutable: bool) -> TestPack {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name: [7; 32],
+                uri: String::from("some link to storage"),
+                description: String::from("Pack description"),
+                mutable,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date: Some(clock.unix_timestamp as u64),
+                redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply: CARD_MAX_SUPPLY,
+                weight: CARD_WEIGHT,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    TestPack {
+        context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        voucher_edition,
+        edition_authority,
+    }
+}
+
+// Activate pack, draw one card without claiming it and deactivate pack
+async fn draw_card(test_pack: &mut TestPack) {
+    let context = &mut test_pack.context;
+    let test_pack_set = &test_pack.test_pack_set;
+
+    test_pack_set.activate(context).await.unwrap();
+
+    let mut test_randomness_oracle = TestRandomnessOracle::new();
+    test_randomness_oracle.init(context).await.unwrap();
+    test_randomness_oracle.update(context).await.unwrap();
+
+    test_pack_set
+        .request_card_for_redeem(
+            context,
+            &test_pack_set.store,
+            &test_pack.voucher_edition.new_edition_pubkey,
+            &test_pack.voucher_edition.mint.pubkey(),
+            &test_pack.edition_authority,
+            &Some(test_pack.voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.deactivate(context).await.unwrap();
+}
+
+#[tokio::test]
+async fn success() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = setup(true).await;
+
+    test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            Some(8),
+            Some(50),
+        )
+        .await
+        .unwrap();
+
+    let pack_card = test_pack_card.get_data(&mut context).await;
+    assert_eq!(pack_card.max_supply, 8);
+    assert_eq!(pack_card.weight, 50);
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.total_editions, 8);
+    assert_eq!(pack_set.total_weight, 50);
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(
+        pack_config.get_card(test_pack_card.index).unwrap(),
+        (test_pack_card.index, 50, 8)
+    );
+}
+
+#[tokio::test]
+async fn success_deactivated_keeps_drawn_cards() {
+    let mut test_pack = setup(true).await;
+    draw_card(&mut test_pack).await;
+
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = test_pack;
+
+    test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            Some(3),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(test_pack_card.get_data(&mut context).await.max_supply, 3);
+
+    // one of new max supply is taken by card which is drawn but not claimed
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.total_editions, 2);
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(
+        pack_config.get_card(test_pack_card.index).unwrap(),
+        (test_pack_card.index, CARD_WEIGHT as u32, 2)
+    );
+}
+
+#[tokio::test]
+async fn fail_max_supply_less_than_drawn() {
+    let mut test_pack = setup(true).await;
+    draw_card(&mut test_pack).await;
+
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = test_pack;
+
+    let result = test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            Some(0),
+            None,
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongMaxSupply, 0);
+}
+
+#[tokio::test]
+async fn fail_more_than_master_edition_supply() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = setup(true).await;
+
+    let result = test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            Some(20),
+            None,
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongMaxSupply, 0);
+}
+
+#[tokio::test]
+async fn fail_same_value() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = setup(true).await;
+
+    let result = test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            None,
+            Some(CARD_WEIGHT),
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::CantSetTheSameValue, 0);
+}
+
+#[tokio::test]
+async fn fail_wrong_store() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = setup(true).await;
+
+    // Token metadata program is read from the store of pack set
+    let other_store_key = create_store(&mut context, &Keypair::new(), true)
+        .await
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction::edit_pack_card(
+            &metaplex_nft_packs::id(),
+            &test_pack_set.keypair.pubkey(),
+            &test_pack_set.pack_config.pubkey(),
+            &test_pack_set.authority.pubkey(),
+            &card_master_edition.pubkey,
+            &other_store_key,
+            EditPackCardArgs {
+                index: test_pack_card.index,
+                max_supply: None,
+                weight: Some(CARD_WEIGHT + 1),
+            },
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &test_pack_set.authority],
+        context.last_blockhash,
+    );
+    let result = context.banks_client.process_transaction(tx).await;
+
+    assert_transport_error!(
+        result.unwrap_err(),
+        TransportError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+}
+
+#[tokio::test]
+async fn fail_activated() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = setup(true).await;
+
+    test_pack_set.activate(&mut context).await.unwrap();
+
+    let result = test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            Some(8),
+            None,
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
+
+#[tokio::test]
+async fn fail_immutable_deactivated() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        card_master_edition,
+        ..
+    } = setup(false).await;
+
+    test_pack_set.activate(&mut context).await.unwrap();
+    test_pack_set.deactivate(&mut context).await.unwrap();
+
+    let result = test_pack_set
+        .edit_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            Some(8),
+            None,
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::ImmutablePackSet, 0);
+}
@@ -0,0 +1,343 @@
+mod utils;
+
+use borsh::BorshSerialize;
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_gate_usage_program_address,
+    state::{GateUsage, HolderGate, HolderGateKind},
+};
+use metaplex_token_metadata::state::{Creator, Key};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{
+    account::AccountSharedData,
+    signer::Signer,
+    transaction::TransactionError,
+    transport::{self, TransportError},
+};
+use utils::*;
+
+// NFT held by voucher owner, payer is its verified creator and `unverified_creator` isn't verified
+async fn create_gate_nft(test_pack: &mut TestPack, unverified_creator: &Pubkey) -> TestMetadata {
+    let test_metadata = TestMetadata::new();
+    let creators = vec![
+        Creator {
+            address: test_pack.context.payer.pubkey(),
+            verified: true,
+            share: 50,
+        },
+        Creator {
+            address: *unverified_creator,
+            verified: false,
+            share: 50,
+        },
+    ];
+
+    test_metadata
+        .create(
+            &mut test_pack.context,
+            "Gate".to_string(),
+            "GT".to_string(),
+            "uri".to_string(),
+            Some(creators),
+            10,
+            false,
+            &test_metadata.token,
+            &test_pack.edition_authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    test_metadata
+}
+
+// Rewrite gate NFT metadata the way token metadata program stores NFT in collection. Every field
+// after `is_mutable` is set, so collection is found only if all the fields before it are read
+async fn set_collection(
+    test_pack: &mut TestPack,
+    gate_nft: &TestMetadata,
+    collection_mint: &Pubkey,
+    verified: bool,
+) {
+    let mut account = get_account(&mut test_pack.context, &gate_nft.pubkey).await;
+
+    let mut data = Vec::new();
+    (Key::MetadataV1 as u8).serialize(&mut data).unwrap();
+    test_pack
+        .edition_authority
+        .pubkey()
+        .serialize(&mut data)
+        .unwrap();
+    gate_nft.mint.pubkey().serialize(&mut data).unwrap();
+    "Gate".to_string().serialize(&mut data).unwrap();
+    "GT".to_string().serialize(&mut data).unwrap();
+    "uri".to_string().serialize(&mut data).unwrap();
+    10u16.serialize(&mut data).unwrap();
+    Some(vec![Creator {
+        address: Pubkey::new_unique(),
+        verified: false,
+        share: 100,
+    }])
+    .serialize(&mut data)
+    .unwrap();
+    // primary sale happened and is mutable
+    false.serialize(&mut data).unwrap();
+    true.serialize(&mut data).unwrap();
+    // edition nonce and non-fungible token standard
+    Some(255u8).serialize(&mut data).unwrap();
+    Some(0u8).serialize(&mut data).unwrap();
+    Some((verified, *collection_mint))
+        .serialize(&mut data)
+        .unwrap();
+    // uses follow collection: method, remaining and total
+    Some((0u8, 1u64, 1u64)).serialize(&mut data).unwrap();
+
+    assert!(data.len() <= account.data.len());
+    data.resize(account.data.len(), 0);
+    account.data = data;
+
+    test_pack
+        .context
+        .set_account(&gate_nft.pubkey, &AccountSharedData::from(account));
+}
+
+async fn request_card(
+    test_pack: &mut TestPack,
+    voucher: usize,
+    gate_nft: &TestMetadata,
+) -> transport::Result<()> {
+    let voucher_edition = &test_pack.voucher_editions[voucher];
+
+    test_pack
+        .test_pack_set
+        .request_card_for_redeem_with_holder_gate(
+            &mut test_pack.context,
+            &test_pack.test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &test_pack.edition_authority,
+            &voucher_edition.token.pubkey(),
+            &test_pack.test_randomness_oracle.keypair.pubkey(),
+            1,
+            &gate_nft.token.pubkey(),
+            &gate_nft.pubkey,
+            &gate_nft.mint.pubkey(),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn success_verified_creator() {
+    let mut test_pack = TestPack::new().await;
+
+    let gate_nft = create_gate_nft(&mut test_pack, &Pubkey::new_unique()).await;
+
+    let holder_gate = HolderGate {
+        kind: HolderGateKind::VerifiedCreator {
+            creator: test_pack.context.payer.pubkey(),
+        },
+        max_uses: 1,
+    };
+    test_pack
+        .test_pack_set
+        .set_holder_gate(&mut test_pack.context, Some(holder_gate.clone()))
+        .await
+        .unwrap();
+
+    let pack_set = test_pack
+        .test_pack_set
+        .get_data(&mut test_pack.context)
+        .await;
+    assert_eq!(pack_set.holder_gate, Some(holder_gate));
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    request_card(&mut test_pack, 0, &gate_nft).await.unwrap();
+
+    let (gate_usage_key, _) = find_gate_usage_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &gate_nft.mint.pubkey(),
+    );
+    let gate_usage_data = get_account(&mut test_pack.context, &gate_usage_key).await;
+    let gate_usage = GateUsage::unpack_from_slice(&gate_usage_data.data).unwrap();
+    assert_eq!(gate_usage.mint, gate_nft.mint.pubkey());
+    assert_eq!(gate_usage.uses, 1);
+
+    let result = request_card(&mut test_pack, 1, &gate_nft).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::GateUsesExhausted, 0);
+}
+
+#[tokio::test]
+async fn fail_unverified_creator() {
+    let mut test_pack = TestPack::new().await;
+
+    let creator = Pubkey::new_unique();
+    let gate_nft = create_gate_nft(&mut test_pack, &creator).await;
+
+    test_pack
+        .test_pack_set
+        .set_holder_gate(
+            &mut test_pack.context,
+            Some(HolderGate {
+                kind: HolderGateKind::VerifiedCreator { creator },
+                max_uses: 1,
+            }),
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = request_card(&mut test_pack, 0, &gate_nft).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotGateHolder, 0);
+}
+
+#[tokio::test]
+async fn success_collection() {
+    let mut test_pack = TestPack::new().await;
+
+    let gate_nft = create_gate_nft(&mut test_pack, &Pubkey::new_unique()).await;
+    let collection_mint = Pubkey::new_unique();
+    set_collection(&mut test_pack, &gate_nft, &collection_mint, true).await;
+
+    test_pack
+        .test_pack_set
+        .set_holder_gate(
+            &mut test_pack.context,
+            Some(HolderGate {
+                kind: HolderGateKind::Collection {
+                    mint: collection_mint,
+                },
+                max_uses: 1,
+            }),
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    request_card(&mut test_pack, 0, &gate_nft).await.unwrap();
+
+    let (gate_usage_key, _) = find_gate_usage_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &gate_nft.mint.pubkey(),
+    );
+    let gate_usage_data = get_account(&mut test_pack.context, &gate_usage_key).await;
+    let gate_usage = GateUsage::unpack_from_slice(&gate_usage_data.data).unwrap();
+    assert_eq!(gate_usage.uses, 1);
+}
+
+#[tokio::test]
+async fn fail_unverified_collection() {
+    let mut test_pack = TestPack::new().await;
+
+    let gate_nft = create_gate_nft(&mut test_pack, &Pubkey::new_unique()).await;
+    let collection_mint = Pubkey::new_unique();
+    set_collection(&mut test_pack, &gate_nft, &collection_mint, false).await;
+
+    test_pack
+        .test_pack_set
+        .set_holder_gate(
+            &mut test_pack.context,
+            Some(HolderGate {
+                kind: HolderGateKind::Collection {
+                    mint: collection_mint,
+                },
+                max_uses: 1,
+            }),
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = request_card(&mut test_pack, 0, &gate_nft).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotGateHolder, 0);
+}
+
+#[tokio::test]
+async fn fail_not_in_collection() {
+    let mut test_pack = TestPack::new().await;
+
+    let gate_nft = create_gate_nft(&mut test_pack, &Pubkey::new_unique()).await;
+
+    test_pack
+        .test_pack_set
+        .set_holder_gate(
+            &mut test_pack.context,
+            Some(HolderGate {
+                kind: HolderGateKind::Collection {
+                    mint: Pubkey::new_unique(),
+                },
+                max_uses: 1,
+            }),
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = request_card(&mut test_pack, 0, &gate_nft).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotGateHolder, 0);
+}
+
+#[tokio::test]
+async fn fail_wrong_holder_gate() {
+    let mut test_pack = TestPack::new().await;
+
+    let result = test_pack
+        .test_pack_set
+        .set_holder_gate(
+            &mut test_pack.context,
+            Some(HolderGate {
+                kind: HolderGateKind::VerifiedCreator {
+                    creator: Pubkey::new_unique(),
+                },
+                max_uses: 0,
+            }),
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongHolderGate, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_activation() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = test_pack
+        .test_pack_set
+        .set_holder_gate(&mut test_pack.context, None)
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
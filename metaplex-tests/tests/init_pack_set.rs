@@ -3,7 +3,7 @@ mod utils;
 use metaplex_nft_packs::{
     error::NFTPacksError,
     instruction,
-    state::{AccountType, PackDistributionType, PackSetState},
+    state::{AccountType, PackDistributionType, PackSetState, RandomnessSource},
 };
 use num_traits::FromPrimitive;
 use solana_program::instruction::InstructionError;
@@ -44,6 +44,10 @@ async fn success() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
@@ -94,6 +98,10 @@ async fn fail() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date: redeem_start_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await;
@@ -0,0 +1,424 @@
+mod utils;
+
+use borsh::BorshSerialize;
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_pack_config_program_address, instruction,
+    state::{
+        AccountType, CleanUpActions, LegacyPackConfig, PackCard, PackDistributionType, PackSet,
+        PackSetState, PackVoucher, ProvingProcess, Versioned, MAX_DESCRIPTION_LEN, MAX_URI_LENGTH,
+    },
+    PROGRAM_VERSION,
+};
+use num_traits::FromPrimitive;
+use solana_program::{
+    instruction::InstructionError, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use std::collections::BTreeMap;
+use utils::*;
+
+// Accounts below are serialized field by field the way program stored them before versioning,
+// fields added since then and version byte didn't exist
+
+fn baseline_pack_card(pack_card: &PackCard) -> Vec<u8> {
+    let mut data = Vec::new();
+    AccountType::PackCard.serialize(&mut data).unwrap();
+    pack_card.pack_set.serialize(&mut data).unwrap();
+    pack_card.master.serialize(&mut data).unwrap();
+    pack_card.metadata.serialize(&mut data).unwrap();
+    pack_card.token_account.serialize(&mut data).unwrap();
+    pack_card.max_supply.serialize(&mut data).unwrap();
+    pack_card.weight.serialize(&mut data).unwrap();
+    data.resize(PackCard::LEGACY_LEN, 0);
+    data
+}
+
+fn baseline_pack_voucher(pack_voucher: &PackVoucher) -> Vec<u8> {
+    let mut data = Vec::new();
+    AccountType::PackVoucher.serialize(&mut data).unwrap();
+    pack_voucher.pack_set.serialize(&mut data).unwrap();
+    pack_voucher.master.serialize(&mut data).unwrap();
+    pack_voucher.metadata.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), PackVoucher::LEGACY_LEN);
+    data
+}
+
+fn baseline_proving_process(proving_process: &ProvingProcess) -> Vec<u8> {
+    let mut data = Vec::new();
+    AccountType::ProvingProcess.serialize(&mut data).unwrap();
+    proving_process.wallet_key.serialize(&mut data).unwrap();
+    proving_process.is_exhausted.serialize(&mut data).unwrap();
+    proving_process.voucher_mint.serialize(&mut data).unwrap();
+    proving_process.pack_set.serialize(&mut data).unwrap();
+    proving_process.cards_redeemed.serialize(&mut data).unwrap();
+    proving_process
+        .cards_to_redeem
+        .serialize(&mut data)
+        .unwrap();
+    data.resize(ProvingProcess::LEGACY_LEN, 0);
+    data
+}
+
+// Description and uri were padded with zeros to their max length
+fn baseline_pack_set(pack_set: &PackSet) -> Vec<u8> {
+    let mut description = pack_set.description.clone().into_bytes();
+    description.resize(MAX_DESCRIPTION_LEN, 0);
+    let mut uri = pack_set.uri.clone().into_bytes();
+    uri.resize(MAX_URI_LENGTH, 0);
+
+    let mut data = Vec::new();
+    AccountType::PackSet.serialize(&mut data).unwrap();
+    pack_set.store.serialize(&mut data).unwrap();
+    pack_set.authority.serialize(&mut data).unwrap();
+    String::from_utf8(description)
+        .unwrap()
+        .serialize(&mut data)
+        .unwrap();
+    String::from_utf8(uri)
+        .unwrap()
+        .serialize(&mut data)
+        .unwrap();
+    pack_set.name.serialize(&mut data).unwrap();
+    pack_set.pack_cards.serialize(&mut data).unwrap();
+    pack_set.pack_vouchers.serialize(&mut data).unwrap();
+    pack_set.total_weight.serialize(&mut data).unwrap();
+    pack_set.total_editions.serialize(&mut data).unwrap();
+    pack_set.mutable.serialize(&mut data).unwrap();
+    pack_set.pack_state.serialize(&mut data).unwrap();
+    pack_set.distribution_type.serialize(&mut data).unwrap();
+    pack_set
+        .allowed_amount_to_redeem
+        .serialize(&mut data)
+        .unwrap();
+    pack_set.redeem_start_date.serialize(&mut data).unwrap();
+    pack_set.redeem_end_date.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), PackSet::LEGACY_LEN);
+    data
+}
+
+fn baseline_pack_config(weights: &[(u32, u32, u32)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    AccountType::PackConfig.serialize(&mut data).unwrap();
+    weights.to_vec().serialize(&mut data).unwrap();
+    CleanUpActions::None.serialize(&mut data).unwrap();
+    data.resize(LegacyPackConfig::LEN, 0);
+    data
+}
+
+async fn setup(key: Pubkey, data: Vec<u8>, owner: Pubkey) -> ProgramTestContext {
+    let mut program = nft_packs_program_test();
+    program.add_account(
+        key,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+
+    program.start_with_context().await
+}
+
+async fn migrate(
+    context: &mut ProgramTestContext,
+    account: &Pubkey,
+    pack_set: &Option<Pubkey>,
+) -> transport::Result<()> {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction::migrate_account(
+            &metaplex_nft_packs::id(),
+            account,
+            &context.payer.pubkey(),
+            pack_set,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+// Check account was resized to current size and stays rent exempt
+async fn assert_resized(context: &mut ProgramTestContext, account: &Account, len: usize) {
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    assert_eq!(account.data.len(), len);
+    assert!(rent.is_exempt(account.lamports, len));
+}
+
+fn pack_card(version: u8) -> PackCard {
+    PackCard {
+        account_type: AccountType::PackCard,
+        pack_set: Pubkey::new_unique(),
+        master: Pubkey::new_unique(),
+        metadata: Pubkey::new_unique(),
+        token_account: Pubkey::new_unique(),
+        max_supply: 5,
+        weight: 10,
+        tier: None,
+        version,
+    }
+}
+
+#[tokio::test]
+async fn success_pack_card() {
+    let pack_card = pack_card(0);
+    let data = baseline_pack_card(&pack_card);
+    assert_eq!(PackCard::unpack(&data).unwrap(), pack_card);
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data, metaplex_nft_packs::id()).await;
+
+    migrate(&mut context, &key, &None).await.unwrap();
+
+    let account = get_account(&mut context, &key).await;
+    assert_resized(&mut context, &account, PackCard::LEN).await;
+    assert_eq!(
+        PackCard::unpack(&account.data).unwrap(),
+        PackCard {
+            version: PROGRAM_VERSION,
+            ..pack_card
+        }
+    );
+}
+
+#[tokio::test]
+async fn success_proving_process() {
+    let mut cards_to_redeem = BTreeMap::new();
+    cards_to_redeem.insert(1, 2);
+    cards_to_redeem.insert(3, 1);
+    let proving_process = ProvingProcess {
+        account_type: AccountType::ProvingProcess,
+        wallet_key: Pubkey::new_unique(),
+        is_exhausted: false,
+        voucher_mint: Pubkey::new_unique(),
+        pack_set: Pubkey::new_unique(),
+        cards_redeemed: 1,
+        cards_to_redeem,
+        // claimed card and cards left to claim
+        draw_counter: 4,
+        pending_draws: 0,
+        pending_round: 0,
+        version: 0,
+    };
+    let data = baseline_proving_process(&proving_process);
+    assert_eq!(ProvingProcess::unpack(&data).unwrap(), proving_process);
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data, metaplex_nft_packs::id()).await;
+
+    migrate(&mut context, &key, &None).await.unwrap();
+
+    let account = get_account(&mut context, &key).await;
+    assert_resized(&mut context, &account, ProvingProcess::LEN).await;
+    assert_eq!(
+        ProvingProcess::unpack(&account.data).unwrap(),
+        ProvingProcess {
+            version: PROGRAM_VERSION,
+            ..proving_process
+        }
+    );
+}
+
+#[tokio::test]
+async fn success_legacy_pack_set() {
+    let pack_set = PackSet {
+        account_type: AccountType::PackSet,
+        store: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        description: "description".to_string(),
+        uri: "uri".to_string(),
+        name: [7; 32],
+        pack_cards: 2,
+        pack_vouchers: 1,
+        total_editions: 10,
+        mutable: true,
+        pack_state: PackSetState::Activated,
+        distribution_type: PackDistributionType::MaxSupply,
+        allowed_amount_to_redeem: 3,
+        redeem_start_date: 100,
+        redeem_end_date: Some(200),
+        ..PackSet::default()
+    };
+    let data = baseline_pack_set(&pack_set);
+    assert!(PackSet::is_legacy(&data));
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data, metaplex_nft_packs::id()).await;
+
+    migrate(&mut context, &key, &None).await.unwrap();
+
+    let account = get_account(&mut context, &key).await;
+    assert_resized(&mut context, &account, PackSet::LEN).await;
+    assert_eq!(account.data[0], AccountType::PackSetV2 as u8);
+    assert_eq!(
+        PackSet::unpack(&account.data).unwrap(),
+        PackSet {
+            version: PROGRAM_VERSION,
+            ..pack_set
+        }
+    );
+}
+
+#[tokio::test]
+async fn success_pack_voucher() {
+    let pack_voucher = PackVoucher {
+        account_type: AccountType::PackVoucher,
+        pack_set: Pubkey::new_unique(),
+        master: Pubkey::new_unique(),
+        metadata: Pubkey::new_unique(),
+        token_account: None,
+        version: 0,
+    };
+    let mut data = baseline_pack_voucher(&pack_voucher);
+    assert_eq!(PackVoucher::unpack(&data).unwrap(), pack_voucher);
+
+    // Voucher of old size has no space for token account until it's migrated
+    assert_eq!(
+        PackVoucher::pack(
+            PackVoucher {
+                token_account: Some(Pubkey::new_unique()),
+                ..pack_voucher.clone()
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        ProgramError::AccountDataTooSmall
+    );
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data, metaplex_nft_packs::id()).await;
+
+    migrate(&mut context, &key, &None).await.unwrap();
+
+    let account = get_account(&mut context, &key).await;
+    assert_resized(&mut context, &account, PackVoucher::LEN).await;
+    assert_eq!(
+        PackVoucher::unpack(&account.data).unwrap(),
+        PackVoucher {
+            version: PROGRAM_VERSION,
+            ..pack_voucher
+        }
+    );
+}
+
+#[tokio::test]
+async fn fail_legacy_account_without_payer_signature() {
+    let pack_voucher = PackVoucher {
+        account_type: AccountType::PackVoucher,
+        pack_set: Pubkey::new_unique(),
+        master: Pubkey::new_unique(),
+        metadata: Pubkey::new_unique(),
+        token_account: None,
+        version: 0,
+    };
+    let data = baseline_pack_voucher(&pack_voucher);
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data.clone(), metaplex_nft_packs::id()).await;
+
+    let mut migrate_instruction = instruction::migrate_account(
+        &metaplex_nft_packs::id(),
+        &key,
+        &Pubkey::new_unique(),
+        &None,
+    );
+    migrate_instruction.accounts[1].is_signer = false;
+    let tx = Transaction::new_signed_with_payer(
+        &[migrate_instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    let result = context.banks_client.process_transaction(tx).await;
+
+    assert_transport_error!(
+        result.unwrap_err(),
+        TransportError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature
+        ))
+    );
+
+    // Account keeps old size and is still readable
+    let account = get_account(&mut context, &key).await;
+    assert_eq!(account.data, data);
+    assert_eq!(PackVoucher::unpack(&account.data).unwrap(), pack_voucher);
+}
+
+#[tokio::test]
+async fn fail_legacy_pack_config() {
+    let pack_set = Pubkey::new_unique();
+    let (key, _) = find_pack_config_program_address(&metaplex_nft_packs::id(), &pack_set);
+    let data = baseline_pack_config(&[(1, 5, 2), (2, 3, 1)]);
+
+    let mut context = setup(key, data.clone(), metaplex_nft_packs::id()).await;
+
+    let result = migrate(&mut context, &key, &Some(pack_set)).await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::AccountAlreadyMigrated,
+        0
+    );
+
+    // Legacy pack config keeps its layout
+    let account = get_account(&mut context, &key).await;
+    assert_eq!(account.data, data);
+    assert_eq!(
+        LegacyPackConfig::unpack(&account.data).unwrap(),
+        LegacyPackConfig {
+            account_type: AccountType::PackConfig,
+            weights: vec![(1, 5, 2), (2, 3, 1)],
+            action_to_do: CleanUpActions::None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn fail_already_migrated() {
+    let pack_card = pack_card(PROGRAM_VERSION);
+    let mut data = vec![0; PackCard::LEN];
+    PackCard::pack(pack_card, &mut data).unwrap();
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data, metaplex_nft_packs::id()).await;
+
+    let result = migrate(&mut context, &key, &None).await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::AccountAlreadyMigrated,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_wrong_owner() {
+    let data = baseline_pack_card(&pack_card(0));
+
+    let key = Pubkey::new_unique();
+    let mut context = setup(key, data, Pubkey::new_unique()).await;
+
+    let result = migrate(&mut context, &key, &None).await;
+
+    assert_transport_error!(
+        result.unwrap_err(),
+        TransportError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::IllegalOwner
+        ))
+    );
+}
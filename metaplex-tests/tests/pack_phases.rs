@@ -0,0 +1,271 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_wallet_record_program_address,
+    state::{PackPhase, PhaseGate, WalletRecord, MAX_PACK_PHASES},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+    transport::{self, TransportError},
+};
+use spl_token::state::Account;
+use utils::*;
+
+// Create gate mint and token account of voucher owner holding `amount` of gate tokens
+async fn create_gate_token(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    amount: u64,
+) -> (Keypair, Keypair) {
+    let gate_mint = Keypair::new();
+    let gate_mint_authority = Keypair::new();
+    let gate_token = Keypair::new();
+
+    create_mint(context, &gate_mint, &gate_mint_authority.pubkey(), None)
+        .await
+        .unwrap();
+    create_token_account(context, &gate_token, &gate_mint.pubkey(), owner)
+        .await
+        .unwrap();
+    if amount > 0 {
+        mint_tokens(
+            context,
+            &gate_mint.pubkey(),
+            &gate_token.pubkey(),
+            amount,
+            &gate_mint_authority.pubkey(),
+            Some(vec![&gate_mint_authority]),
+        )
+        .await
+        .unwrap();
+    }
+
+    (gate_mint, gate_token)
+}
+
+async fn request_card_with_gate(
+    test_pack: &mut TestPack,
+    voucher: usize,
+    gate_mint: &Keypair,
+    gate_token: &Keypair,
+) -> transport::Result<()> {
+    let voucher_edition = &test_pack.voucher_editions[voucher];
+
+    test_pack
+        .test_pack_set
+        .request_card_for_redeem_with_gate(
+            &mut test_pack.context,
+            &test_pack.test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &test_pack.edition_authority,
+            &voucher_edition.token.pubkey(),
+            &test_pack.test_randomness_oracle.keypair.pubkey(),
+            1,
+            &gate_token.pubkey(),
+            &gate_mint.pubkey(),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn success_wallet_limit() {
+    let mut test_pack = TestPack::new().await;
+
+    let phases = vec![PackPhase {
+        start_date: test_pack.now,
+        end_date: None,
+        gate: PhaseGate::Open,
+        max_per_wallet: Some(1),
+    }];
+    test_pack
+        .test_pack_set
+        .set_pack_phases(&mut test_pack.context, phases.clone())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack
+        .test_pack_set
+        .get_data(&mut test_pack.context)
+        .await;
+    assert_eq!(pack_set.phases, phases);
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    test_pack.request_card(0).await.unwrap();
+
+    let (wallet_record_key, _) = find_wallet_record_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &test_pack.edition_authority.pubkey(),
+    );
+    let wallet_record_data = get_account(&mut test_pack.context, &wallet_record_key).await;
+    let wallet_record = WalletRecord::unpack_from_slice(&wallet_record_data.data).unwrap();
+    assert_eq!(wallet_record.phase_vouchers[0], 1);
+
+    let result = test_pack.request_card(1).await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::PhaseWalletLimitReached,
+        0
+    );
+}
+
+#[tokio::test]
+async fn success_allowlist() {
+    let mut test_pack = TestPack::new().await;
+
+    let owner = test_pack.edition_authority.pubkey();
+    let (gate_mint, gate_token) = create_gate_token(&mut test_pack.context, &owner, 1).await;
+
+    test_pack
+        .test_pack_set
+        .set_pack_phases(
+            &mut test_pack.context,
+            vec![PackPhase {
+                start_date: test_pack.now,
+                end_date: None,
+                gate: PhaseGate::Allowlist {
+                    mint: gate_mint.pubkey(),
+                },
+                max_per_wallet: None,
+            }],
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    request_card_with_gate(&mut test_pack, 0, &gate_mint, &gate_token)
+        .await
+        .unwrap();
+
+    // Allowlist token is burned, so it can't open the second voucher
+    let gate_token_data = get_account(&mut test_pack.context, &gate_token.pubkey()).await;
+    let gate_token_acc = Account::unpack(&gate_token_data.data).unwrap();
+    assert_eq!(gate_token_acc.amount, 0);
+
+    let result = request_card_with_gate(&mut test_pack, 1, &gate_mint, &gate_token).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotInPhaseAllowlist, 0);
+}
+
+#[tokio::test]
+async fn fail_not_token_holder() {
+    let mut test_pack = TestPack::new().await;
+
+    let owner = test_pack.edition_authority.pubkey();
+    let (gate_mint, gate_token) = create_gate_token(&mut test_pack.context, &owner, 0).await;
+
+    test_pack
+        .test_pack_set
+        .set_pack_phases(
+            &mut test_pack.context,
+            vec![PackPhase {
+                start_date: test_pack.now,
+                end_date: None,
+                gate: PhaseGate::TokenHolder {
+                    mint: gate_mint.pubkey(),
+                },
+                max_per_wallet: None,
+            }],
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = request_card_with_gate(&mut test_pack, 0, &gate_mint, &gate_token).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::NotPhaseTokenHolder, 0);
+}
+
+#[tokio::test]
+async fn fail_phase_not_active() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .set_pack_phases(
+            &mut test_pack.context,
+            vec![PackPhase {
+                start_date: test_pack.now + 1000,
+                end_date: None,
+                gate: PhaseGate::Open,
+                max_per_wallet: None,
+            }],
+        )
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = test_pack.request_card(0).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PackPhaseNotActive, 0);
+}
+
+#[tokio::test]
+async fn fail_wrong_phases() {
+    let mut test_pack = TestPack::new().await;
+
+    let phase = PackPhase {
+        start_date: test_pack.now,
+        end_date: Some(test_pack.now + 100),
+        gate: PhaseGate::Open,
+        max_per_wallet: None,
+    };
+
+    // Phases overlap
+    let result = test_pack
+        .test_pack_set
+        .set_pack_phases(&mut test_pack.context, vec![phase.clone(), phase.clone()])
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackPhases, 0);
+
+    let result = test_pack
+        .test_pack_set
+        .set_pack_phases(&mut test_pack.context, vec![phase; MAX_PACK_PHASES + 1])
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackPhases, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_activation() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = test_pack
+        .test_pack_set
+        .set_pack_phases(&mut test_pack.context, Vec::new())
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
@@ -0,0 +1,387 @@
+use borsh::BorshSerialize;
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    state::{
+        AccountType, HolderGate, HolderGateKind, PackDistributionType, PackPhase, PackSet,
+        PackSetState, PackSetView, PackSlot, PhaseGate, PityRule, RandomnessSource,
+        RandomnessVersion, RarityTier, TreasuryRecipient, Versioned, MAX_DESCRIPTION_LEN,
+        MAX_PACK_PHASES, MAX_PACK_SLOTS, MAX_RARITY_TIERS, MAX_TREASURY_RECIPIENTS, MAX_URI_LENGTH,
+    },
+    PROGRAM_VERSION,
+};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+// Pack set with every optional field and vector filled up to its limit
+fn full_pack_set() -> PackSet {
+    PackSet {
+        account_type: AccountType::PackSet,
+        store: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        description: "d".repeat(MAX_DESCRIPTION_LEN),
+        uri: "u".repeat(MAX_URI_LENGTH),
+        name: [7; 32],
+        pack_cards: 3,
+        pack_vouchers: 2,
+        total_weight: 300,
+        total_editions: 10,
+        mutable: true,
+        pack_state: PackSetState::Activated,
+        distribution_type: PackDistributionType::Fixed,
+        allowed_amount_to_redeem: 5,
+        redeem_start_date: 100,
+        redeem_end_date: Some(200),
+        price: Some(1_000_000),
+        payment_mint: Some(Pubkey::new_unique()),
+        treasury_recipients: (0..MAX_TREASURY_RECIPIENTS)
+            .map(|i| TreasuryRecipient {
+                address: Pubkey::new_unique(),
+                share: i as u16 * 100,
+            })
+            .collect(),
+        randomness_version: RandomnessVersion::V1,
+        randomness_source: RandomnessSource::SignedValue {
+            signer: Pubkey::new_unique(),
+        },
+        randomness_commitment: Some([1; 32]),
+        randomness_rounds: 2,
+        rarity_tiers: (0..MAX_RARITY_TIERS)
+            .map(|i| RarityTier {
+                probability: i as u16 + 1,
+            })
+            .collect(),
+        pack_slots: (0..MAX_PACK_SLOTS)
+            .map(|i| PackSlot { tiers: 1 << i })
+            .collect(),
+        unique_cards: true,
+        pity_rule: Some(PityRule {
+            rare_weight_threshold: 10,
+            max_misses: 4,
+        }),
+        pack_config: Pubkey::new_unique(),
+        version: PROGRAM_VERSION,
+        phases: (0..MAX_PACK_PHASES as u64)
+            .map(|i| PackPhase {
+                start_date: 100 + i * 20,
+                end_date: if i % 2 == 0 { Some(110 + i * 20) } else { None },
+                gate: match i % 3 {
+                    0 => PhaseGate::Open,
+                    1 => PhaseGate::Allowlist {
+                        mint: Pubkey::new_unique(),
+                    },
+                    _ => PhaseGate::TokenHolder {
+                        mint: Pubkey::new_unique(),
+                    },
+                },
+                max_per_wallet: if i % 2 == 0 { None } else { Some(i as u32) },
+            })
+            .collect(),
+        allowlist_root: Some([9; 32]),
+        holder_gate: Some(HolderGate {
+            kind: HolderGateKind::Collection {
+                mint: Pubkey::new_unique(),
+            },
+            max_uses: 3,
+        }),
+        max_vouchers_per_wallet: Some(10),
+        restocks: 2,
+    }
+}
+
+// Pack set serialized with borsh before fixed layout, it has only fields up to redeem end date
+fn legacy_pack_set() -> PackSet {
+    let pack_set = full_pack_set();
+
+    PackSet {
+        account_type: pack_set.account_type,
+        store: pack_set.store,
+        authority: pack_set.authority,
+        description: pack_set.description,
+        uri: pack_set.uri,
+        name: pack_set.name,
+        pack_cards: pack_set.pack_cards,
+        pack_vouchers: pack_set.pack_vouchers,
+        total_weight: pack_set.total_weight,
+        total_editions: pack_set.total_editions,
+        mutable: pack_set.mutable,
+        pack_state: pack_set.pack_state,
+        distribution_type: pack_set.distribution_type,
+        allowed_amount_to_redeem: pack_set.allowed_amount_to_redeem,
+        redeem_start_date: pack_set.redeem_start_date,
+        redeem_end_date: pack_set.redeem_end_date,
+        ..PackSet::default()
+    }
+}
+
+// Serialize pack set field by field the way it was stored before fixed layout, description and
+// uri of max length need no padding
+fn legacy_data(pack_set: &PackSet) -> Vec<u8> {
+    let mut data = Vec::new();
+    AccountType::PackSet.serialize(&mut data).unwrap();
+    pack_set.store.serialize(&mut data).unwrap();
+    pack_set.authority.serialize(&mut data).unwrap();
+    pack_set.description.serialize(&mut data).unwrap();
+    pack_set.uri.serialize(&mut data).unwrap();
+    pack_set.name.serialize(&mut data).unwrap();
+    pack_set.pack_cards.serialize(&mut data).unwrap();
+    pack_set.pack_vouchers.serialize(&mut data).unwrap();
+    pack_set.total_weight.serialize(&mut data).unwrap();
+    pack_set.total_editions.serialize(&mut data).unwrap();
+    pack_set.mutable.serialize(&mut data).unwrap();
+    pack_set.pack_state.serialize(&mut data).unwrap();
+    pack_set.distribution_type.serialize(&mut data).unwrap();
+    pack_set
+        .allowed_amount_to_redeem
+        .serialize(&mut data)
+        .unwrap();
+    pack_set.redeem_start_date.serialize(&mut data).unwrap();
+    pack_set.redeem_end_date.serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn layout_fits_account() {
+    assert!(PackSetView::<&[u8]>::LAYOUT_LEN <= PackSet::LEN);
+    assert_eq!(legacy_data(&legacy_pack_set()).len(), PackSet::LEGACY_LEN);
+}
+
+#[test]
+fn fixed_layout_roundtrip() {
+    let pack_set = full_pack_set();
+    let mut data = vec![0; PackSet::LEN];
+    PackSet::pack(pack_set.clone(), &mut data).unwrap();
+
+    assert_eq!(data[0], AccountType::PackSetV2 as u8);
+    assert_eq!(PackSet::unpack(&data).unwrap(), pack_set);
+
+    let view = PackSetView::new(&data[..]).unwrap();
+    assert_eq!(view.to_pack_set().unwrap(), pack_set);
+    assert_eq!(view.store(), pack_set.store);
+    assert_eq!(view.allowed_amount_to_redeem(), 5);
+    assert_eq!(view.redeem_end_date(), Some(200));
+    assert_eq!(view.pity_rule(), pack_set.pity_rule);
+    assert_eq!(
+        view.get_pack_slot(3).unwrap(),
+        Some(PackSlot { tiers: 1 << 3 })
+    );
+    assert_eq!(view.get_pack_slot(MAX_PACK_SLOTS).unwrap(), None);
+    assert_eq!(view.pack_config(), pack_set.pack_config);
+    assert_eq!(view.version(), PROGRAM_VERSION);
+    assert_eq!(view.allowlist_root(), Some([9; 32]));
+    assert_eq!(view.holder_gate().unwrap(), pack_set.holder_gate);
+    assert_eq!(view.max_vouchers_per_wallet(), Some(10));
+    assert_eq!(view.restocks(), 2);
+}
+
+#[test]
+fn empty_fields_roundtrip() {
+    let pack_set = PackSet {
+        account_type: AccountType::PackSet,
+        description: "description".to_string(),
+        ..PackSet::default()
+    };
+    let mut data = vec![0; PackSet::LEN];
+    PackSet::pack(pack_set.clone(), &mut data).unwrap();
+
+    assert_eq!(PackSet::unpack(&data).unwrap(), pack_set);
+}
+
+#[test]
+fn legacy_pack_set_is_read() {
+    let pack_set = legacy_pack_set();
+    let data = legacy_data(&pack_set);
+
+    assert!(PackSet::is_legacy(&data));
+    assert_eq!(PackSet::unpack(&data).unwrap(), pack_set);
+
+    // Readonly load converts a copy and leaves account data untouched
+    let view = PackSetView::load(&data).unwrap();
+    assert_eq!(view.to_pack_set().unwrap(), pack_set);
+    assert_eq!(data, legacy_data(&pack_set));
+
+    // Fields added after redeem end date are read as empty
+    assert!(view.is_legacy_size());
+    assert_eq!(view.price(), None);
+    assert_eq!(view.randomness_version().unwrap(), RandomnessVersion::V0);
+    assert_eq!(view.randomness_source().unwrap(), RandomnessSource::Oracle);
+    assert_eq!(view.get_pack_slot(0).unwrap(), None);
+    assert_eq!(view.pity_rule(), None);
+    assert_eq!(view.pack_config(), Pubkey::default());
+    assert_eq!(view.version(), 0);
+
+    assert_eq!(
+        PackSetView::new(&data[..]).err().unwrap(),
+        ProgramError::InvalidAccountData
+    );
+}
+
+#[test]
+fn legacy_pack_set_is_converted_in_place() {
+    let pack_set = legacy_pack_set();
+    let mut data = legacy_data(&pack_set);
+
+    let mut view = PackSetView::load_mut(&mut data).unwrap();
+    view.decrement_supply().unwrap();
+
+    let mut expected = pack_set;
+    expected.total_editions -= 1;
+
+    assert_eq!(data.len(), PackSet::LEGACY_LEN);
+    assert_eq!(data[0], AccountType::PackSetV2 as u8);
+    assert!(!PackSet::is_legacy(&data));
+    assert_eq!(PackSet::unpack(&data).unwrap(), expected);
+    assert_eq!(
+        PackSetView::new(&data[..]).unwrap().to_pack_set().unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn fail_wrong_account_type() {
+    let mut data = vec![0; PackSet::LEN];
+    data[0] = AccountType::PackCard as u8;
+
+    assert_eq!(
+        PackSet::unpack(&data).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+    assert_eq!(
+        PackSetView::load(&data).err().unwrap(),
+        ProgramError::InvalidAccountData
+    );
+}
+
+#[test]
+fn legacy_size_pack_set_has_no_new_fields() {
+    let pack_set = legacy_pack_set();
+    let mut data = vec![0; PackSet::LEGACY_LEN];
+    PackSet::pack(pack_set.clone(), &mut data).unwrap();
+
+    assert_eq!(PackSet::unpack(&data).unwrap(), pack_set);
+    assert_eq!(
+        PackSetView::new(&data[..]).unwrap().phases().unwrap(),
+        vec![]
+    );
+    assert_eq!(PackSetView::new(&data[..]).unwrap().allowlist_root(), None);
+    assert_eq!(
+        PackSetView::new(&data[..]).unwrap().holder_gate().unwrap(),
+        None
+    );
+    assert_eq!(
+        PackSetView::new(&data[..])
+            .unwrap()
+            .max_vouchers_per_wallet(),
+        None
+    );
+    assert_eq!(PackSetView::new(&data[..]).unwrap().restocks(), 0);
+
+    assert_eq!(
+        PackSet::pack(full_pack_set(), &mut data).unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+    assert_eq!(
+        PackSet::pack(
+            PackSet {
+                price: Some(1),
+                ..legacy_pack_set()
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+    assert_eq!(
+        PackSet::pack(
+            PackSet {
+                pack_config: Pubkey::new_unique(),
+                ..legacy_pack_set()
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+    assert_eq!(
+        PackSet::pack(
+            PackSet {
+                allowlist_root: Some([1; 32]),
+                ..pack_set
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+    assert_eq!(
+        PackSet::pack(
+            PackSet {
+                holder_gate: full_pack_set().holder_gate,
+                ..legacy_pack_set()
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+    assert_eq!(
+        PackSet::pack(
+            PackSet {
+                max_vouchers_per_wallet: Some(1),
+                ..legacy_pack_set()
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+    assert_eq!(
+        PackSet::pack(
+            PackSet {
+                restocks: 1,
+                ..legacy_pack_set()
+            },
+            &mut data
+        )
+        .unwrap_err(),
+        NFTPacksError::PackSetTooSmall.into()
+    );
+}
+
+#[test]
+fn active_phase() {
+    let pack_set = full_pack_set();
+    let mut data = vec![0; PackSet::LEN];
+    PackSet::pack(pack_set.clone(), &mut data).unwrap();
+    let view = PackSetView::new(&data[..]).unwrap();
+
+    assert_eq!(
+        view.get_active_phase(99).unwrap_err(),
+        NFTPacksError::PackPhaseNotActive.into()
+    );
+    assert_eq!(
+        view.get_active_phase(105).unwrap(),
+        Some((0, pack_set.phases[0].clone()))
+    );
+    // Phase 0 ends before phase 1 starts
+    assert_eq!(
+        view.get_active_phase(115).unwrap_err(),
+        NFTPacksError::PackPhaseNotActive.into()
+    );
+    // Phase 1 has no end date and lasts until phase 2 starts
+    assert_eq!(
+        view.get_active_phase(139).unwrap(),
+        Some((1, pack_set.phases[1].clone()))
+    );
+    assert_eq!(
+        view.get_active_phase(190).unwrap(),
+        Some((4, pack_set.phases[4].clone()))
+    );
+    assert_eq!(
+        view.get_active_phase(191).unwrap_err(),
+        NFTPacksError::PackPhaseNotActive.into()
+    );
+
+    // Pack set without phases is limited only by redeem dates
+    PackSet::pack(legacy_pack_set(), &mut data).unwrap();
+    let view = PackSetView::new(&data[..]).unwrap();
+    assert_eq!(view.get_active_phase(99).unwrap(), None);
+}
@@ -0,0 +1,284 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_proving_process_program_address,
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackDistributionType, PackSlot, ProvingProcess, RandomnessSource, RarityTier},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const ALLOWED_AMOUNT_TO_REDEEM: u32 = 2;
+const COMMON: u16 = 1 << 0;
+const RARE: u16 = 1 << 1;
+
+async fn setup() -> (ProgramTestContext, TestPackSet) {
+    setup_with_allowed_amount(ALLOWED_AMOUNT_TO_REDEEM).await
+}
+
+async fn setup_with_allowed_amount(
+    allowed_amount_to_redeem: u32,
+) -> (ProgramTestContext, TestPackSet) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let redeem_start_date = Some(clock.unix_timestamp as u64);
+    let redeem_end_date = Some(redeem_start_date.unwrap() + 100);
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem,
+                redeem_start_date,
+                redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    (context, test_pack_set)
+}
+
+async fn set_tiers(context: &mut ProgramTestContext, test_pack_set: &TestPackSet) {
+    test_pack_set
+        .set_rarity_tiers(
+            context,
+            vec![
+                RarityTier { probability: 9000 },
+                RarityTier { probability: 1000 },
+            ],
+        )
+        .await
+        .unwrap();
+}
+
+/// Adds cards with (index, tier, max_supply) and one voucher, activates pack and draws all the cards
+async fn draw_all_cards(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    cards: &[(u32, u8, u32)],
+    allowed_amount_to_redeem: u32,
+) -> ProvingProcess {
+    for (index, tier, max_supply) in cards.iter().copied() {
+        let (card_metadata, card_master_edition, card_master_token_holder) =
+            create_master_edition(context, test_pack_set, Some(10)).await;
+
+        let test_pack_card = TestPackCard::new(test_pack_set, index);
+        test_pack_set
+            .add_card(
+                context,
+                &test_pack_card,
+                &card_master_edition,
+                &card_metadata,
+                &card_master_token_holder,
+                AddCardToPackArgs {
+                    max_supply,
+                    weight: 100,
+                    index: test_pack_card.index,
+                    tier: Some(tier),
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(context, test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.activate(context).await.unwrap();
+    let mut test_randomness_oracle = TestRandomnessOracle::new();
+    test_randomness_oracle.init(context).await.unwrap();
+    test_randomness_oracle.update(context).await.unwrap();
+
+    test_pack_set
+        .request_cards_for_redeem(
+            context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+            allowed_amount_to_redeem,
+        )
+        .await
+        .unwrap();
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+    let proving_process_data = get_account(context, &proving_process_key).await;
+    ProvingProcess::unpack_from_slice(&proving_process_data.data).unwrap()
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_pack_set) = setup().await;
+
+    set_tiers(&mut context, &test_pack_set).await;
+
+    // Commons only in the first slot and rare in the second one
+    let slots = vec![PackSlot { tiers: COMMON }, PackSlot { tiers: RARE }];
+    test_pack_set
+        .set_pack_slots(&mut context, slots.clone())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_slots, slots);
+
+    let proving_process = draw_all_cards(
+        &mut context,
+        &test_pack_set,
+        &[(1, 0, 5), (2, 1, 5)],
+        ALLOWED_AMOUNT_TO_REDEEM,
+    )
+    .await;
+
+    // Every slot gives a card of its tier
+    assert_eq!(proving_process.cards_to_redeem.get(&1), Some(&1));
+    assert_eq!(proving_process.cards_to_redeem.get(&2), Some(&1));
+}
+
+#[tokio::test]
+async fn success_same_card_in_several_slots() {
+    let allowed_amount_to_redeem = 3;
+    let (mut context, test_pack_set) = setup_with_allowed_amount(allowed_amount_to_redeem).await;
+
+    set_tiers(&mut context, &test_pack_set).await;
+
+    // The only common card is drawn twice, slots are still counted by draws
+    let slots = vec![
+        PackSlot { tiers: COMMON },
+        PackSlot { tiers: COMMON },
+        PackSlot { tiers: RARE },
+    ];
+    test_pack_set
+        .set_pack_slots(&mut context, slots)
+        .await
+        .unwrap();
+
+    let proving_process = draw_all_cards(
+        &mut context,
+        &test_pack_set,
+        &[(1, 0, 5), (2, 1, 5)],
+        allowed_amount_to_redeem,
+    )
+    .await;
+
+    assert_eq!(proving_process.draw_counter, allowed_amount_to_redeem);
+    assert_eq!(proving_process.cards_to_redeem.get(&1), Some(&2));
+    assert_eq!(proving_process.cards_to_redeem.get(&2), Some(&1));
+}
+
+#[tokio::test]
+async fn fail_without_tiers() {
+    let (mut context, test_pack_set) = setup().await;
+
+    let result = test_pack_set
+        .set_pack_slots(&mut context, vec![PackSlot { tiers: COMMON }])
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackSlots, 0);
+}
+
+#[tokio::test]
+async fn fail_unknown_tier() {
+    let (mut context, test_pack_set) = setup().await;
+
+    set_tiers(&mut context, &test_pack_set).await;
+
+    let result = test_pack_set
+        .set_pack_slots(&mut context, vec![PackSlot { tiers: 1 << 2 }])
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackSlots, 0);
+}
+
+#[tokio::test]
+async fn fail_empty_slot() {
+    let (mut context, test_pack_set) = setup().await;
+
+    set_tiers(&mut context, &test_pack_set).await;
+
+    let result = test_pack_set
+        .set_pack_slots(&mut context, vec![PackSlot { tiers: 0 }])
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackSlots, 0);
+}
@@ -0,0 +1,314 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_proving_process_program_address, find_wallet_record_program_address,
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackDistributionType, PityRule, ProvingProcess, RandomnessSource, WalletRecord},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const ALLOWED_AMOUNT_TO_REDEEM: u32 = 5;
+const COMMON_CARD: u32 = 1;
+const RARE_CARD: u32 = 2;
+const PITY_RULE: PityRule = PityRule {
+    rare_weight_threshold: 1,
+    max_misses: 1,
+};
+
+async fn init_pack(distribution_type: PackDistributionType) -> (ProgramTestContext, TestPackSet) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let redeem_start_date = Some(clock.unix_timestamp as u64);
+    let redeem_end_date = Some(redeem_start_date.unwrap() + 100);
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type,
+                allowed_amount_to_redeem: ALLOWED_AMOUNT_TO_REDEEM,
+                redeem_start_date,
+                redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    (context, test_pack_set)
+}
+
+async fn setup() -> (
+    ProgramTestContext,
+    TestPackSet,
+    TestEditionMarker,
+    Keypair,
+    TestRandomnessOracle,
+) {
+    let (mut context, test_pack_set) = init_pack(PackDistributionType::Unlimited).await;
+
+    test_pack_set
+        .set_pity_rule(&mut context, Some(PITY_RULE))
+        .await
+        .unwrap();
+
+    for index in [COMMON_CARD, RARE_CARD].iter().copied() {
+        let (card_metadata, card_master_edition, card_master_token_holder) =
+            create_master_edition(&mut context, &test_pack_set, None).await;
+
+        let test_pack_card = TestPackCard::new(&test_pack_set, index);
+        test_pack_set
+            .add_card(
+                &mut context,
+                &test_pack_card,
+                &card_master_edition,
+                &card_metadata,
+                &card_master_token_holder,
+                AddCardToPackArgs {
+                    max_supply: 0,
+                    weight: if index == COMMON_CARD { 100 } else { 1 },
+                    index: test_pack_card.index,
+                    tier: None,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    let mut test_randomness_oracle = TestRandomnessOracle::new();
+    test_randomness_oracle.init(&mut context).await.unwrap();
+    test_randomness_oracle.update(&mut context).await.unwrap();
+
+    (
+        context,
+        test_pack_set,
+        voucher_edition,
+        edition_authority,
+        test_randomness_oracle,
+    )
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_pack_set, voucher_edition, edition_authority, test_randomness_oracle) =
+        setup().await;
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pity_rule, Some(PITY_RULE));
+
+    test_pack_set.activate(&mut context).await.unwrap();
+    test_pack_set
+        .request_cards_for_redeem(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+            ALLOWED_AMOUNT_TO_REDEEM,
+        )
+        .await
+        .unwrap();
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+    let proving_process_data = get_account(&mut context, &proving_process_key).await;
+    let proving_process = ProvingProcess::unpack_from_slice(&proving_process_data.data).unwrap();
+
+    // Two common cards are never drawn in a row, so at least every second card is rare
+    let rare_cards = *proving_process
+        .cards_to_redeem
+        .get(&RARE_CARD)
+        .unwrap_or(&0);
+    let common_cards = *proving_process
+        .cards_to_redeem
+        .get(&COMMON_CARD)
+        .unwrap_or(&0);
+    assert_eq!(rare_cards + common_cards, ALLOWED_AMOUNT_TO_REDEEM);
+    assert!(rare_cards >= ALLOWED_AMOUNT_TO_REDEEM / 2);
+
+    let (wallet_record_key, _) = find_wallet_record_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+    );
+    let wallet_record_data = get_account(&mut context, &wallet_record_key).await;
+    let wallet_record = WalletRecord::unpack_from_slice(&wallet_record_data.data).unwrap();
+    assert_eq!(wallet_record.pack_set, test_pack_set.keypair.pubkey());
+    assert_eq!(wallet_record.wallet, edition_authority.pubkey());
+    assert!(wallet_record.pity_counter <= PITY_RULE.max_misses);
+}
+
+#[tokio::test]
+async fn fail_zero_max_misses() {
+    let (mut context, test_pack_set, _voucher_edition, _edition_authority, _test_randomness_oracle) =
+        setup().await;
+
+    let result = test_pack_set
+        .set_pity_rule(
+            &mut context,
+            Some(PityRule {
+                rare_weight_threshold: 1,
+                max_misses: 0,
+            }),
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPityRule, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_activation() {
+    let (mut context, test_pack_set, _voucher_edition, _edition_authority, _test_randomness_oracle) =
+        setup().await;
+
+    test_pack_set.activate(&mut context).await.unwrap();
+
+    let result = test_pack_set.set_pity_rule(&mut context, None).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_cards_added() {
+    let (mut context, test_pack_set, _voucher_edition, _edition_authority, _test_randomness_oracle) =
+        setup().await;
+
+    let result = test_pack_set.set_pity_rule(&mut context, None).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PackSetHasCards, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_cards_deleted() {
+    let (mut context, test_pack_set) = init_pack(PackDistributionType::Unlimited).await;
+
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, None).await;
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply: 0,
+                weight: 1,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let payer_pubkey = context.payer.pubkey();
+    test_pack_set
+        .delete_card(
+            &mut context,
+            &test_pack_card,
+            &payer_pubkey,
+            &card_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    // Pack config is initialized without rare card trees and keeps its layout
+    let result = test_pack_set
+        .set_pity_rule(&mut context, Some(PITY_RULE))
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PackConfigInitialized, 0);
+}
+
+#[tokio::test]
+async fn fail_set_for_max_supply() {
+    let (mut context, test_pack_set) = init_pack(PackDistributionType::MaxSupply).await;
+
+    let result = test_pack_set
+        .set_pity_rule(&mut context, Some(PITY_RULE))
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PityRuleWithMaxSupply, 0);
+}
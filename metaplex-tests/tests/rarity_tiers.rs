@@ -0,0 +1,256 @@
+mod utils;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    instruction::{AddCardToPackArgs, InitPackSetArgs, NFTPacksInstruction},
+    state::{PackConfig, PackDistributionType, RandomnessSource, RarityTier},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::TransactionError,
+    transport::{self, TransportError},
+};
+use utils::*;
+
+async fn setup() -> (ProgramTestContext, TestPackSet) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date: None,
+                redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    (context, test_pack_set)
+}
+
+async fn add_card(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    index: u32,
+    tier: Option<u8>,
+) -> transport::Result<(TestPackCard, User)> {
+    let test_metadata = TestMetadata::new();
+    let test_master_edition = TestMasterEditionV2::new(&test_metadata);
+
+    let user_token_acc = Keypair::new();
+    let user = User {
+        owner: Keypair::new(),
+        token_account: user_token_acc.pubkey(),
+    };
+
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            &user_token_acc,
+            &test_pack_set.authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    test_master_edition.create(context, Some(10)).await.unwrap();
+
+    let test_pack_card = TestPackCard::new(test_pack_set, index);
+    test_pack_set
+        .add_card(
+            context,
+            &test_pack_card,
+            &test_master_edition,
+            &test_metadata,
+            &user,
+            AddCardToPackArgs {
+                max_supply: 5,
+                weight: 100,
+                index: test_pack_card.index,
+                tier,
+            },
+        )
+        .await?;
+
+    Ok((test_pack_card, user))
+}
+
+fn tiers() -> Vec<RarityTier> {
+    vec![
+        RarityTier { probability: 9500 },
+        RarityTier { probability: 500 },
+    ]
+}
+
+#[test]
+fn decode_args_without_tier() {
+    let args = AddCardToPackArgs {
+        max_supply: 5,
+        weight: 100,
+        index: 1,
+        tier: None,
+    };
+    let mut data = NFTPacksInstruction::AddCardToPack(args.clone())
+        .try_to_vec()
+        .unwrap();
+
+    // Clients built before rarity tiers were added don't send tier at all
+    assert_eq!(data.pop(), Some(0));
+    match NFTPacksInstruction::try_from_slice(&data).unwrap() {
+        NFTPacksInstruction::AddCardToPack(decoded) => assert_eq!(decoded, args),
+        _ => panic!("wrong instruction"),
+    }
+
+    // Tier is still decoded when it's passed
+    let args = AddCardToPackArgs {
+        tier: Some(1),
+        ..args
+    };
+    let data = args.try_to_vec().unwrap();
+    assert_eq!(AddCardToPackArgs::try_from_slice(&data).unwrap(), args);
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_pack_set) = setup().await;
+
+    test_pack_set
+        .set_rarity_tiers(&mut context, tiers())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.rarity_tiers, tiers());
+
+    add_card(&mut context, &test_pack_set, 1, Some(0))
+        .await
+        .unwrap();
+    add_card(&mut context, &test_pack_set, 2, Some(1))
+        .await
+        .unwrap();
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 2);
+    let pack_card = test_pack_card.get_data(&mut context).await;
+    assert_eq!(pack_card.tier, Some(1));
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(pack_config.tiers(), 2);
+    assert_eq!(pack_config.get_card_tier(1).unwrap(), 0);
+    assert_eq!(pack_config.get_card_tier(2).unwrap(), 1);
+}
+
+#[tokio::test]
+async fn fail_wrong_probabilities() {
+    let (mut context, test_pack_set) = setup().await;
+
+    let result = test_pack_set
+        .set_rarity_tiers(
+            &mut context,
+            vec![
+                RarityTier { probability: 9000 },
+                RarityTier { probability: 500 },
+            ],
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRarityTiers, 0);
+}
+
+#[tokio::test]
+async fn fail_card_without_tier() {
+    let (mut context, test_pack_set) = setup().await;
+
+    test_pack_set
+        .set_rarity_tiers(&mut context, tiers())
+        .await
+        .unwrap();
+
+    let result = add_card(&mut context, &test_pack_set, 1, None).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRarityTier, 0);
+}
+
+#[tokio::test]
+async fn fail_unknown_tier() {
+    let (mut context, test_pack_set) = setup().await;
+
+    test_pack_set
+        .set_rarity_tiers(&mut context, tiers())
+        .await
+        .unwrap();
+
+    let result = add_card(&mut context, &test_pack_set, 1, Some(2)).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongRarityTier, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_cards_added() {
+    let (mut context, test_pack_set) = setup().await;
+
+    add_card(&mut context, &test_pack_set, 1, None)
+        .await
+        .unwrap();
+
+    let result = test_pack_set.set_rarity_tiers(&mut context, tiers()).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PackSetHasCards, 0);
+}
+
+#[tokio::test]
+async fn fail_set_after_cards_deleted() {
+    let (mut context, test_pack_set) = setup().await;
+
+    let (test_pack_card, user) = add_card(&mut context, &test_pack_set, 1, None)
+        .await
+        .unwrap();
+
+    let payer_pubkey = context.payer.pubkey();
+    test_pack_set
+        .delete_card(
+            &mut context,
+            &test_pack_card,
+            &payer_pubkey,
+            &user.token_account,
+        )
+        .await
+        .unwrap();
+
+    // Pack config is initialized without tiers and keeps its layout
+    let result = test_pack_set.set_rarity_tiers(&mut context, tiers()).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::PackConfigInitialized, 0);
+}
@@ -2,9 +2,9 @@ mod utils;
 
 use metaplex_nft_packs::{
     error::NFTPacksError,
-    find_proving_process_program_address,
-    instruction::{AddCardToPackArgs, InitPackSetArgs},
-    state::{PackDistributionType, ProvingProcess},
+    find_proving_process_program_address, find_wallet_record_program_address,
+    instruction::{self, AddCardToPackArgs, InitPackSetArgs},
+    state::{PackConfig, PackDistributionType, ProvingProcess, RandomnessSource},
 };
 use num_traits::FromPrimitive;
 use solana_program::{instruction::InstructionError, program_pack::Pack, system_instruction};
@@ -17,39 +17,6 @@ use solana_sdk::{
 };
 use utils::*;
 
-async fn create_master_edition(
-    context: &mut ProgramTestContext,
-    test_pack_set: &TestPackSet,
-) -> (TestMetadata, TestMasterEditionV2, User) {
-    let test_metadata = TestMetadata::new();
-    let test_master_edition = TestMasterEditionV2::new(&test_metadata);
-
-    let user_token_acc = Keypair::new();
-    let master_token_holder = User {
-        owner: Keypair::new(),
-        token_account: user_token_acc.pubkey(),
-    };
-
-    test_metadata
-        .create(
-            context,
-            "Test".to_string(),
-            "TST".to_string(),
-            "uri".to_string(),
-            None,
-            10,
-            false,
-            &user_token_acc,
-            &test_pack_set.authority.pubkey(),
-        )
-        .await
-        .unwrap();
-
-    test_master_edition.create(context, Some(10)).await.unwrap();
-
-    (test_metadata, test_master_edition, master_token_holder)
-}
-
 #[tokio::test]
 async fn success() {
     let mut context = nft_packs_program_test().start_with_context().await;
@@ -81,16 +48,20 @@ async fn success() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -133,6 +104,7 @@ async fn success() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -152,7 +124,6 @@ async fn success() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -217,19 +188,23 @@ async fn success_two_cards() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (card_metadata2, card_master_edition2, card_master_token_holder2) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -272,6 +247,7 @@ async fn success_two_cards() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -289,6 +265,7 @@ async fn success_two_cards() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card2.index,
+                tier: None,
             },
         )
         .await
@@ -308,8 +285,6 @@ async fn success_two_cards() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -345,7 +320,7 @@ async fn success_two_cards() {
 }
 
 #[tokio::test]
-async fn fail_request_without_clean_up() {
+async fn success_request_twice_without_clean_up() {
     let mut context = nft_packs_program_test().start_with_context().await;
 
     let name = [7; 32];
@@ -375,16 +350,20 @@ async fn fail_request_without_clean_up() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
 
@@ -427,6 +406,7 @@ async fn fail_request_without_clean_up() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -446,7 +426,6 @@ async fn fail_request_without_clean_up() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -468,7 +447,7 @@ async fn fail_request_without_clean_up() {
     // do wrap to update state
     context.warp_to_slot(5).unwrap();
 
-    let result = test_pack_set
+    test_pack_set
         .request_card_for_redeem(
             &mut context,
             &store_key,
@@ -479,15 +458,26 @@ async fn fail_request_without_clean_up() {
             &test_randomness_oracle.keypair.pubkey(),
             1,
         )
-        .await;
+        .await
+        .unwrap();
 
-    assert_transport_error!(
-        result.unwrap_err(),
-        TransportError::TransactionError(TransactionError::InstructionError(
-            0,
-            InstructionError::IllegalOwner
-        ))
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
     );
+    let proving_process_data = get_account(&mut context, &proving_process_key).await;
+    let proving_process = ProvingProcess::unpack(&proving_process_data.data).unwrap();
+
+    assert_eq!(proving_process.cards_to_redeem.get(&1), Some(&2));
+
+    // supply is decremented right on draw
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+
+    assert_eq!(pack_config.get_card(1).unwrap(), (1, 100, 3));
 }
 
 #[tokio::test]
@@ -521,19 +511,23 @@ async fn fail_request_after_end_date() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (card_metadata2, card_master_edition2, card_master_token_holder2) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
     let edition_authority = Keypair::new();
@@ -575,6 +569,7 @@ async fn fail_request_after_end_date() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -592,6 +587,7 @@ async fn fail_request_after_end_date() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card2.index,
+                tier: None,
             },
         )
         .await
@@ -610,8 +606,6 @@ async fn fail_request_after_end_date() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -674,19 +668,23 @@ async fn fail_request_with_invalid_voucher() {
                 allowed_amount_to_redeem: 10,
                 redeem_start_date,
                 redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
             },
         )
         .await
         .unwrap();
 
     let (card_metadata, card_master_edition, card_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (card_metadata2, card_master_edition2, card_master_token_holder2) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
-        create_master_edition(&mut context, &test_pack_set).await;
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
 
     let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
     let edition_authority = Keypair::new();
@@ -728,6 +726,7 @@ async fn fail_request_with_invalid_voucher() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card.index,
+                tier: None,
             },
         )
         .await
@@ -745,6 +744,7 @@ async fn fail_request_with_invalid_voucher() {
                 max_supply: 5,
                 weight: 100,
                 index: test_pack_card2.index,
+                tier: None,
             },
         )
         .await
@@ -763,8 +763,6 @@ async fn fail_request_with_invalid_voucher() {
         .unwrap();
 
     test_pack_set.activate(&mut context).await.unwrap();
-    test_pack_set.clean_up(&mut context).await.unwrap();
-
     let mut test_randomness_oracle = TestRandomnessOracle::new();
     test_randomness_oracle.init(&mut context).await.unwrap();
     test_randomness_oracle.update(&mut context).await.unwrap();
@@ -801,3 +799,62 @@ async fn fail_request_with_invalid_voucher() {
         ))
     );
 }
+
+#[tokio::test]
+async fn success_without_wallet_record() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let voucher_edition = &test_pack.voucher_editions[0];
+    let mut request_ix = instruction::request_card_for_redeem(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &test_pack.test_pack_set.pack_config.pubkey(),
+        &test_pack.test_pack_set.store,
+        &voucher_edition.new_edition_pubkey,
+        &voucher_edition.mint.pubkey(),
+        &test_pack.edition_authority.pubkey(),
+        &Some(voucher_edition.token.pubkey()),
+        &test_pack.test_randomness_oracle.keypair.pubkey(),
+        1,
+        None,
+    );
+
+    // Accounts passed by clients built before wallet record was added
+    let (wallet_record_key, _) = find_wallet_record_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &test_pack.edition_authority.pubkey(),
+    );
+    let wallet_record_meta = request_ix.accounts.pop().unwrap();
+    assert_eq!(wallet_record_meta.pubkey, wallet_record_key);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[request_ix],
+        Some(&test_pack.context.payer.pubkey()),
+        &[&test_pack.context.payer, &test_pack.edition_authority],
+        test_pack.context.last_blockhash,
+    );
+    test_pack
+        .context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &test_pack.edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+    let proving_process_data = get_account(&mut test_pack.context, &proving_process_key).await;
+    let proving_process = ProvingProcess::unpack(&proving_process_data.data).unwrap();
+    assert_eq!(proving_process.draw_counter, 1);
+    assert!(is_empty_account(&mut test_pack.context, &wallet_record_key).await);
+}
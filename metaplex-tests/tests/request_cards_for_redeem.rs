@@ -0,0 +1,302 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_proving_process_program_address,
+    instruction::{self, AddCardToPackArgs, InitPackSetArgs},
+    state::{
+        AccountType, CleanUpActions, LegacyPackConfig, PackConfig, PackDistributionType,
+        ProvingProcess, RandomnessSource,
+    },
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const CARD_MAX_SUPPLY: u32 = 5;
+const ALLOWED_AMOUNT_TO_REDEEM: u32 = 3;
+
+async fn setup() -> (
+    ProgramTestContext,
+    TestPackSet,
+    TestPackCard,
+    TestEditionMarker,
+    Keypair,
+    TestRandomnessOracle,
+) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let redeem_start_date = Some(clock.unix_timestamp as u64);
+    let redeem_end_date = Some(redeem_start_date.unwrap() + 100);
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: ALLOWED_AMOUNT_TO_REDEEM,
+                redeem_start_date,
+                redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply: CARD_MAX_SUPPLY,
+                weight: 100,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.activate(&mut context).await.unwrap();
+    let mut test_randomness_oracle = TestRandomnessOracle::new();
+    test_randomness_oracle.init(&mut context).await.unwrap();
+    test_randomness_oracle.update(&mut context).await.unwrap();
+
+    (
+        context,
+        test_pack_set,
+        test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_oracle,
+    )
+}
+
+#[tokio::test]
+async fn success() {
+    let (
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_oracle,
+    ) = setup().await;
+
+    test_pack_set
+        .request_cards_for_redeem(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+            ALLOWED_AMOUNT_TO_REDEEM,
+        )
+        .await
+        .unwrap();
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+    let proving_process_data = get_account(&mut context, &proving_process_key).await;
+    let proving_process = ProvingProcess::unpack_from_slice(&proving_process_data.data).unwrap();
+    assert_eq!(
+        proving_process.cards_to_redeem.get(&test_pack_card.index),
+        Some(&ALLOWED_AMOUNT_TO_REDEEM)
+    );
+
+    // Every draw is applied without CleanUp
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(
+        pack_set.total_editions,
+        (CARD_MAX_SUPPLY - ALLOWED_AMOUNT_TO_REDEEM) as u64
+    );
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(
+        pack_config.get_card(test_pack_card.index).unwrap(),
+        (
+            test_pack_card.index,
+            100,
+            CARD_MAX_SUPPLY - ALLOWED_AMOUNT_TO_REDEEM
+        )
+    );
+}
+
+#[tokio::test]
+async fn fail_count_more_than_allowed() {
+    let (
+        mut context,
+        test_pack_set,
+        _test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_oracle,
+    ) = setup().await;
+
+    let result = test_pack_set
+        .request_cards_for_redeem(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+            ALLOWED_AMOUNT_TO_REDEEM + 1,
+        )
+        .await;
+
+    assert_transport_error!(
+        result.unwrap_err(),
+        TransportError::TransactionError(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidArgument
+        ))
+    );
+}
+
+#[tokio::test]
+async fn fail_multiple_draws_with_legacy_pack_config() {
+    let (
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_oracle,
+    ) = setup().await;
+
+    let pack_config_key = test_pack_set
+        .set_legacy_pack_config(
+            &mut context,
+            LegacyPackConfig {
+                account_type: AccountType::PackConfig,
+                weights: vec![(test_pack_card.index, 100, CARD_MAX_SUPPLY)],
+                action_to_do: CleanUpActions::None,
+            },
+        )
+        .await;
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction::request_cards_for_redeem(
+            &metaplex_nft_packs::id(),
+            &test_pack_set.keypair.pubkey(),
+            &pack_config_key,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority.pubkey(),
+            &Some(voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+            2,
+            None,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+    let result = context.banks_client.process_transaction(tx).await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::MultipleDrawsWithLegacyPackConfig,
+        0
+    );
+
+    // Voucher isn't burned and proving process isn't created
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+    assert!(is_empty_account(&mut context, &proving_process_key).await);
+}
@@ -0,0 +1,224 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackConfig, PackDistributionType, PackSetState, RandomnessSource},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::{self, TransportError},
+};
+use utils::*;
+
+struct TestPack {
+    context: ProgramTestContext,
+    test_pack_set: TestPackSet,
+    voucher_edition: TestEditionMarker,
+    edition_authority: Keypair,
+}
+
+async fn add_card(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    index: u32,
+    max_supply: u32,
+    weight: u16,
+) -> transport::Result<()> {
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(context, test_pack_set, Some(10)).await;
+
+    let test_pack_card = TestPackCard::new(test_pack_set, index);
+    test_pack_set
+        .add_card(
+            context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply,
+                weight,
+                index,
+                tier: None,
+            },
+        )
+        .await
+}
+
+// Activated and then deactivated pack with one card
+async fn setup(mutable: bool) -> TestPack {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name: [7; 32],
+                uri: String::from("some link to storage"),
+                description: String::from("Pack description"),
+                mutable,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date: Some(clock.unix_timestamp as u64),
+                redeem_end_date: None,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    add_card(&mut context, &test_pack_set, 1, 5, 100)
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.activate(&mut context).await.unwrap();
+    test_pack_set.deactivate(&mut context).await.unwrap();
+
+    TestPack {
+        context,
+        test_pack_set,
+        voucher_edition,
+        edition_authority,
+    }
+}
+
+#[tokio::test]
+async fn success() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        voucher_edition,
+        edition_authority,
+    } = setup(true).await;
+
+    add_card(&mut context, &test_pack_set, 2, 3, 50)
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_cards, 2);
+    assert_eq!(pack_set.total_editions, 8);
+    assert_eq!(pack_set.total_weight, 150);
+    assert_eq!(pack_set.restocks, 1);
+
+    let mut pack_config_data = test_pack_set.get_config_data(&mut context).await;
+    let pack_config =
+        PackConfig::load(&mut pack_config_data, &test_pack_set.keypair.pubkey()).unwrap();
+    assert_eq!(pack_config.get_card(2).unwrap(), (2, 50, 3));
+
+    test_pack_set.activate(&mut context).await.unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.pack_state, PackSetState::Activated);
+    assert_eq!(pack_set.restocks, 1);
+
+    let mut test_randomness_oracle = TestRandomnessOracle::new();
+    test_randomness_oracle.init(&mut context).await.unwrap();
+    test_randomness_oracle.update(&mut context).await.unwrap();
+
+    test_pack_set
+        .request_card_for_redeem(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            &test_randomness_oracle.keypair.pubkey(),
+            1,
+        )
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.total_editions, 7);
+}
+
+#[tokio::test]
+async fn fail_activated() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        ..
+    } = setup(true).await;
+
+    test_pack_set.activate(&mut context).await.unwrap();
+
+    let result = add_card(&mut context, &test_pack_set, 2, 3, 50).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 1);
+}
+
+#[tokio::test]
+async fn fail_immutable() {
+    let TestPack {
+        mut context,
+        test_pack_set,
+        ..
+    } = setup(false).await;
+
+    let result = add_card(&mut context, &test_pack_set, 2, 3, 50).await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::ImmutablePackSet, 1);
+}
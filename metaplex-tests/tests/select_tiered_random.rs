@@ -0,0 +1,191 @@
+use metaplex_nft_packs::{
+    state::{CardFilter, PackConfig, RarityTier},
+    utils::next_random_value,
+};
+use solana_program::pubkey::Pubkey;
+use std::collections::BTreeMap;
+
+const SAMPLES: u64 = 200_000;
+
+// Cards are (weight, tier), card index is position + 1
+fn pack_config_data(
+    cards: &[(u32, u8)],
+    tiers_amount: usize,
+    rare_weight_threshold: Option<u32>,
+) -> Vec<u8> {
+    let mut data = vec![
+        0;
+        PackConfig::get_len(
+            cards.len().max(1) as u32,
+            tiers_amount,
+            rare_weight_threshold.is_some()
+        )
+    ];
+    let mut config = PackConfig::init(
+        &mut data,
+        &Pubkey::default(),
+        tiers_amount,
+        rare_weight_threshold,
+    )
+    .unwrap();
+
+    for (i, (weight, tier)) in cards.iter().enumerate() {
+        config
+            .add_card(i as u32 + 1, *weight, 5, Some(*tier))
+            .unwrap();
+    }
+
+    data
+}
+
+fn load(data: &mut [u8]) -> PackConfig<'_> {
+    PackConfig::load(data, &Pubkey::default()).unwrap()
+}
+
+fn tiers(probabilities: &[u16]) -> Vec<RarityTier> {
+    probabilities
+        .iter()
+        .map(|probability| RarityTier {
+            probability: *probability,
+        })
+        .collect()
+}
+
+// Checks that count is within 5 standard deviations of expected binomial value
+fn assert_proportional(count: u64, weight: u64, weight_sum: u64) {
+    let p = weight as f64 / weight_sum as f64;
+    let expected = SAMPLES as f64 * p;
+    let deviation = (SAMPLES as f64 * p * (1.0 - p)).sqrt();
+
+    assert!(
+        (count as f64 - expected).abs() <= 5.0 * deviation + 1.0,
+        "count {} is too far from expected {}",
+        count,
+        expected
+    );
+}
+
+fn sample(config: &PackConfig, tiers: &[RarityTier], filter: &CardFilter) -> BTreeMap<u32, u64> {
+    let mut counts = BTreeMap::new();
+    let mut random_value = 42;
+
+    for _ in 0..SAMPLES {
+        random_value = next_random_value(random_value);
+        let selected = config
+            .select_tiered_random_filtered(random_value, tiers, filter)
+            .unwrap();
+        *counts.entry(selected.0).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+#[test]
+fn tier_probability_doesnt_depend_on_card_weights() {
+    let mut data = pack_config_data(&[(1000, 0), (1000, 0), (1, 1), (3, 1)], 2, None);
+    let config = load(&mut data);
+    let tiers = tiers(&[9500, 500]);
+
+    let counts = sample(&config, &tiers, &CardFilter::default());
+
+    assert_proportional(counts[&1] + counts[&2], 9500, 10000);
+    assert_proportional(counts[&3] + counts[&4], 500, 10000);
+
+    // Within tier cards are selected with their weights
+    assert_proportional(counts[&1], 4750, 10000);
+    assert_proportional(counts[&3], 125, 10000);
+    assert_proportional(counts[&4], 375, 10000);
+}
+
+#[test]
+fn empty_tier_is_skipped() {
+    // Card of second tier is out of supply
+    let mut data = pack_config_data(&[(10, 0), (0, 1), (10, 2)], 3, None);
+    let config = load(&mut data);
+    let tiers = tiers(&[6000, 3000, 1000]);
+
+    let counts = sample(&config, &tiers, &CardFilter::default());
+
+    assert_eq!(counts.get(&2), None);
+    assert_proportional(counts[&1], 6000, 7000);
+    assert_proportional(counts[&3], 1000, 7000);
+}
+
+#[test]
+fn filtered_tiers_are_renormalized() {
+    let mut data = pack_config_data(&[(10, 0), (10, 1), (10, 2)], 3, None);
+    let config = load(&mut data);
+    let tiers = tiers(&[7000, 2000, 1000]);
+
+    let filter = CardFilter {
+        tiers: Some(0b110),
+        ..CardFilter::default()
+    };
+    let counts = sample(&config, &tiers, &filter);
+
+    assert_eq!(counts.get(&1), None);
+    assert_proportional(counts[&2], 2000, 3000);
+    assert_proportional(counts[&3], 1000, 3000);
+}
+
+#[test]
+fn rare_only_cards_are_selected() {
+    let mut data = pack_config_data(&[(100, 0), (3, 0), (50, 1), (1, 1)], 2, Some(5));
+    let config = load(&mut data);
+    let tiers = tiers(&[5000, 5000]);
+
+    let filter = CardFilter {
+        rare_only: true,
+        ..CardFilter::default()
+    };
+    assert!(config.has_cards(&filter).unwrap());
+
+    let counts = sample(&config, &tiers, &filter);
+
+    assert_eq!(counts.get(&1), None);
+    assert_eq!(counts.get(&3), None);
+    assert_proportional(counts[&2], 1, 2);
+    assert_proportional(counts[&4], 1, 2);
+}
+
+#[test]
+fn selection_is_deterministic() {
+    let mut data = pack_config_data(&[(10, 0), (10, 1), (10, 1)], 2, None);
+    let config = load(&mut data);
+    let tiers = tiers(&[5000, 5000]);
+
+    let mut random_value = 7;
+    for _ in 0..100 {
+        random_value = next_random_value(random_value);
+        assert_eq!(
+            config.select_tiered_random(random_value, &tiers).unwrap(),
+            config.select_tiered_random(random_value, &tiers).unwrap()
+        );
+    }
+}
+
+#[test]
+fn fail_filtered_tiers_empty() {
+    // Only card of first tier is left
+    let mut data = pack_config_data(&[(10, 0), (0, 1)], 2, None);
+    let config = load(&mut data);
+
+    let filter = CardFilter {
+        tiers: Some(0b10),
+        ..CardFilter::default()
+    };
+    assert!(!config.has_cards(&filter).unwrap());
+    assert!(config
+        .select_tiered_random_filtered(0, &tiers(&[5000, 5000]), &filter)
+        .is_err());
+}
+
+#[test]
+fn fail_all_tiers_empty() {
+    let mut data = pack_config_data(&[(0, 0), (0, 1)], 2, None);
+    let config = load(&mut data);
+
+    assert!(config
+        .select_tiered_random(0, &tiers(&[5000, 5000]))
+        .is_err());
+}
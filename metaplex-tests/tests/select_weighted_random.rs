@@ -0,0 +1,345 @@
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    state::{
+        AccountType, CardFilter, CleanUpActions, LegacyPackConfig, PackConfig,
+        PackDistributionType, MAX_PACK_CARDS_AMOUNT,
+    },
+    utils::{next_random_value, random_in_range},
+};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+const SAMPLES: u64 = 200_000;
+
+fn pack_config_data(weights: &[u32]) -> Vec<u8> {
+    let mut data = vec![0; PackConfig::get_len(weights.len().max(1) as u32, 0, false)];
+    let mut config = PackConfig::init(&mut data, &Pubkey::default(), 0, None).unwrap();
+
+    for (i, weight) in weights.iter().enumerate() {
+        config.add_card(i as u32 + 1, *weight, 1, None).unwrap();
+    }
+
+    data
+}
+
+fn load(data: &mut [u8]) -> PackConfig<'_> {
+    PackConfig::load(data, &Pubkey::default()).unwrap()
+}
+
+// Checks that count is within 5 standard deviations of expected binomial value
+fn assert_proportional(count: u64, weight: u64, weight_sum: u64) {
+    let p = weight as f64 / weight_sum as f64;
+    let expected = SAMPLES as f64 * p;
+    let deviation = (SAMPLES as f64 * p * (1.0 - p)).sqrt();
+
+    assert!(
+        (count as f64 - expected).abs() <= 5.0 * deviation + 1.0,
+        "count {} is too far from expected {}",
+        count,
+        expected
+    );
+}
+
+fn sample(config: &PackConfig, filter: &CardFilter) -> Vec<u64> {
+    let mut counts = vec![0u64; config.cards() as usize];
+    let mut random_value = 42;
+
+    for _ in 0..SAMPLES {
+        random_value = next_random_value(random_value);
+        let selected = config
+            .select_weighted_random_filtered(random_value, filter)
+            .unwrap();
+        counts[selected.0 as usize - 1] += 1;
+    }
+
+    counts
+}
+
+#[test]
+fn random_in_range_bounds() {
+    for range in [1, 2, 3, 7, 100, u32::MAX as u64, u64::MAX].iter().copied() {
+        assert_eq!(random_in_range(u64::MAX, range).unwrap(), range - 1);
+
+        let mut random_value = range;
+        for _ in 0..1000 {
+            random_value = next_random_value(random_value);
+            assert!(random_in_range(random_value, range).unwrap() < range);
+        }
+    }
+
+    assert_eq!(random_in_range(0, 2).unwrap(), 0);
+    assert_eq!(
+        random_in_range(1, 0).unwrap_err(),
+        ProgramError::InvalidArgument
+    );
+}
+
+#[test]
+fn random_in_range_uniform() {
+    let range = 6;
+    let mut counts = vec![0u64; range as usize];
+    let mut random_value = 7;
+
+    for _ in 0..SAMPLES {
+        random_value = next_random_value(random_value);
+        counts[random_in_range(random_value, range).unwrap() as usize] += 1;
+    }
+
+    for count in counts {
+        assert_proportional(count, 1, range);
+    }
+}
+
+#[test]
+fn selection_is_proportional_to_weights() {
+    let weights = [1, 10, 100, 500, 389];
+    let weight_sum = weights.iter().map(|i| *i as u64).sum();
+    let mut data = pack_config_data(&weights);
+    let config = load(&mut data);
+
+    let counts = sample(&config, &CardFilter::default());
+
+    for (count, weight) in counts.iter().zip(weights.iter()) {
+        assert_proportional(*count, *weight as u64, weight_sum);
+    }
+}
+
+#[test]
+fn first_entry_has_no_extra_odds() {
+    let mut data = pack_config_data(&[1, 1]);
+    let config = load(&mut data);
+
+    // Lowest value selects first entry and highest value selects the last one
+    assert_eq!(config.select_weighted_random_exact(0).unwrap().0, 1);
+    assert_eq!(
+        config.select_weighted_random_exact(u64::MAX / 2).unwrap().0,
+        1
+    );
+    assert_eq!(
+        config
+            .select_weighted_random_exact(u64::MAX / 2 + 1)
+            .unwrap()
+            .0,
+        2
+    );
+    assert_eq!(config.select_weighted_random_exact(u64::MAX).unwrap().0, 2);
+
+    let counts = sample(&config, &CardFilter::default());
+    assert_proportional(counts[0], 1, 2);
+    assert_proportional(counts[1], 1, 2);
+}
+
+#[test]
+fn zero_weight_is_never_selected() {
+    let mut data = pack_config_data(&[0, 3, 0, 1]);
+    let config = load(&mut data);
+
+    assert_eq!(config.cards(), 4);
+    assert_eq!(config.cards_left(), 2);
+
+    let counts = sample(&config, &CardFilter::default());
+
+    assert_eq!(counts[0], 0);
+    assert_eq!(counts[2], 0);
+    assert_proportional(counts[1], 3, 4);
+    assert_proportional(counts[3], 1, 4);
+}
+
+#[test]
+fn excluded_card_is_never_selected() {
+    let mut data = pack_config_data(&[1, 10, 2, 3]);
+    let config = load(&mut data);
+
+    let filter = CardFilter {
+        excluded_cards: vec![2],
+        ..CardFilter::default()
+    };
+    assert_eq!(config.count_cards_left(&filter.excluded_cards).unwrap(), 3);
+
+    let counts = sample(&config, &filter);
+
+    assert_eq!(counts[1], 0);
+    assert_proportional(counts[0], 1, 6);
+    assert_proportional(counts[2], 2, 6);
+    assert_proportional(counts[3], 3, 6);
+}
+
+#[test]
+fn card_out_of_supply_is_removed() {
+    let mut data = pack_config_data(&[1000, 1]);
+    let mut config = load(&mut data);
+
+    // Fixed distribution keeps weight until max supply runs out
+    config
+        .decrement_supply(1, &PackDistributionType::Fixed)
+        .unwrap();
+    assert_eq!(config.get_card(1).unwrap(), (1, 0, 0));
+    assert_eq!(config.cards_left(), 1);
+
+    let mut random_value = 42;
+    for _ in 0..1000 {
+        random_value = next_random_value(random_value);
+        assert_eq!(
+            config.select_weighted_random_exact(random_value).unwrap().0,
+            2
+        );
+    }
+
+    config
+        .decrement_supply(2, &PackDistributionType::Unlimited)
+        .unwrap();
+    assert_eq!(config.get_card(2).unwrap(), (2, 1, 1));
+
+    config
+        .decrement_supply(2, &PackDistributionType::Fixed)
+        .unwrap();
+    assert_eq!(
+        config.select_weighted_random_exact(0).unwrap_err(),
+        NFTPacksError::MissingEditionsInPack.into()
+    );
+}
+
+#[test]
+fn max_supply_value_is_decremented() {
+    let mut data = pack_config_data(&[2, 6]);
+    let mut config = load(&mut data);
+
+    config
+        .decrement_supply(2, &PackDistributionType::MaxSupply)
+        .unwrap();
+    config
+        .decrement_supply(2, &PackDistributionType::MaxSupply)
+        .unwrap();
+    assert_eq!(config.get_card(2).unwrap(), (2, 4, 1));
+
+    let counts = sample(&config, &CardFilter::default());
+    assert_proportional(counts[0], 2, 6);
+    assert_proportional(counts[1], 4, 6);
+}
+
+#[test]
+fn large_pack() {
+    let weights = (1..=MAX_PACK_CARDS_AMOUNT).collect::<Vec<u32>>();
+    let weight_sum = weights.iter().map(|i| *i as u64).sum::<u64>();
+    let mut data = pack_config_data(&weights);
+    let mut config = load(&mut data);
+
+    assert_eq!(config.capacity(), MAX_PACK_CARDS_AMOUNT);
+    assert_eq!(
+        config
+            .add_card(MAX_PACK_CARDS_AMOUNT + 1, 1, 1, None)
+            .unwrap_err(),
+        NFTPacksError::PackIsFullWithCards.into()
+    );
+
+    // Cumulative weight of first card is 1 and of the last one is weight_sum
+    assert_eq!(
+        config
+            .select_weighted_random_exact(u64::MAX / weight_sum)
+            .unwrap()
+            .0,
+        1
+    );
+    assert_eq!(
+        config.select_weighted_random_exact(u64::MAX).unwrap().0,
+        MAX_PACK_CARDS_AMOUNT
+    );
+
+    // Remove every odd card
+    for index in (1..=MAX_PACK_CARDS_AMOUNT).step_by(2) {
+        config
+            .decrement_supply(index, &PackDistributionType::Fixed)
+            .unwrap();
+    }
+    assert_eq!(config.cards_left(), MAX_PACK_CARDS_AMOUNT / 2);
+
+    let mut random_value = 42;
+    for _ in 0..1000 {
+        random_value = next_random_value(random_value);
+        let (index, weight, _) = config.select_weighted_random_exact(random_value).unwrap();
+        assert_eq!(index % 2, 0);
+        assert_eq!(weight, index);
+    }
+}
+
+#[test]
+fn legacy_selection_walks_sorted_weights() {
+    let mut data = pack_config_data(&[10, 30, 30, 20]);
+    let config = load(&mut data);
+
+    // Sorted weights are [(2, 30), (3, 30), (4, 20), (1, 10)] with sum 90
+    let legacy_config = LegacyPackConfig {
+        account_type: AccountType::PackConfig,
+        weights: vec![(2, 30, 1), (3, 30, 1), (4, 20, 1), (1, 10, 1)],
+        action_to_do: CleanUpActions::None,
+    };
+
+    let cases = [
+        // Random value scaled to 0, 31 and 90
+        (0, 90, 2),
+        (22574, 90, 3),
+        (u16::MAX, 90, 1),
+        // Pack set total isn't recalculated when cards run out, random value is scaled to 90 of 180
+        (32768, 90, 3),
+        (32768, 180, 1),
+        // Without weight sum random value is divided by number of cards
+        (8, 0, 2),
+        (u16::MAX, 0, 1),
+    ];
+    for (rand, weight_sum, index) in cases.iter().copied() {
+        assert_eq!(
+            config.select_weighted_random(rand, weight_sum).unwrap().0,
+            index
+        );
+        assert_eq!(
+            legacy_config
+                .select_weighted_random(rand, weight_sum)
+                .unwrap()
+                .0,
+            index
+        );
+    }
+}
+
+#[test]
+fn legacy_pack_config_clean_up_actions() {
+    let mut legacy_config = LegacyPackConfig {
+        account_type: AccountType::PackConfig,
+        weights: vec![(1, 5, 1), (2, 5, 1), (3, 5, 1), (4, 1, 1)],
+        action_to_do: CleanUpActions::Change(1, 4),
+    };
+
+    assert_eq!(
+        legacy_config.assert_cleaned_up().unwrap_err(),
+        NFTPacksError::WeightsNotCleanedUp.into()
+    );
+
+    // Card is moved behind all the cards with greater weight
+    legacy_config.change_weight(1, 4).unwrap();
+    assert_eq!(
+        legacy_config.weights,
+        vec![(2, 5, 1), (3, 5, 1), (1, 4, 1), (4, 1, 1)]
+    );
+
+    legacy_config.change_supply(4, 0).unwrap();
+    legacy_config.remove_at(4);
+    assert_eq!(legacy_config.weights, vec![(2, 5, 1), (3, 5, 1), (1, 4, 1)]);
+    assert_eq!(
+        legacy_config.change_weight(4, 1).unwrap_err(),
+        NFTPacksError::InvalidWeightPosition.into()
+    );
+
+    legacy_config.action_to_do = CleanUpActions::None;
+    legacy_config.assert_cleaned_up().unwrap();
+
+    let mut data = vec![0; LegacyPackConfig::LEN];
+    LegacyPackConfig::pack(legacy_config.clone(), &mut data).unwrap();
+    assert_eq!(LegacyPackConfig::unpack(&data).unwrap(), legacy_config);
+}
+
+#[test]
+fn fail_empty_weights() {
+    let mut data = pack_config_data(&[]);
+    let config = load(&mut data);
+
+    assert!(config.select_weighted_random_exact(0).is_err());
+}
@@ -0,0 +1,138 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    instruction::InitPackSetArgs,
+    state::{PackDistributionType, RandomnessSource, TreasuryRecipient},
+};
+use num_traits::FromPrimitive;
+use solana_program::instruction::InstructionError;
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair, signer::Signer, transaction::TransactionError, transport::TransportError,
+};
+use utils::*;
+
+async fn setup() -> (ProgramTestContext, TestPackSet) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date: None,
+                redeem_end_date: None,
+                price: Some(1000000),
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    (context, test_pack_set)
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_pack_set) = setup().await;
+
+    let recipients = vec![
+        TreasuryRecipient {
+            address: Keypair::new().pubkey(),
+            share: 2500,
+        },
+        TreasuryRecipient {
+            address: Keypair::new().pubkey(),
+            share: 500,
+        },
+    ];
+
+    test_pack_set
+        .set_treasury_recipients(&mut context, recipients.clone())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.treasury_recipients, recipients);
+
+    context.warp_to_slot(3).unwrap();
+
+    // Recipients list can be shrunk
+    test_pack_set
+        .set_treasury_recipients(&mut context, recipients[..1].to_vec())
+        .await
+        .unwrap();
+
+    let pack_set = test_pack_set.get_data(&mut context).await;
+    assert_eq!(pack_set.treasury_recipients, recipients[..1].to_vec());
+}
+
+#[tokio::test]
+async fn fail_wrong_shares() {
+    let (mut context, test_pack_set) = setup().await;
+
+    let result = test_pack_set
+        .set_treasury_recipients(
+            &mut context,
+            vec![
+                TreasuryRecipient {
+                    address: Keypair::new().pubkey(),
+                    share: 7500,
+                },
+                TreasuryRecipient {
+                    address: Keypair::new().pubkey(),
+                    share: 5000,
+                },
+            ],
+        )
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongTreasuryShares, 0);
+}
+
+#[tokio::test]
+async fn fail_duplicate_recipient() {
+    let (mut context, test_pack_set) = setup().await;
+
+    let recipient = Keypair::new().pubkey();
+
+    let result = test_pack_set
+        .set_treasury_recipients(
+            &mut context,
+            vec![
+                TreasuryRecipient {
+                    address: recipient,
+                    share: 1000,
+                },
+                TreasuryRecipient {
+                    address: recipient,
+                    share: 1000,
+                },
+            ],
+        )
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::DuplicateTreasuryRecipient,
+        0
+    );
+}
@@ -0,0 +1,282 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_proving_process_program_address,
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackDistributionType, ProvingProcess, RandomnessSource},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const CARD_MAX_SUPPLY: u32 = 5;
+const ALLOWED_AMOUNT_TO_REDEEM: u32 = 3;
+const VALUE: [u8; 32] = [42; 32];
+
+async fn setup() -> (
+    ProgramTestContext,
+    TestPackSet,
+    TestPackCard,
+    TestEditionMarker,
+    Keypair,
+    TestRandomnessSigner,
+) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let clock = context.banks_client.get_clock().await.unwrap();
+
+    let redeem_start_date = Some(clock.unix_timestamp as u64);
+    let redeem_end_date = Some(redeem_start_date.unwrap() + 100);
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_randomness_signer = TestRandomnessSigner::new();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: ALLOWED_AMOUNT_TO_REDEEM,
+                redeem_start_date,
+                redeem_end_date,
+                price: None,
+                payment_mint: None,
+                randomness_source: RandomnessSource::SignedValue {
+                    signer: test_randomness_signer.pubkey(),
+                },
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let (card_metadata, card_master_edition, card_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+        create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+    let voucher_edition = TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, 1);
+
+    let edition_authority = Keypair::new();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::create_account(
+            &context.payer.pubkey(),
+            &edition_authority.pubkey(),
+            100000000000000,
+            0,
+            &solana_program::system_program::id(),
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &edition_authority],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    voucher_edition
+        .create(
+            &mut context,
+            &edition_authority,
+            &test_pack_set.authority,
+            &voucher_master_token_holder.token_account,
+        )
+        .await
+        .unwrap();
+
+    let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+    test_pack_set
+        .add_card(
+            &mut context,
+            &test_pack_card,
+            &card_master_edition,
+            &card_metadata,
+            &card_master_token_holder,
+            AddCardToPackArgs {
+                max_supply: CARD_MAX_SUPPLY,
+                weight: 100,
+                index: test_pack_card.index,
+                tier: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+    test_pack_set
+        .add_voucher(
+            &mut context,
+            &test_pack_voucher,
+            &voucher_master_edition,
+            &voucher_metadata,
+            &voucher_master_token_holder,
+        )
+        .await
+        .unwrap();
+
+    test_pack_set.activate(&mut context).await.unwrap();
+    (
+        context,
+        test_pack_set,
+        test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_signer,
+    )
+}
+
+#[tokio::test]
+async fn success() {
+    let (
+        mut context,
+        test_pack_set,
+        test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_signer,
+    ) = setup().await;
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+
+    test_pack_set
+        .request_card_for_redeem_with_signed_value(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            test_randomness_signer.sign(&proving_process_key, 0, &VALUE),
+            1,
+        )
+        .await
+        .unwrap();
+
+    let proving_process_data = get_account(&mut context, &proving_process_key).await;
+    let proving_process = ProvingProcess::unpack_from_slice(&proving_process_data.data).unwrap();
+    assert_eq!(proving_process.draw_counter, 1);
+    assert_eq!(
+        proving_process.cards_to_redeem.get(&test_pack_card.index),
+        Some(&1)
+    );
+}
+
+#[tokio::test]
+async fn fail_wrong_signer() {
+    let (
+        mut context,
+        test_pack_set,
+        _test_pack_card,
+        voucher_edition,
+        edition_authority,
+        _test_randomness_signer,
+    ) = setup().await;
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+
+    let wrong_signer = TestRandomnessSigner::new();
+
+    let result = test_pack_set
+        .request_card_for_redeem_with_signed_value(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            wrong_signer.sign(&proving_process_key, 0, &VALUE),
+            1,
+        )
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::SignedRandomValueMissing,
+        1
+    );
+}
+
+#[tokio::test]
+async fn fail_replay_signed_value() {
+    let (
+        mut context,
+        test_pack_set,
+        _test_pack_card,
+        voucher_edition,
+        edition_authority,
+        test_randomness_signer,
+    ) = setup().await;
+
+    let (proving_process_key, _) = find_proving_process_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack_set.keypair.pubkey(),
+        &edition_authority.pubkey(),
+        &voucher_edition.mint.pubkey(),
+    );
+
+    test_pack_set
+        .request_card_for_redeem_with_signed_value(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &Some(voucher_edition.token.pubkey()),
+            test_randomness_signer.sign(&proving_process_key, 0, &VALUE),
+            1,
+        )
+        .await
+        .unwrap();
+
+    // Value signed for the first draw can't be used for the second one
+    let result = test_pack_set
+        .request_card_for_redeem_with_signed_value(
+            &mut context,
+            &test_pack_set.store,
+            &voucher_edition.new_edition_pubkey,
+            &voucher_edition.mint.pubkey(),
+            &edition_authority,
+            &None,
+            test_randomness_signer.sign(&proving_process_key, 0, &[7; 32]),
+            1,
+        )
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::SignedRandomValueMissing,
+        1
+    );
+}
@@ -9,6 +9,8 @@ mod pack_card;
 mod pack_set;
 mod pack_voucher;
 mod randomness_oracle;
+mod randomness_signer;
+mod test_pack;
 mod user;
 mod vault;
 
@@ -22,6 +24,7 @@ pub use pack_card::TestPackCard;
 pub use pack_set::TestPackSet;
 pub use pack_voucher::TestPackVoucher;
 pub use randomness_oracle::TestRandomnessOracle;
+pub use randomness_signer::TestRandomnessSigner;
 use solana_program_test::*;
 use solana_sdk::{
     account::Account, program_pack::Pack, pubkey::Pubkey, signature::Signer,
@@ -29,6 +32,7 @@ use solana_sdk::{
 };
 use spl_token::state::Mint;
 use std::time;
+pub use test_pack::*;
 pub use user::*;
 pub use vault::TestVault;
 
@@ -1,20 +1,37 @@
 use crate::*;
 use metaplex_nft_packs::{
-    instruction::{self, EditPackSetArgs},
-    state::PackSet,
+    find_gate_usage_program_address, find_pack_config_program_address,
+    instruction::{
+        self, ClaimCardAccounts, CommitRandomnessArgs, DeletePackCardArgs, DeletePackVoucherArgs,
+        EditPackCardArgs, EditPackSetArgs, OpenPackCardAccounts, RevealRandomnessArgs,
+        SetAllowlistRootArgs, SetHolderGateArgs, SetPackPhasesArgs, SetPackSlotsArgs,
+        SetPityRuleArgs, SetRarityTiersArgs, SetTreasuryRecipientsArgs, SetWalletVoucherLimitArgs,
+    },
+    state::{
+        AllowlistProof, HolderGate, LegacyPackConfig, PackConfig, PackPhase, PackSet, PackSlot,
+        PityRule, RandomnessVersion, RarityTier, TreasuryRecipient, MAX_RARITY_TIERS,
+    },
 };
 use solana_program::{
-    instruction::AccountMeta, program_pack::Pack, pubkey::Pubkey, system_instruction,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, sysvar,
 };
 use solana_program_test::*;
 use solana_sdk::{
-    signature::Signer, signer::keypair::Keypair, transaction::Transaction, transport,
+    account::AccountSharedData, signature::Signer, signer::keypair::Keypair,
+    transaction::Transaction, transport,
 };
 use spl_token::state::Account;
 
+/// Amount of cards pack config account created in tests can hold
+pub const TEST_PACK_CONFIG_CAPACITY: u32 = 20;
+
 #[derive(Debug)]
 pub struct TestPackSet {
     pub keypair: Keypair,
+    pub pack_config: Keypair,
     pub authority: Keypair,
     pub minting_authority: Keypair,
     pub store: Pubkey,
@@ -25,6 +42,7 @@ impl TestPackSet {
     pub fn new(store: Pubkey) -> Self {
         Self {
             keypair: Keypair::new(),
+            pack_config: Keypair::new(),
             authority: Keypair::new(),
             minting_authority: Keypair::new(),
             store,
@@ -36,6 +54,46 @@ impl TestPackSet {
         PackSet::unpack_unchecked(&account.data).unwrap()
     }
 
+    pub async fn get_config_data(&self, context: &mut ProgramTestContext) -> Vec<u8> {
+        get_account(context, &self.pack_config.pubkey()).await.data
+    }
+
+    /// Turn pack set into one created before pack config key was stored,
+    /// its `LegacyPackConfig` is written to the pack config PDA
+    pub async fn set_legacy_pack_config(
+        &self,
+        context: &mut ProgramTestContext,
+        pack_config: LegacyPackConfig,
+    ) -> Pubkey {
+        let mut pack_set_account = get_account(context, &self.keypair.pubkey()).await;
+        let mut pack_set = PackSet::unpack_unchecked(&pack_set_account.data).unwrap();
+        pack_set.pack_config = Pubkey::default();
+        pack_set.randomness_version = RandomnessVersion::V0;
+        PackSet::pack(pack_set, &mut pack_set_account.data).unwrap();
+        context.set_account(
+            &self.keypair.pubkey(),
+            &AccountSharedData::from(pack_set_account),
+        );
+
+        let (pack_config_key, _) =
+            find_pack_config_program_address(&metaplex_nft_packs::id(), &self.keypair.pubkey());
+        let mut data = vec![0; LegacyPackConfig::LEN];
+        LegacyPackConfig::pack(pack_config, &mut data).unwrap();
+        let rent = context.banks_client.get_rent().await.unwrap();
+        context.set_account(
+            &pack_config_key,
+            &AccountSharedData::create(
+                rent.minimum_balance(data.len()),
+                data,
+                metaplex_nft_packs::id(),
+                false,
+                0,
+            ),
+        );
+
+        pack_config_key
+    }
+
     pub async fn init(
         &self,
         context: &mut ProgramTestContext,
@@ -43,8 +101,20 @@ impl TestPackSet {
     ) -> transport::Result<()> {
         create_account::<PackSet>(context, &self.keypair, &metaplex_nft_packs::id()).await?;
 
+        // Big enough for test cards with any rarity tiers and pity rule
+        let pack_config_len =
+            PackConfig::get_len(TEST_PACK_CONFIG_CAPACITY, MAX_RARITY_TIERS, true);
+        let rent = context.banks_client.get_rent().await.unwrap();
+
         let tx = Transaction::new_signed_with_payer(
             &[
+                system_instruction::create_account(
+                    &context.payer.pubkey(),
+                    &self.pack_config.pubkey(),
+                    rent.minimum_balance(pack_config_len),
+                    pack_config_len as u64,
+                    &metaplex_nft_packs::id(),
+                ),
                 // Transfer a few lamports to cover fee for create account
                 system_instruction::transfer(
                     &context.payer.pubkey(),
@@ -54,6 +124,7 @@ impl TestPackSet {
                 instruction::init_pack(
                     &metaplex_nft_packs::id(),
                     &self.keypair.pubkey(),
+                    &self.pack_config.pubkey(),
                     &self.authority.pubkey(),
                     &self.store,
                     &self.minting_authority.pubkey(),
@@ -61,26 +132,13 @@ impl TestPackSet {
                 ),
             ],
             Some(&context.payer.pubkey()),
-            &[&context.payer, &self.authority],
+            &[&context.payer, &self.pack_config, &self.authority],
             context.last_blockhash,
         );
 
         context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn clean_up(&self, context: &mut ProgramTestContext) -> transport::Result<()> {
-        let tx = Transaction::new_signed_with_payer(
-            &[instruction::clean_up(
-                &metaplex_nft_packs::id(),
-                &self.keypair.pubkey(),
-            )],
-            Some(&context.payer.pubkey()),
-            &[&context.payer],
-            context.last_blockhash,
-        );
-        context.banks_client.process_transaction(tx).await
-    }
-
     pub async fn add_card(
         &self,
         context: &mut ProgramTestContext,
@@ -103,6 +161,7 @@ impl TestPackSet {
                 instruction::add_card_to_pack(
                     &metaplex_nft_packs::id(),
                     &self.keypair.pubkey(),
+                    &self.pack_config.pubkey(),
                     &self.authority.pubkey(),
                     &test_master_edition.pubkey,
                     &test_metadata.pubkey,
@@ -170,6 +229,20 @@ impl TestPackSet {
         context.banks_client.process_transaction(tx).await
     }
 
+    pub async fn clean_up(&self, context: &mut ProgramTestContext) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::clean_up(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
     pub async fn transfer_pack_authority(
         &self,
         context: &mut ProgramTestContext,
@@ -193,22 +266,14 @@ impl TestPackSet {
     pub async fn edit(
         &self,
         context: &mut ProgramTestContext,
-        mutable: Option<bool>,
-        name: Option<[u8; 32]>,
-        description: Option<String>,
-        uri: Option<String>,
+        args: EditPackSetArgs,
     ) -> transport::Result<()> {
         let tx = Transaction::new_signed_with_payer(
             &[instruction::edit_pack(
                 &metaplex_nft_packs::id(),
                 &self.keypair.pubkey(),
                 &self.authority.pubkey(),
-                EditPackSetArgs {
-                    mutable,
-                    name,
-                    description,
-                    uri,
-                },
+                args,
             )],
             Some(&context.payer.pubkey()),
             &[&self.authority, &context.payer],
@@ -225,15 +290,20 @@ impl TestPackSet {
         refunder: &Pubkey,
         new_master_edition_owner_token_acc: &Pubkey,
     ) -> transport::Result<()> {
+        let last_index = self.get_data(context).await.pack_cards;
         let tx = Transaction::new_signed_with_payer(
             &[instruction::delete_pack_card(
                 &metaplex_nft_packs::id(),
                 &self.keypair.pubkey(),
-                &test_pack_card.pubkey,
+                &Some(self.pack_config.pubkey()),
                 &self.authority.pubkey(),
                 refunder,
                 new_master_edition_owner_token_acc,
                 &test_pack_card.token_account.pubkey(),
+                last_index,
+                DeletePackCardArgs {
+                    index: Some(test_pack_card.index),
+                },
             )],
             Some(&context.payer.pubkey()),
             &[&self.authority, &context.payer],
@@ -269,14 +339,39 @@ impl TestPackSet {
         test_pack_voucher: &TestPackVoucher,
         refunder: &Pubkey,
     ) -> transport::Result<()> {
+        let delete_voucher_ix = self
+            .delete_voucher_instruction(context, test_pack_voucher, refunder, &None)
+            .await;
         let tx = Transaction::new_signed_with_payer(
-            &[instruction::delete_pack_voucher(
-                &metaplex_nft_packs::id(),
-                &self.keypair.pubkey(),
-                &test_pack_voucher.pubkey,
-                &self.authority.pubkey(),
+            &[delete_voucher_ix],
+            Some(&context.payer.pubkey()),
+            &[&self.authority, &context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn delete_voucher_in_custody(
+        &self,
+        context: &mut ProgramTestContext,
+        test_pack_voucher: &TestPackVoucher,
+        refunder: &Pubkey,
+        new_master_edition_owner_token_acc: &Pubkey,
+    ) -> transport::Result<()> {
+        let delete_voucher_ix = self
+            .delete_voucher_instruction(
+                context,
+                test_pack_voucher,
                 refunder,
-            )],
+                &Some((
+                    *new_master_edition_owner_token_acc,
+                    test_pack_voucher.token_account.pubkey(),
+                )),
+            )
+            .await;
+        let tx = Transaction::new_signed_with_payer(
+            &[delete_voucher_ix],
             Some(&context.payer.pubkey()),
             &[&self.authority, &context.payer],
             context.last_blockhash,
@@ -285,6 +380,33 @@ impl TestPackSet {
         context.banks_client.process_transaction(tx).await
     }
 
+    async fn delete_voucher_instruction(
+        &self,
+        context: &mut ProgramTestContext,
+        test_pack_voucher: &TestPackVoucher,
+        refunder: &Pubkey,
+        master_token_accounts: &Option<(Pubkey, Pubkey)>,
+    ) -> Instruction {
+        let last_index = self.get_data(context).await.pack_vouchers;
+        let last_test_pack_voucher = TestPackVoucher::new(self, last_index);
+        let voucher_master = test_pack_voucher.get_data(context).await.master;
+        let last_voucher_master = last_test_pack_voucher.get_data(context).await.master;
+
+        instruction::delete_pack_voucher(
+            &metaplex_nft_packs::id(),
+            &self.keypair.pubkey(),
+            &self.authority.pubkey(),
+            refunder,
+            master_token_accounts,
+            &voucher_master,
+            &last_voucher_master,
+            last_index,
+            DeletePackVoucherArgs {
+                index: Some(test_pack_voucher.index),
+            },
+        )
+    }
+
     pub async fn add_voucher(
         &self,
         context: &mut ProgramTestContext,
@@ -315,6 +437,52 @@ impl TestPackSet {
                     &test_master_edition.mint_pubkey,
                     &user.token_account,
                     &self.store,
+                    &None,
+                ),
+            ],
+            Some(&context.payer.pubkey()),
+            &[
+                &context.payer,
+                &test_pack_voucher.token_account,
+                &self.authority,
+            ],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn add_voucher_in_custody(
+        &self,
+        context: &mut ProgramTestContext,
+        test_pack_voucher: &TestPackVoucher,
+        test_master_edition: &TestMasterEditionV2,
+        test_metadata: &TestMetadata,
+        user: &User,
+    ) -> transport::Result<()> {
+        let rent = context.banks_client.get_rent().await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                system_instruction::create_account(
+                    &context.payer.pubkey(),
+                    &test_pack_voucher.token_account.pubkey(),
+                    rent.minimum_balance(Account::LEN),
+                    Account::LEN as u64,
+                    &spl_token::id(),
+                ),
+                instruction::add_voucher_to_pack(
+                    &metaplex_nft_packs::id(),
+                    &self.keypair.pubkey(),
+                    &test_pack_voucher.pubkey,
+                    &self.authority.pubkey(),
+                    &self.authority.pubkey(),
+                    &test_master_edition.pubkey,
+                    &test_metadata.pubkey,
+                    &test_master_edition.mint_pubkey,
+                    &user.token_account,
+                    &self.store,
+                    &Some(test_pack_voucher.token_account.pubkey()),
                 ),
             ],
             Some(&context.payer.pubkey()),
@@ -344,6 +512,7 @@ impl TestPackSet {
             &[instruction::request_card_for_redeem(
                 &metaplex_nft_packs::id(),
                 &self.keypair.pubkey(),
+                &self.pack_config.pubkey(),
                 store,
                 edition,
                 edition_mint,
@@ -351,6 +520,7 @@ impl TestPackSet {
                 user_token_acc,
                 random_oracle,
                 voucher_index,
+                None,
             )],
             Some(&context.payer.pubkey()),
             &[&context.payer, user_wallet],
@@ -360,7 +530,7 @@ impl TestPackSet {
         context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn request_card_for_redeem_fake_voucher(
+    pub async fn request_card_for_redeem_with_allowlist_proof(
         &self,
         context: &mut ProgramTestContext,
         store: &Pubkey,
@@ -370,24 +540,65 @@ impl TestPackSet {
         user_token_acc: &Option<Pubkey>,
         random_oracle: &Pubkey,
         voucher_index: u32,
+        allowlist_proof: Option<AllowlistProof>,
     ) -> transport::Result<()> {
-        let mut ix = instruction::request_card_for_redeem(
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::request_card_for_redeem(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.pack_config.pubkey(),
+                store,
+                edition,
+                edition_mint,
+                &user_wallet.pubkey(),
+                user_token_acc,
+                random_oracle,
+                voucher_index,
+                allowlist_proof,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn request_card_for_redeem_with_gate(
+        &self,
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        edition: &Pubkey,
+        edition_mint: &Pubkey,
+        user_wallet: &Keypair,
+        user_token_acc: &Pubkey,
+        random_oracle: &Pubkey,
+        voucher_index: u32,
+        gate_token_acc: &Pubkey,
+        gate_mint: &Pubkey,
+    ) -> transport::Result<()> {
+        let mut request_card_ix = instruction::request_card_for_redeem(
             &metaplex_nft_packs::id(),
             &self.keypair.pubkey(),
+            &self.pack_config.pubkey(),
             store,
             edition,
             edition_mint,
             &user_wallet.pubkey(),
-            user_token_acc,
+            &Some(*user_token_acc),
             random_oracle,
             voucher_index,
+            None,
         );
-
-        let fake_pack_voucher = Pubkey::new_unique();
-        ix.accounts[5] = AccountMeta::new_readonly(fake_pack_voucher, false);
+        request_card_ix
+            .accounts
+            .push(AccountMeta::new(*gate_token_acc, false));
+        request_card_ix
+            .accounts
+            .push(AccountMeta::new(*gate_mint, false));
 
         let tx = Transaction::new_signed_with_payer(
-            &[ix],
+            &[request_card_ix],
             Some(&context.payer.pubkey()),
             &[&context.payer, user_wallet],
             context.last_blockhash,
@@ -396,85 +607,797 @@ impl TestPackSet {
         context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn claim_pack(
+    pub async fn request_card_for_redeem_with_holder_gate(
         &self,
         context: &mut ProgramTestContext,
+        store: &Pubkey,
+        edition: &Pubkey,
+        edition_mint: &Pubkey,
         user_wallet: &Keypair,
-        voucher_mint: &Pubkey,
-        master_token_account: &Pubkey,
-        master_edition_account: &Pubkey,
-        new_mint: &Keypair,
-        new_mint_token_acc: &Keypair,
-        new_mint_authority: &Keypair,
-        master_metadata: &Pubkey,
-        master_mint: &Pubkey,
-        randomness_oracle: &Pubkey,
-        index: u32,
+        user_token_acc: &Pubkey,
+        random_oracle: &Pubkey,
+        voucher_index: u32,
+        gate_nft_token_acc: &Pubkey,
+        gate_nft_metadata: &Pubkey,
+        gate_nft_mint: &Pubkey,
     ) -> transport::Result<()> {
-        create_mint(context, new_mint, &new_mint_authority.pubkey(), None)
-            .await
-            .unwrap();
-        create_token_account(
-            context,
-            new_mint_token_acc,
-            &new_mint.pubkey(),
-            &user_wallet.pubkey(),
-        )
-        .await
-        .unwrap();
-        mint_tokens(
-            context,
-            &new_mint.pubkey(),
-            &new_mint_token_acc.pubkey(),
-            1,
-            &new_mint_authority.pubkey(),
-            Some(vec![new_mint_authority]),
-        )
-        .await
-        .unwrap();
+        let (gate_usage, _) = find_gate_usage_program_address(
+            &metaplex_nft_packs::id(),
+            &self.keypair.pubkey(),
+            gate_nft_mint,
+        );
 
-        let mint_key = new_mint.pubkey();
-        let spl_token_metadata_key = metaplex_token_metadata::id();
+        let mut request_card_ix = instruction::request_card_for_redeem(
+            &metaplex_nft_packs::id(),
+            &self.keypair.pubkey(),
+            &self.pack_config.pubkey(),
+            store,
+            edition,
+            edition_mint,
+            &user_wallet.pubkey(),
+            &Some(*user_token_acc),
+            random_oracle,
+            voucher_index,
+            None,
+        );
+        request_card_ix
+            .accounts
+            .push(AccountMeta::new_readonly(*gate_nft_token_acc, false));
+        request_card_ix
+            .accounts
+            .push(AccountMeta::new_readonly(*gate_nft_metadata, false));
+        request_card_ix
+            .accounts
+            .push(AccountMeta::new(gate_usage, false));
 
-        let metadata_seeds = &[
-            metaplex_token_metadata::state::PREFIX.as_bytes(),
-            spl_token_metadata_key.as_ref(),
-            mint_key.as_ref(),
-        ];
-        let (new_metadata_pubkey, _) =
-            Pubkey::find_program_address(metadata_seeds, &metaplex_token_metadata::id());
+        let tx = Transaction::new_signed_with_payer(
+            &[request_card_ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
 
-        let master_edition_seeds = &[
-            metaplex_token_metadata::state::PREFIX.as_bytes(),
-            spl_token_metadata_key.as_ref(),
-            mint_key.as_ref(),
-            metaplex_token_metadata::state::EDITION.as_bytes(),
-        ];
-        let (new_edition_pubkey, _) =
-            Pubkey::find_program_address(master_edition_seeds, &metaplex_token_metadata::id());
+        context.banks_client.process_transaction(tx).await
+    }
 
+    pub async fn request_card_for_redeem_with_signed_value(
+        &self,
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        edition: &Pubkey,
+        edition_mint: &Pubkey,
+        user_wallet: &Keypair,
+        user_token_acc: &Option<Pubkey>,
+        signed_value: Instruction,
+        voucher_index: u32,
+    ) -> transport::Result<()> {
         let tx = Transaction::new_signed_with_payer(
-            &[instruction::claim_pack(
-                &metaplex_nft_packs::id(),
-                &self.keypair.pubkey(),
-                &user_wallet.pubkey(),
-                voucher_mint,
-                master_token_account,
-                &new_metadata_pubkey,
-                &new_edition_pubkey,
-                master_edition_account,
-                &new_mint.pubkey(),
-                &new_mint_authority.pubkey(),
-                master_metadata,
-                master_mint,
-                randomness_oracle,
-                index,
-            )],
-            Some(&context.payer.pubkey()),
+            &[
+                signed_value,
+                instruction::request_card_for_redeem(
+                    &metaplex_nft_packs::id(),
+                    &self.keypair.pubkey(),
+                    &self.pack_config.pubkey(),
+                    store,
+                    edition,
+                    edition_mint,
+                    &user_wallet.pubkey(),
+                    user_token_acc,
+                    &sysvar::instructions::id(),
+                    voucher_index,
+                    None,
+                ),
+            ],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn request_cards_for_redeem(
+        &self,
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        edition: &Pubkey,
+        edition_mint: &Pubkey,
+        user_wallet: &Keypair,
+        user_token_acc: &Option<Pubkey>,
+        random_oracle: &Pubkey,
+        voucher_index: u32,
+        count: u32,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::request_cards_for_redeem(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.pack_config.pubkey(),
+                store,
+                edition,
+                edition_mint,
+                &user_wallet.pubkey(),
+                user_token_acc,
+                random_oracle,
+                voucher_index,
+                count,
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn request_card_for_redeem_fake_voucher(
+        &self,
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        edition: &Pubkey,
+        edition_mint: &Pubkey,
+        user_wallet: &Keypair,
+        user_token_acc: &Option<Pubkey>,
+        random_oracle: &Pubkey,
+        voucher_index: u32,
+    ) -> transport::Result<()> {
+        let mut ix = instruction::request_card_for_redeem(
+            &metaplex_nft_packs::id(),
+            &self.keypair.pubkey(),
+            &self.pack_config.pubkey(),
+            store,
+            edition,
+            edition_mint,
+            &user_wallet.pubkey(),
+            user_token_acc,
+            random_oracle,
+            voucher_index,
+            None,
+        );
+
+        let fake_pack_voucher = Pubkey::new_unique();
+        ix.accounts[5] = AccountMeta::new_readonly(fake_pack_voucher, false);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn claim_pack(
+        &self,
+        context: &mut ProgramTestContext,
+        user_wallet: &Keypair,
+        voucher_mint: &Pubkey,
+        master_token_account: &Pubkey,
+        master_edition_account: &Pubkey,
+        new_mint: &Keypair,
+        new_mint_token_acc: &Keypair,
+        new_mint_authority: &Keypair,
+        master_metadata: &Pubkey,
+        master_mint: &Pubkey,
+        randomness_oracle: &Pubkey,
+        index: u32,
+    ) -> transport::Result<()> {
+        create_mint(context, new_mint, &new_mint_authority.pubkey(), None)
+            .await
+            .unwrap();
+        create_token_account(
+            context,
+            new_mint_token_acc,
+            &new_mint.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await
+        .unwrap();
+        mint_tokens(
+            context,
+            &new_mint.pubkey(),
+            &new_mint_token_acc.pubkey(),
+            1,
+            &new_mint_authority.pubkey(),
+            Some(vec![new_mint_authority]),
+        )
+        .await
+        .unwrap();
+
+        let mint_key = new_mint.pubkey();
+        let spl_token_metadata_key = metaplex_token_metadata::id();
+
+        let metadata_seeds = &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            spl_token_metadata_key.as_ref(),
+            mint_key.as_ref(),
+        ];
+        let (new_metadata_pubkey, _) =
+            Pubkey::find_program_address(metadata_seeds, &metaplex_token_metadata::id());
+
+        let master_edition_seeds = &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            spl_token_metadata_key.as_ref(),
+            mint_key.as_ref(),
+            metaplex_token_metadata::state::EDITION.as_bytes(),
+        ];
+        let (new_edition_pubkey, _) =
+            Pubkey::find_program_address(master_edition_seeds, &metaplex_token_metadata::id());
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::claim_pack(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &user_wallet.pubkey(),
+                voucher_mint,
+                master_token_account,
+                &new_metadata_pubkey,
+                &new_edition_pubkey,
+                master_edition_account,
+                &new_mint.pubkey(),
+                &new_mint_authority.pubkey(),
+                master_metadata,
+                master_mint,
+                randomness_oracle,
+                index,
+            )],
+            Some(&context.payer.pubkey()),
             &[&context.payer, user_wallet, &new_mint_authority],
             context.last_blockhash,
         );
 
         context.banks_client.process_transaction(tx).await
     }
+
+    pub async fn claim_packs(
+        &self,
+        context: &mut ProgramTestContext,
+        user_wallet: &Keypair,
+        voucher_mint: &Pubkey,
+        cards: &[(&TestPackCard, &TestMasterEditionV2, &TestMetadata)],
+    ) -> transport::Result<()> {
+        let mut cards_accounts = Vec::new();
+        let mut master_supplies: Vec<(Pubkey, u64)> = Vec::new();
+
+        for (test_pack_card, master_edition, master_metadata) in cards {
+            let new_mint = Keypair::new();
+            let new_mint_token_acc = Keypair::new();
+
+            create_mint(context, &new_mint, &user_wallet.pubkey(), None)
+                .await
+                .unwrap();
+            create_token_account(
+                context,
+                &new_mint_token_acc,
+                &new_mint.pubkey(),
+                &user_wallet.pubkey(),
+            )
+            .await
+            .unwrap();
+            mint_tokens(
+                context,
+                &new_mint.pubkey(),
+                &new_mint_token_acc.pubkey(),
+                1,
+                &user_wallet.pubkey(),
+                Some(vec![user_wallet]),
+            )
+            .await
+            .unwrap();
+
+            let mint_key = new_mint.pubkey();
+            let spl_token_metadata_key = metaplex_token_metadata::id();
+
+            let metadata_seeds = &[
+                metaplex_token_metadata::state::PREFIX.as_bytes(),
+                spl_token_metadata_key.as_ref(),
+                mint_key.as_ref(),
+            ];
+            let (new_metadata_pubkey, _) =
+                Pubkey::find_program_address(metadata_seeds, &metaplex_token_metadata::id());
+
+            let master_edition_seeds = &[
+                metaplex_token_metadata::state::PREFIX.as_bytes(),
+                spl_token_metadata_key.as_ref(),
+                mint_key.as_ref(),
+                metaplex_token_metadata::state::EDITION.as_bytes(),
+            ];
+            let (new_edition_pubkey, _) =
+                Pubkey::find_program_address(master_edition_seeds, &metaplex_token_metadata::id());
+
+            // Editions of the same master are minted one by one
+            let edition = match master_supplies
+                .iter_mut()
+                .find(|(master, _)| *master == master_edition.pubkey)
+            {
+                Some((_, supply)) => {
+                    *supply += 1;
+                    *supply
+                }
+                None => {
+                    let supply = master_edition.get_data(context).await.supply + 1;
+                    master_supplies.push((master_edition.pubkey, supply));
+                    supply
+                }
+            };
+
+            cards_accounts.push(ClaimCardAccounts {
+                index: test_pack_card.index,
+                token_account: test_pack_card.token_account.pubkey(),
+                new_mint: new_mint.pubkey(),
+                new_metadata: new_metadata_pubkey,
+                new_edition: new_edition_pubkey,
+                master_edition: master_edition.pubkey,
+                metadata: master_metadata.pubkey,
+                metadata_mint: master_edition.mint_pubkey,
+                edition,
+            });
+        }
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::claim_packs(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &user_wallet.pubkey(),
+                voucher_mint,
+                &user_wallet.pubkey(),
+                &cards_accounts,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn buy_pack(
+        &self,
+        context: &mut ProgramTestContext,
+        buyer: &Keypair,
+        test_pack_voucher: &TestPackVoucher,
+        master_edition: &TestMasterEditionV2,
+        master_metadata: &TestMetadata,
+        new_mint: &Keypair,
+        new_mint_token_acc: &Keypair,
+        payment: &Option<(Pubkey, Pubkey)>,
+    ) -> transport::Result<()> {
+        create_mint(context, new_mint, &buyer.pubkey(), None)
+            .await
+            .unwrap();
+        create_token_account(
+            context,
+            new_mint_token_acc,
+            &new_mint.pubkey(),
+            &buyer.pubkey(),
+        )
+        .await
+        .unwrap();
+        mint_tokens(
+            context,
+            &new_mint.pubkey(),
+            &new_mint_token_acc.pubkey(),
+            1,
+            &buyer.pubkey(),
+            Some(vec![buyer]),
+        )
+        .await
+        .unwrap();
+
+        let mint_key = new_mint.pubkey();
+        let spl_token_metadata_key = metaplex_token_metadata::id();
+
+        let metadata_seeds = &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            spl_token_metadata_key.as_ref(),
+            mint_key.as_ref(),
+        ];
+        let (new_metadata_pubkey, _) =
+            Pubkey::find_program_address(metadata_seeds, &metaplex_token_metadata::id());
+
+        let master_edition_seeds = &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            spl_token_metadata_key.as_ref(),
+            mint_key.as_ref(),
+            metaplex_token_metadata::state::EDITION.as_bytes(),
+        ];
+        let (new_edition_pubkey, _) =
+            Pubkey::find_program_address(master_edition_seeds, &metaplex_token_metadata::id());
+
+        let edition = master_edition.get_data(context).await.supply + 1;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::buy_pack(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &buyer.pubkey(),
+                &test_pack_voucher.token_account.pubkey(),
+                &new_metadata_pubkey,
+                &new_edition_pubkey,
+                &master_edition.pubkey,
+                &new_mint.pubkey(),
+                &buyer.pubkey(),
+                &master_metadata.pubkey,
+                &master_edition.mint_pubkey,
+                edition,
+                test_pack_voucher.index,
+                payment,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, buyer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_treasury_recipients(
+        &self,
+        context: &mut ProgramTestContext,
+        recipients: Vec<TreasuryRecipient>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_treasury_recipients(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                SetTreasuryRecipientsArgs { recipients },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn withdraw_treasury(
+        &self,
+        context: &mut ProgramTestContext,
+        recipients: &[Pubkey],
+        payment: &Option<(Pubkey, Pubkey)>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::withdraw_treasury(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                recipients,
+                payment,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn open_pack(
+        &self,
+        context: &mut ProgramTestContext,
+        store: &Pubkey,
+        edition: &Pubkey,
+        edition_mint: &Pubkey,
+        user_wallet: &Keypair,
+        user_token_acc: &Pubkey,
+        random_oracle: &Pubkey,
+        new_mint: &Keypair,
+        new_mint_token_acc: &Keypair,
+        voucher_index: u32,
+        cards: &[OpenPackCardAccounts],
+    ) -> transport::Result<()> {
+        create_mint(context, new_mint, &user_wallet.pubkey(), None)
+            .await
+            .unwrap();
+        create_token_account(
+            context,
+            new_mint_token_acc,
+            &new_mint.pubkey(),
+            &user_wallet.pubkey(),
+        )
+        .await
+        .unwrap();
+        mint_tokens(
+            context,
+            &new_mint.pubkey(),
+            &new_mint_token_acc.pubkey(),
+            1,
+            &user_wallet.pubkey(),
+            Some(vec![user_wallet]),
+        )
+        .await
+        .unwrap();
+
+        let mint_key = new_mint.pubkey();
+        let spl_token_metadata_key = metaplex_token_metadata::id();
+
+        let metadata_seeds = &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            spl_token_metadata_key.as_ref(),
+            mint_key.as_ref(),
+        ];
+        let (new_metadata_pubkey, _) =
+            Pubkey::find_program_address(metadata_seeds, &metaplex_token_metadata::id());
+
+        let master_edition_seeds = &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            spl_token_metadata_key.as_ref(),
+            mint_key.as_ref(),
+            metaplex_token_metadata::state::EDITION.as_bytes(),
+        ];
+        let (new_edition_pubkey, _) =
+            Pubkey::find_program_address(master_edition_seeds, &metaplex_token_metadata::id());
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::open_pack(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.pack_config.pubkey(),
+                store,
+                edition,
+                edition_mint,
+                &user_wallet.pubkey(),
+                user_token_acc,
+                random_oracle,
+                &new_metadata_pubkey,
+                &new_edition_pubkey,
+                &new_mint.pubkey(),
+                &user_wallet.pubkey(),
+                voucher_index,
+                cards,
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user_wallet],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn commit_randomness(
+        &self,
+        context: &mut ProgramTestContext,
+        commitment: [u8; 32],
+    ) -> transport::Result<()> {
+        let round_index = self.get_data(context).await.randomness_rounds;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::commit_randomness(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                round_index,
+                CommitRandomnessArgs { commitment },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn reveal_randomness(
+        &self,
+        context: &mut ProgramTestContext,
+        round_index: u32,
+        secret: [u8; 32],
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::reveal_randomness(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                round_index,
+                RevealRandomnessArgs { secret },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn resolve_draws(
+        &self,
+        context: &mut ProgramTestContext,
+        proving_process: &Pubkey,
+        user_wallet: &Pubkey,
+        round_index: u32,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::resolve_draws(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.pack_config.pubkey(),
+                proving_process,
+                user_wallet,
+                round_index,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_rarity_tiers(
+        &self,
+        context: &mut ProgramTestContext,
+        tiers: Vec<RarityTier>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_rarity_tiers(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                &self.pack_config.pubkey(),
+                SetRarityTiersArgs { tiers },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_pack_slots(
+        &self,
+        context: &mut ProgramTestContext,
+        slots: Vec<PackSlot>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_pack_slots(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                SetPackSlotsArgs { slots },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_pack_phases(
+        &self,
+        context: &mut ProgramTestContext,
+        phases: Vec<PackPhase>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_pack_phases(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                SetPackPhasesArgs { phases },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_allowlist_root(
+        &self,
+        context: &mut ProgramTestContext,
+        root: Option<[u8; 32]>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_allowlist_root(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                SetAllowlistRootArgs { root },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_holder_gate(
+        &self,
+        context: &mut ProgramTestContext,
+        holder_gate: Option<HolderGate>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_holder_gate(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                SetHolderGateArgs { holder_gate },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_wallet_voucher_limit(
+        &self,
+        context: &mut ProgramTestContext,
+        max_vouchers_per_wallet: Option<u32>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_wallet_voucher_limit(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                SetWalletVoucherLimitArgs {
+                    max_vouchers_per_wallet,
+                },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_pity_rule(
+        &self,
+        context: &mut ProgramTestContext,
+        pity_rule: Option<PityRule>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::set_pity_rule(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.authority.pubkey(),
+                &self.pack_config.pubkey(),
+                SetPityRuleArgs { pity_rule },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn edit_card(
+        &self,
+        context: &mut ProgramTestContext,
+        test_pack_card: &TestPackCard,
+        test_master_edition: &TestMasterEditionV2,
+        max_supply: Option<u32>,
+        weight: Option<u16>,
+    ) -> transport::Result<()> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::edit_pack_card(
+                &metaplex_nft_packs::id(),
+                &self.keypair.pubkey(),
+                &self.pack_config.pubkey(),
+                &self.authority.pubkey(),
+                &test_master_edition.pubkey,
+                &self.store,
+                EditPackCardArgs {
+                    index: test_pack_card.index,
+                    max_supply,
+                    weight,
+                },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &self.authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
 }
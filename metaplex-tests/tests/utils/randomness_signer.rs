@@ -0,0 +1,36 @@
+use metaplex_nft_packs::utils::signed_randomness_message;
+use solana_sdk::{
+    ed25519_instruction::new_ed25519_instruction, instruction::Instruction, pubkey::Pubkey,
+    signature::Keypair, signer::Signer,
+};
+
+/// Stand-in for off-chain server which signs random values
+pub struct TestRandomnessSigner {
+    pub keypair: Keypair,
+}
+
+impl TestRandomnessSigner {
+    pub fn new() -> Self {
+        TestRandomnessSigner {
+            keypair: Keypair::new(),
+        }
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    pub fn sign(
+        &self,
+        proving_process: &Pubkey,
+        draw_counter: u32,
+        value: &[u8; 32],
+    ) -> Instruction {
+        let keypair = ed25519_dalek::Keypair::from_bytes(&self.keypair.to_bytes()).unwrap();
+
+        new_ed25519_instruction(
+            &keypair,
+            &signed_randomness_message(proving_process, draw_counter, value),
+        )
+    }
+}
@@ -0,0 +1,192 @@
+use crate::*;
+use metaplex_nft_packs::{
+    instruction::{AddCardToPackArgs, InitPackSetArgs},
+    state::{PackDistributionType, RandomnessSource},
+};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Signer, signer::keypair::Keypair, system_instruction, transaction::Transaction,
+    transport,
+};
+
+/// Not activated pack with one card and one voucher, voucher edition owner holds two editions
+pub struct TestPack {
+    pub context: ProgramTestContext,
+    pub test_pack_set: TestPackSet,
+    pub voucher_editions: Vec<TestEditionMarker>,
+    pub edition_authority: Keypair,
+    pub test_randomness_oracle: TestRandomnessOracle,
+    pub now: u64,
+}
+
+impl TestPack {
+    pub async fn new() -> Self {
+        let mut context = nft_packs_program_test().start_with_context().await;
+
+        let clock = context.banks_client.get_clock().await.unwrap();
+        let now = clock.unix_timestamp as u64;
+
+        let store_admin = Keypair::new();
+        let store_key = create_store(&mut context, &store_admin, true)
+            .await
+            .unwrap();
+
+        let test_pack_set = TestPackSet::new(store_key);
+        test_pack_set
+            .init(
+                &mut context,
+                InitPackSetArgs {
+                    name: [7; 32],
+                    uri: String::from("some link to storage"),
+                    description: String::from("Pack description"),
+                    mutable: true,
+                    distribution_type: PackDistributionType::Unlimited,
+                    allowed_amount_to_redeem: 1,
+                    redeem_start_date: Some(now),
+                    redeem_end_date: None,
+                    price: None,
+                    payment_mint: None,
+                    randomness_source: RandomnessSource::Oracle,
+                    unique_cards: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        let (card_metadata, card_master_edition, card_master_token_holder) =
+            create_master_edition(&mut context, &test_pack_set, None).await;
+
+        let test_pack_card = TestPackCard::new(&test_pack_set, 1);
+        test_pack_set
+            .add_card(
+                &mut context,
+                &test_pack_card,
+                &card_master_edition,
+                &card_metadata,
+                &card_master_token_holder,
+                AddCardToPackArgs {
+                    max_supply: 0,
+                    weight: 100,
+                    index: test_pack_card.index,
+                    tier: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let (voucher_metadata, voucher_master_edition, voucher_master_token_holder) =
+            create_master_edition(&mut context, &test_pack_set, Some(10)).await;
+
+        let edition_authority = Keypair::new();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[system_instruction::create_account(
+                &context.payer.pubkey(),
+                &edition_authority.pubkey(),
+                100000000000000,
+                0,
+                &solana_program::system_program::id(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &edition_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let mut voucher_editions = Vec::new();
+        for edition in 1..=2 {
+            let voucher_edition =
+                TestEditionMarker::new(&voucher_metadata, &voucher_master_edition, edition);
+            voucher_edition
+                .create(
+                    &mut context,
+                    &edition_authority,
+                    &test_pack_set.authority,
+                    &voucher_master_token_holder.token_account,
+                )
+                .await
+                .unwrap();
+            voucher_editions.push(voucher_edition);
+        }
+
+        let test_pack_voucher = TestPackVoucher::new(&test_pack_set, 1);
+        test_pack_set
+            .add_voucher(
+                &mut context,
+                &test_pack_voucher,
+                &voucher_master_edition,
+                &voucher_metadata,
+                &voucher_master_token_holder,
+            )
+            .await
+            .unwrap();
+
+        let mut test_randomness_oracle = TestRandomnessOracle::new();
+        test_randomness_oracle.init(&mut context).await.unwrap();
+        test_randomness_oracle.update(&mut context).await.unwrap();
+
+        Self {
+            context,
+            test_pack_set,
+            voucher_editions,
+            edition_authority,
+            test_randomness_oracle,
+            now,
+        }
+    }
+
+    pub async fn request_card(&mut self, voucher: usize) -> transport::Result<()> {
+        let voucher_edition = &self.voucher_editions[voucher];
+
+        self.test_pack_set
+            .request_card_for_redeem(
+                &mut self.context,
+                &self.test_pack_set.store,
+                &voucher_edition.new_edition_pubkey,
+                &voucher_edition.mint.pubkey(),
+                &self.edition_authority,
+                &Some(voucher_edition.token.pubkey()),
+                &self.test_randomness_oracle.keypair.pubkey(),
+                1,
+            )
+            .await
+    }
+}
+
+pub async fn create_master_edition(
+    context: &mut ProgramTestContext,
+    test_pack_set: &TestPackSet,
+    max_supply: Option<u64>,
+) -> (TestMetadata, TestMasterEditionV2, User) {
+    let test_metadata = TestMetadata::new();
+    let test_master_edition = TestMasterEditionV2::new(&test_metadata);
+
+    let user_token_acc = Keypair::new();
+    let master_token_holder = User {
+        owner: Keypair::new(),
+        token_account: user_token_acc.pubkey(),
+    };
+
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            &user_token_acc,
+            &test_pack_set.authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    test_master_edition
+        .create(context, max_supply)
+        .await
+        .unwrap();
+
+    (test_metadata, test_master_edition, master_token_holder)
+}
@@ -0,0 +1,110 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError, find_wallet_record_program_address, state::WalletRecord,
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, program_pack::Pack};
+use solana_program_test::*;
+use solana_sdk::{signer::Signer, transaction::TransactionError, transport::TransportError};
+use utils::*;
+
+#[tokio::test]
+async fn success() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .set_wallet_voucher_limit(&mut test_pack.context, Some(1))
+        .await
+        .unwrap();
+
+    let pack_set = test_pack
+        .test_pack_set
+        .get_data(&mut test_pack.context)
+        .await;
+    assert_eq!(pack_set.max_vouchers_per_wallet, Some(1));
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    test_pack.request_card(0).await.unwrap();
+
+    let (wallet_record_key, _) = find_wallet_record_program_address(
+        &metaplex_nft_packs::id(),
+        &test_pack.test_pack_set.keypair.pubkey(),
+        &test_pack.edition_authority.pubkey(),
+    );
+    let wallet_record_data = get_account(&mut test_pack.context, &wallet_record_key).await;
+    let wallet_record = WalletRecord::unpack_from_slice(&wallet_record_data.data).unwrap();
+    assert_eq!(wallet_record.opened_vouchers, 1);
+
+    let result = test_pack.request_card(1).await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::WalletVoucherLimitReached,
+        0
+    );
+}
+
+#[tokio::test]
+async fn success_remove_limit() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .set_wallet_voucher_limit(&mut test_pack.context, Some(1))
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .set_wallet_voucher_limit(&mut test_pack.context, None)
+        .await
+        .unwrap();
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    test_pack.request_card(0).await.unwrap();
+    test_pack.request_card(1).await.unwrap();
+}
+
+#[tokio::test]
+async fn fail_wrong_wallet_voucher_limit() {
+    let mut test_pack = TestPack::new().await;
+
+    let result = test_pack
+        .test_pack_set
+        .set_wallet_voucher_limit(&mut test_pack.context, Some(0))
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::WrongWalletVoucherLimit,
+        0
+    );
+}
+
+#[tokio::test]
+async fn fail_set_after_activation() {
+    let mut test_pack = TestPack::new().await;
+
+    test_pack
+        .test_pack_set
+        .activate(&mut test_pack.context)
+        .await
+        .unwrap();
+
+    let result = test_pack
+        .test_pack_set
+        .set_wallet_voucher_limit(&mut test_pack.context, Some(1))
+        .await;
+
+    assert_custom_error!(result.unwrap_err(), NFTPacksError::WrongPackState, 0);
+}
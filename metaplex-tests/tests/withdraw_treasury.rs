@@ -0,0 +1,129 @@
+mod utils;
+
+use metaplex_nft_packs::{
+    error::NFTPacksError,
+    find_treasury_program_address,
+    instruction::InitPackSetArgs,
+    state::{PackDistributionType, RandomnessSource, TreasuryRecipient},
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::InstructionError, pubkey::Pubkey, system_instruction};
+use solana_program_test::*;
+use solana_sdk::{
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+use utils::*;
+
+const TREASURY_BALANCE: u64 = 1000000000;
+
+async fn setup() -> (ProgramTestContext, TestPackSet, Pubkey) {
+    let mut context = nft_packs_program_test().start_with_context().await;
+
+    let name = [7; 32];
+    let uri = String::from("some link to storage");
+    let description = String::from("Pack description");
+
+    let store_admin = Keypair::new();
+    let store_key = create_store(&mut context, &store_admin, true)
+        .await
+        .unwrap();
+
+    let test_pack_set = TestPackSet::new(store_key);
+    test_pack_set
+        .init(
+            &mut context,
+            InitPackSetArgs {
+                name,
+                uri: uri.clone(),
+                description: description.clone(),
+                mutable: true,
+                distribution_type: PackDistributionType::Fixed,
+                allowed_amount_to_redeem: 10,
+                redeem_start_date: None,
+                redeem_end_date: None,
+                price: Some(1000000),
+                payment_mint: None,
+                randomness_source: RandomnessSource::Oracle,
+                unique_cards: false,
+            },
+        )
+        .await
+        .unwrap();
+
+    let recipient = Keypair::new().pubkey();
+
+    test_pack_set
+        .set_treasury_recipients(
+            &mut context,
+            vec![TreasuryRecipient {
+                address: recipient,
+                share: 2500,
+            }],
+        )
+        .await
+        .unwrap();
+
+    // Fill treasury as if vouchers were sold
+    let (treasury, _) =
+        find_treasury_program_address(&metaplex_nft_packs::id(), &test_pack_set.keypair.pubkey());
+
+    let tx = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &context.payer.pubkey(),
+            &treasury,
+            TREASURY_BALANCE,
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    (context, test_pack_set, recipient)
+}
+
+#[tokio::test]
+async fn success() {
+    let (mut context, test_pack_set, recipient) = setup().await;
+
+    let authority_balance = get_account(&mut context, &test_pack_set.authority.pubkey())
+        .await
+        .lamports;
+
+    test_pack_set
+        .withdraw_treasury(&mut context, &[recipient], &None)
+        .await
+        .unwrap();
+
+    let recipient_account = get_account(&mut context, &recipient).await;
+    assert_eq!(recipient_account.lamports, TREASURY_BALANCE / 4);
+
+    let authority_account = get_account(&mut context, &test_pack_set.authority.pubkey()).await;
+    assert_eq!(
+        authority_account.lamports,
+        authority_balance + TREASURY_BALANCE - TREASURY_BALANCE / 4
+    );
+
+    let (treasury, _) =
+        find_treasury_program_address(&metaplex_nft_packs::id(), &test_pack_set.keypair.pubkey());
+    assert!(is_empty_account(&mut context, &treasury).await);
+}
+
+#[tokio::test]
+async fn fail_recipient_mismatch() {
+    let (mut context, test_pack_set, _recipient) = setup().await;
+
+    let result = test_pack_set
+        .withdraw_treasury(&mut context, &[Keypair::new().pubkey()], &None)
+        .await;
+
+    assert_custom_error!(
+        result.unwrap_err(),
+        NFTPacksError::TreasuryRecipientMismatch,
+        0
+    );
+}
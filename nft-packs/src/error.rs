@@ -175,6 +175,190 @@ pub enum NFTPacksError {
     /// Invalid weight position
     #[error("Invalid weight position")]
     InvalidWeightPosition,
+
+    /// Pack isn't for sale
+    #[error("Pack isn't for sale")]
+    PackNotForSale,
+
+    /// Wrong voucher price
+    #[error("Wrong voucher price")]
+    WrongPrice,
+
+    /// Wrong payment mint
+    #[error("Wrong payment mint")]
+    WrongPaymentMint,
+
+    /// Voucher master edition isn't held by the program
+    #[error("Voucher master edition isn't held by the program")]
+    VoucherNotInCustody,
+
+    /// Too many treasury recipients
+    #[error("Too many treasury recipients")]
+    TooManyTreasuryRecipients,
+
+    /// Wrong treasury recipient shares
+    #[error("Wrong treasury recipient shares")]
+    WrongTreasuryShares,
+
+    /// Duplicate treasury recipient
+    #[error("Duplicate treasury recipient")]
+    DuplicateTreasuryRecipient,
+
+    /// Treasury recipient mismatch
+    #[error("Treasury recipient mismatch")]
+    TreasuryRecipientMismatch,
+
+    /// Wrong randomness source
+    #[error("Wrong randomness source")]
+    WrongRandomnessSource,
+
+    /// Randomness isn't committed
+    #[error("Randomness isn't committed")]
+    RandomnessNotCommitted,
+
+    /// Randomness is already revealed
+    #[error("Randomness is already revealed")]
+    RandomnessAlreadyRevealed,
+
+    /// Randomness isn't revealed
+    #[error("Randomness isn't revealed")]
+    RandomnessNotRevealed,
+
+    /// Wrong randomness secret
+    #[error("Wrong randomness secret")]
+    WrongRandomnessSecret,
+
+    /// Proving process doesn't have pending draws
+    #[error("Proving process doesn't have pending draws")]
+    NoPendingDraws,
+
+    /// Signed random value is missing
+    #[error("Signed random value is missing")]
+    SignedRandomValueMissing,
+
+    /// Wrong rarity tiers
+    #[error("Wrong rarity tiers")]
+    WrongRarityTiers,
+
+    /// Wrong rarity tier of card
+    #[error("Wrong rarity tier of card")]
+    WrongRarityTier,
+
+    /// Rarity tiers and pity rule can't be changed after cards are added
+    #[error("Rarity tiers and pity rule can't be changed after cards are added")]
+    PackSetHasCards,
+
+    /// Randomness version doesn't support rarity tiers
+    #[error("Randomness version doesn't support rarity tiers")]
+    UnsupportedRandomnessVersion,
+
+    /// Wrong pack slots
+    #[error("Wrong pack slots")]
+    WrongPackSlots,
+
+    /// Not enough distinct cards left to fill all the slots
+    #[error("Not enough distinct cards left to fill all the slots")]
+    NotEnoughDistinctCards,
+
+    /// Wrong pity rule
+    #[error("Wrong pity rule")]
+    WrongPityRule,
+
+    /// Pack config belongs to another pack set
+    #[error("Pack config belongs to another pack set")]
+    WrongPackConfig,
+
+    /// Account is already migrated to current version
+    #[error("Account is already migrated to current version")]
+    AccountAlreadyMigrated,
+
+    /// Wrong pack phases
+    #[error("Wrong pack phases")]
+    WrongPackPhases,
+
+    /// None of pack phases is active at the moment
+    #[error("None of pack phases is active at the moment")]
+    PackPhaseNotActive,
+
+    /// Wallet doesn't hold allowlist token of active pack phase
+    #[error("Wallet doesn't hold allowlist token of active pack phase")]
+    NotInPhaseAllowlist,
+
+    /// Wallet doesn't hold token required by active pack phase
+    #[error("Wallet doesn't hold token required by active pack phase")]
+    NotPhaseTokenHolder,
+
+    /// Wallet already opened max amount of vouchers in active pack phase
+    #[error("Wallet already opened max amount of vouchers in active pack phase")]
+    PhaseWalletLimitReached,
+
+    /// Pack set was created with old size and has no space for new fields
+    #[error("Pack set was created with old size and has no space for new fields")]
+    PackSetTooSmall,
+
+    /// Wallet isn't in pack allowlist or its proof is wrong
+    #[error("Wallet isn't in pack allowlist or its proof is wrong")]
+    NotInAllowlist,
+
+    /// Wallet already opened all the vouchers allowed by its allowlist quota
+    #[error("Wallet already opened all the vouchers allowed by its allowlist quota")]
+    AllowlistQuotaReached,
+
+    /// Holder gate should allow at least one use of NFT
+    #[error("Holder gate should allow at least one use of NFT")]
+    WrongHolderGate,
+
+    /// Wallet doesn't hold NFT required by pack holder gate
+    #[error("Wallet doesn't hold NFT required by pack holder gate")]
+    NotGateHolder,
+
+    /// Gating NFT was already used max amount of times
+    #[error("Gating NFT was already used max amount of times")]
+    GateUsesExhausted,
+
+    /// Wallet voucher limit should allow at least one voucher
+    #[error("Wallet voucher limit should allow at least one voucher")]
+    WrongWalletVoucherLimit,
+
+    /// Wallet already opened max amount of vouchers of the pack
+    #[error("Wallet already opened max amount of vouchers of the pack")]
+    WalletVoucherLimitReached,
+
+    /// Accounts of drawn card weren't passed to OpenPack
+    #[error("Accounts of drawn card weren't passed to OpenPack")]
+    MissingCardAccounts,
+
+    /// Randomness round is already open
+    #[error("Randomness round is already open")]
+    RandomnessAlreadyCommitted,
+
+    /// Pending draws of previous randomness round should be resolved first
+    #[error("Pending draws of previous randomness round should be resolved first")]
+    PendingDrawsNotResolved,
+
+    /// Hash of randomness reveal slot isn't available
+    #[error("Hash of randomness reveal slot isn't available")]
+    SlotHashNotFound,
+
+    /// Card has drawn editions which aren't claimed yet
+    #[error("Card has drawn editions which aren't claimed yet")]
+    UnclaimedCardDraws,
+
+    /// Card can't be moved, only the last card can be deleted
+    #[error("Card can't be moved, only the last card can be deleted")]
+    CardCantBeMoved,
+
+    /// Pack with legacy pack config needs CleanUp after each draw
+    #[error("Pack with legacy pack config needs CleanUp after each draw")]
+    MultipleDrawsWithLegacyPackConfig,
+
+    /// Pack config is initialized with the first card, its layout can't be changed
+    #[error("Pack config is initialized with the first card, its layout can't be changed")]
+    PackConfigInitialized,
+
+    /// Pity rule can't be set for pack with MaxSupply distribution type
+    #[error("Pity rule can't be set for pack with MaxSupply distribution type")]
+    PityRuleWithMaxSupply,
 }
 
 impl From<NFTPacksError> for ProgramError {
@@ -4,7 +4,13 @@
 use crate::{
     find_pack_card_program_address, find_pack_config_program_address,
     find_pack_voucher_program_address, find_program_authority,
-    find_proving_process_program_address, state::PackDistributionType,
+    find_proving_process_program_address, find_randomness_round_program_address,
+    find_treasury_program_address, find_treasury_token_program_address,
+    find_voucher_lookup_program_address, find_wallet_record_program_address,
+    state::{
+        AllowlistProof, HolderGate, PackDistributionType, PackPhase, PackSlot, PityRule,
+        RandomnessSource, RarityTier, TreasuryRecipient,
+    },
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
@@ -14,7 +20,7 @@ use solana_program::{
 };
 
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone)]
 pub struct AddCardToPackArgs {
     /// How many editions of this card will exists in pack
     pub max_supply: u32,
@@ -22,6 +28,20 @@ pub struct AddCardToPackArgs {
     pub weight: u16,
     /// Index
     pub index: u32,
+    /// Rarity tier index, required only if PackSet has rarity tiers
+    pub tier: Option<u8>,
+}
+
+impl BorshDeserialize for AddCardToPackArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            max_supply: BorshDeserialize::deserialize(buf)?,
+            weight: BorshDeserialize::deserialize(buf)?,
+            index: BorshDeserialize::deserialize(buf)?,
+            // clients built before rarity tiers were added don't send tier
+            tier: deserialize_appended(buf)?,
+        })
+    }
 }
 
 /// Initialize a PackSet arguments
@@ -44,11 +64,19 @@ pub struct InitPackSetArgs {
     pub redeem_start_date: Option<u64>,
     /// Redeem end date
     pub redeem_end_date: Option<u64>,
+    /// Voucher price for primary sale, pack isn't for sale if it's not set
+    pub price: Option<u64>,
+    /// Mint of SPL token accepted as payment, if not set price is in lamports
+    pub payment_mint: Option<Pubkey>,
+    /// Source of random values used to draw cards
+    pub randomness_source: RandomnessSource,
+    /// If true every card drawn with one voucher is distinct
+    pub unique_cards: bool,
 }
 
 /// Edit a PackSet arguments
 #[repr(C)]
-#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone, Default)]
 pub struct EditPackSetArgs {
     /// Name
     pub name: Option<[u8; 32]>,
@@ -58,6 +86,81 @@ pub struct EditPackSetArgs {
     pub uri: Option<String>,
     /// If true authority can make changes at deactivated phase
     pub mutable: Option<bool>,
+    /// Allowed amount to redeem, can be changed only before activation
+    pub allowed_amount_to_redeem: Option<u32>,
+    /// Redeem start date, can be changed only before activation
+    pub redeem_start_date: Option<u64>,
+    /// Redeem end date, can be only extended after activation
+    pub redeem_end_date: Option<u64>,
+}
+
+impl BorshDeserialize for EditPackSetArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            name: BorshDeserialize::deserialize(buf)?,
+            description: BorshDeserialize::deserialize(buf)?,
+            uri: BorshDeserialize::deserialize(buf)?,
+            mutable: BorshDeserialize::deserialize(buf)?,
+            // clients built before redeem schedule could be edited don't send the rest of fields
+            allowed_amount_to_redeem: deserialize_appended(buf)?,
+            redeem_start_date: deserialize_appended(buf)?,
+            redeem_end_date: deserialize_appended(buf)?,
+        })
+    }
+}
+
+/// Edit a PackCard arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct EditPackCardArgs {
+    /// Card index
+    pub index: u32,
+    /// How many editions of this card will exists in pack
+    pub max_supply: Option<u32>,
+    /// Probability value, can be set only if PackSet distribution type != MaxSupply
+    pub weight: Option<u16>,
+}
+
+/// Delete a PackCard arguments
+#[repr(C)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone, Default)]
+pub struct DeletePackCardArgs {
+    /// Card index, the last card is deleted if it's not set
+    pub index: Option<u32>,
+}
+
+impl BorshDeserialize for DeletePackCardArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        // clients built before cards could be deleted by index send the instruction without arguments
+        if buf.is_empty() {
+            return Ok(Self::default());
+        }
+
+        Ok(Self {
+            index: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+/// Delete a PackVoucher arguments
+#[repr(C)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone, Default)]
+pub struct DeletePackVoucherArgs {
+    /// Voucher index, the last voucher is deleted if it's not set
+    pub index: Option<u32>,
+}
+
+impl BorshDeserialize for DeletePackVoucherArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        // clients built before vouchers could be deleted by index send the instruction without arguments
+        if buf.is_empty() {
+            return Ok(Self::default());
+        }
+
+        Ok(Self {
+            index: BorshDeserialize::deserialize(buf)?,
+        })
+    }
 }
 
 /// Claim card from pack
@@ -68,12 +171,180 @@ pub struct ClaimPackArgs {
     pub index: u32,
 }
 
-/// Request card to redeem arguments
+/// Claim several cards from pack
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ClaimPacksArgs {
+    /// Card indexes
+    pub indexes: Vec<u32>,
+}
+
+/// Request card to redeem arguments
+#[repr(C)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone)]
 pub struct RequestCardToRedeemArgs {
     /// Voucher index
     pub index: u32,
+    /// Proof that wallet is in pack allowlist, required to open voucher if pack has allowlist
+    pub allowlist_proof: Option<AllowlistProof>,
+}
+
+impl BorshDeserialize for RequestCardToRedeemArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            index: BorshDeserialize::deserialize(buf)?,
+            allowlist_proof: deserialize_allowlist_proof(buf)?,
+        })
+    }
+}
+
+/// Request cards to redeem arguments
+#[repr(C)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone)]
+pub struct RequestCardsToRedeemArgs {
+    /// Voucher index
+    pub index: u32,
+    /// Amount of cards to draw, can't be more than allowed amount to redeem
+    pub count: u32,
+    /// Proof that wallet is in pack allowlist, required to open voucher if pack has allowlist
+    pub allowlist_proof: Option<AllowlistProof>,
+}
+
+impl BorshDeserialize for RequestCardsToRedeemArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            index: BorshDeserialize::deserialize(buf)?,
+            count: BorshDeserialize::deserialize(buf)?,
+            allowlist_proof: deserialize_allowlist_proof(buf)?,
+        })
+    }
+}
+
+/// Buy pack voucher arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct BuyPackArgs {
+    /// Voucher index
+    pub index: u32,
+}
+
+/// Open pack arguments
+#[repr(C)]
+#[derive(BorshSerialize, PartialEq, Debug, Clone)]
+pub struct OpenPackArgs {
+    /// Voucher index
+    pub index: u32,
+    /// Proof that wallet is in pack allowlist, required to open voucher if pack has allowlist
+    pub allowlist_proof: Option<AllowlistProof>,
+}
+
+impl BorshDeserialize for OpenPackArgs {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Self {
+            index: BorshDeserialize::deserialize(buf)?,
+            allowlist_proof: deserialize_allowlist_proof(buf)?,
+        })
+    }
+}
+
+/// Allowlist proof is the last field of draw arguments and clients built before allowlist was added don't send it,
+/// so missing proof is read as `None`
+fn deserialize_allowlist_proof(buf: &mut &[u8]) -> std::io::Result<Option<AllowlistProof>> {
+    if buf.is_empty() {
+        return Ok(None);
+    }
+
+    BorshDeserialize::deserialize(buf)
+}
+
+/// Optional field appended to arguments isn't sent by clients built before it was added,
+/// so missing field is read as `None`
+fn deserialize_appended<T: BorshDeserialize>(buf: &mut &[u8]) -> std::io::Result<Option<T>> {
+    if buf.is_empty() {
+        return Ok(None);
+    }
+
+    BorshDeserialize::deserialize(buf)
+}
+
+/// Set treasury recipients arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetTreasuryRecipientsArgs {
+    /// Treasury recipients with their shares in basis points
+    pub recipients: Vec<TreasuryRecipient>,
+}
+
+/// Set rarity tiers arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetRarityTiersArgs {
+    /// Rarity tiers with their probabilities in basis points
+    pub tiers: Vec<RarityTier>,
+}
+
+/// Set pack slots arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetPackSlotsArgs {
+    /// Slot layout, every slot is a bit mask of rarity tiers
+    pub slots: Vec<PackSlot>,
+}
+
+/// Set pity rule arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetPityRuleArgs {
+    /// Bad luck protection rule, `None` removes it
+    pub pity_rule: Option<PityRule>,
+}
+
+/// Set pack phases arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetPackPhasesArgs {
+    /// Redemption phases sorted by start date, empty list removes them
+    pub phases: Vec<PackPhase>,
+}
+
+/// Set allowlist root arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetAllowlistRootArgs {
+    /// Merkle root of allowlist, `None` removes allowlist
+    pub root: Option<[u8; 32]>,
+}
+
+/// Set holder gate arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetHolderGateArgs {
+    /// NFT holder gate, `None` removes the gate
+    pub holder_gate: Option<HolderGate>,
+}
+
+/// Set wallet voucher limit arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SetWalletVoucherLimitArgs {
+    /// Max amount of vouchers one wallet can open, `None` removes the limit
+    pub max_vouchers_per_wallet: Option<u32>,
+}
+
+/// Commit randomness arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CommitRandomnessArgs {
+    /// sha256 hash of authority secret
+    pub commitment: [u8; 32],
+}
+
+/// Reveal randomness arguments
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct RevealRandomnessArgs {
+    /// Authority secret
+    pub secret: [u8; 32],
 }
 
 /// Instruction definition
@@ -85,6 +356,7 @@ pub enum NFTPacksInstruction {
     ///
     /// Accounts:
     /// - write                          pack_set
+    /// - read                           pack_config, account created with `PackConfig::get_len` size, initialized with the first card
     /// - signer                         authority
     /// - read                           store
     /// - read                           Rent account
@@ -100,16 +372,21 @@ pub enum NFTPacksInstruction {
     /// - allowed_amount_to_redeem    u32
     /// - redeem_start_date    Option<u64>
     /// - redeem_end_date    Option<u64>
+    /// - price    Option<u64>
+    /// - payment_mint    Option<Pubkey>
+    /// - randomness_source    RandomnessSource
+    /// - unique_cards    bool
     InitPack(InitPackSetArgs),
 
     /// AddCardToPack
     ///
     /// Creates new account with PackCard structure and program token account which will hold MasterEdition token.
     /// Also admin points how many items of this specific MasterEdition will be in the pack. Check MasterEdition for V2.
+    /// Cards can be added to not activated pack or to deactivated mutable pack to restock it, restock is logged.
     ///
     /// Accounts:
     /// - read, write                   pack_set
-    /// - write                         pack_config (PDA, ['config', pack])
+    /// - write                         pack_config
     /// - write                         pack_card (PDA, ['card', pack, index])
     /// - signer                        authority
     /// - read                          master_edition
@@ -127,16 +404,20 @@ pub enum NFTPacksInstruction {
     /// - max_supply	Option<u32>
     /// - probability_type	enum[fixed number, probability based]
     /// - probability	u64
+    /// - tier	Option<u8>
     AddCardToPack(AddCardToPackArgs),
 
     /// AddVoucherToPack
     ///
     /// Creates new account with PackVoucher structure, saves there data about NFTs which user has to provide to open the pack.
+    /// Voucher lookup is created with voucher index so voucher can be found by its MasterEdition, pack can't have two vouchers with the same MasterEdition.
+    /// If token_account is passed MasterEdition token is transferred to it so program can sell vouchers with `BuyPack`.
     /// Check MasterEdition for V2.
     ///
     /// Accounts:
     /// - read, write                   pack_set
     /// - write                         pack_voucher (PDA, ['voucher', pack, index])
+    /// - write                         voucher_lookup (PDA, ['voucher_master', pack, master_edition])
     /// - signer, write                 authority
     /// - signer, read                  voucher_owner
     /// - read                          master_edition
@@ -147,11 +428,14 @@ pub enum NFTPacksInstruction {
     /// - read                          rent
     /// - read                          system_program
     /// - read                          spl_token program
+    /// - write                         token_account optional (program account to hold MasterEdition token)
+    /// - read                          program_authority optional, required if token_account is passed
     AddVoucherToPack,
 
     /// Activate
     ///
     /// Pack authority call this instruction to activate pack, means close for changing.
+    /// Pack with `RandomnessSource::CommitReveal` should have randomness committed.
     ///
     /// Accounts:
     /// - write            pack_set
@@ -197,7 +481,7 @@ pub enum NFTPacksInstruction {
     /// - read              metadata_mint_acc
     /// - read              edition_acc
     /// - read              rent program
-    /// - read              randomness oracle account
+    /// - read              randomness oracle account (any account if pack randomness source isn't Oracle)
     /// - read              metaplex_token_metadata program
     /// - read              spl_token program
     /// - read              system program
@@ -228,8 +512,12 @@ pub enum NFTPacksInstruction {
 
     /// DeletePackCard
     ///
-    /// Transfer all the SOL from pack card account to refunder account and thus remove it.
-    /// Also transfer master token to new owner.
+    /// Delete card with any index and transfer master token to new owner.
+    /// The last card is moved to index of deleted card and its PackConfig entry is moved with it,
+    /// then all the SOL from the last pack card account is transferred to refunder account and thus it's removed.
+    /// Cards of ended pack can be deleted or moved only if all their drawn editions are claimed,
+    /// cards of unlimited packs and packs without PackConfig can't be moved.
+    /// If index isn't passed the last card is deleted, so instruction without arguments sent by older clients keeps working.
     ///
     /// Accounts:
     /// - write            pack_set
@@ -241,22 +529,42 @@ pub enum NFTPacksInstruction {
     /// - read             program_authority
     /// - read             rent
     /// - read             spl_token program
-    DeletePackCard,
+    /// - write            pack_config optional, required if pack has PackConfig
+    /// - write            last_pack_card optional, required if not the last card is deleted
+    ///
+    /// Parameters:
+    /// - index            Option<u32>
+    DeletePackCard(DeletePackCardArgs),
 
     /// DeletePackVoucher
     ///
-    /// Transfer all the SOL from pack voucher account to refunder account and thus remove it.
+    /// Delete voucher with any index, the last voucher is moved to its index and its lookup is updated.
+    /// All the SOL from the last pack voucher account and from lookup of deleted voucher is transferred to refunder account.
+    /// If voucher MasterEdition is held by the program it's transferred to new owner.
+    /// Vouchers added before lookups were introduced don't have them, lookup accounts are skipped if they don't exist.
+    /// If index isn't passed the last voucher is deleted and only its lookup follows optional accounts.
     ///
     /// Accounts:
     /// - write            pack_set
     /// - write            pack_voucher
     /// - signer           authority
     /// - write            refunder
-    DeletePackVoucher,
+    /// - write            new_master_edition_owner optional, required if voucher has token_account
+    /// - write            token_account optional, required if voucher has token_account
+    /// - read             program_authority optional, required if voucher has token_account
+    /// - read             spl_token program optional, required if voucher has token_account
+    /// - write            last_pack_voucher optional, required if index is passed, the same as pack_voucher if the last voucher is deleted
+    /// - write            voucher_lookup (PDA, ['voucher_master', pack, voucher_master])
+    /// - write            last_voucher_lookup optional, required if index is passed (PDA, ['voucher_master', pack, last_voucher_master])
+    ///
+    /// Parameters:
+    /// - index            Option<u32>
+    DeletePackVoucher(DeletePackVoucherArgs),
 
     /// EditPack
     ///
     /// Edit pack data.
+    /// Redeem schedule and allowed amount to redeem are validated against the clock like on `InitPack`.
     ///
     /// Accounts:
     /// - write            pack_set
@@ -267,37 +575,52 @@ pub enum NFTPacksInstruction {
     /// - description Option<String>
     /// - URI Option<String>
     /// - mutable	Option<bool> (only can be changed from true to false)
+    /// - allowed_amount_to_redeem	Option<u32> (only in not activated state)
+    /// - redeem_start_date	Option<u64> (only in not activated state)
+    /// - redeem_end_date	Option<u64> (can be only extended in deactivated state)
     EditPack(EditPackSetArgs),
 
     /// RequestCardForRedeem
     ///
-    /// Count card index which user can redeem next
+    /// Count card index which user can redeem next.
+    /// If pack randomness source is SignedValue, Ed25519 program instruction with signed random value
+    /// should be placed before this instruction in the same transaction.
+    /// If pack has allowlist, voucher can be opened only with proof that wallet is in it.
     ///
     /// Accounts:
     /// - read                     pack_set
-    /// - read, write              pack_config (PDA, ['config', pack])
+    /// - read, write              pack_config (PDA, ['config', pack] if pack set has no pack config key)
     /// - read                     store
     /// - read                     edition
     /// - read                     edition_mint
-    /// - read                     pack_voucher
+    /// - read                     pack_voucher (PDA, ['voucher', pack, index]) or voucher_lookup (PDA, ['voucher_master', pack, voucher_master])
     /// - read, write              proving_process (PDA, ['proving', pack, user_wallet])
     /// - signer                   user_wallet
-    /// - read                     randomness_oracle
+    /// - read                     randomness_oracle (instructions sysvar if pack randomness source is SignedValue)
     /// - read                     clock
     /// - read                     rent
     /// - read                     system_program
-    /// - read                     user_token_account optional
+    /// - read                     user_token_account optional, required on voucher opening
+    /// - write                    wallet_record (PDA, ['wallet', pack, user_wallet]) optional, required if pack has pity rule or wallet limits or if gate accounts are passed
+    /// - write                    gate_token_account optional, required on voucher opening if active phase has gate
+    /// - write                    gate_mint optional, required on voucher opening if active phase has allowlist gate
+    /// - read                     gate_nft_token_account optional, required on voucher opening if pack has holder gate
+    /// - read                     gate_nft_metadata optional, required on voucher opening if pack has holder gate
+    /// - write                    gate_usage (PDA, ['gate_usage', pack, gate_nft_mint]) optional, required on voucher opening if pack has holder gate
     ///
     /// Parameters:
     /// - index    u32
+    /// - allowlist_proof    Option<AllowlistProof>
     RequestCardForRedeem(RequestCardToRedeemArgs),
 
     /// CleanUp
     ///
-    /// Sorts weights of all the cards and removes exhausted
+    /// Applies supply change of the last draw to `LegacyPackConfig` and sorts its weights,
+    /// required before every draw from such config. Pack config is updated right on draw
+    /// for the rest of packs so CleanUp does nothing for them
     ///
     /// Accounts:
-    /// - read                     pack_set
+    /// - write                    pack_set
     /// - read, write              pack_config (PDA, ['config', pack])
     CleanUp,
 
@@ -307,108 +630,520 @@ pub enum NFTPacksInstruction {
     ///
     /// Accounts:
     /// - read                pack_set
-    /// - write               pack_config (PDA, ['config', pack])
+    /// - write               pack_config (PDA, ['config', pack] if pack set has no pack config key)
     /// - write               refunder
     /// - signer              authority
     DeletePackConfig,
-}
-
-/// Create `InitPack` instruction
-pub fn init_pack(
-    program_id: &Pubkey,
-    pack_set: &Pubkey,
-    authority: &Pubkey,
-    store: &Pubkey,
-    whitelisted_creator: &Pubkey,
-    args: InitPackSetArgs,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new(*pack_set, false),
-        AccountMeta::new_readonly(*authority, true),
-        AccountMeta::new_readonly(*store, false),
-        AccountMeta::new_readonly(sysvar::rent::id(), false),
-        AccountMeta::new_readonly(sysvar::clock::id(), false),
-        AccountMeta::new_readonly(*whitelisted_creator, false),
-    ];
 
-    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::InitPack(args), accounts)
-}
+    /// BuyPack
+    ///
+    /// Takes voucher price from buyer to pack treasury and mints new voucher edition to buyer.
+    /// Voucher MasterEdition should be held by the program.
+    ///
+    /// Accounts:
+    /// - read              pack_set
+    /// - read              pack_voucher (PDA, ['voucher', pack, index])
+    /// - signer, write     buyer
+    /// - write             treasury (PDA, ['treasury', pack])
+    /// - read              program_authority
+    /// - read              token_account (program account which holds voucher MasterEdition token)
+    /// - write             new_metadata_acc
+    /// - write             new_edition_acc
+    /// - write             master_edition_acc
+    /// - write             new_mint_account
+    /// - signer            new_mint_authority_acc
+    /// - write             metadata_acc
+    /// - read              metadata_mint_acc
+    /// - write             edition_marker
+    /// - read              rent program
+    /// - read              metaplex_token_metadata program
+    /// - read              spl_token program
+    /// - read              system program
+    /// - write             buyer_token_account optional, required if pack is sold for SPL tokens
+    /// - write             treasury_token_account optional (PDA, ['treasury', pack, payment_mint])
+    /// - read              payment_mint optional, required if pack is sold for SPL tokens
+    ///
+    /// Parameters:
+    /// - index             u32
+    BuyPack(BuyPackArgs),
 
-/// Creates 'AddCardToPack' instruction.
-#[allow(clippy::too_many_arguments)]
-pub fn add_card_to_pack(
-    program_id: &Pubkey,
-    pack_set: &Pubkey,
-    authority: &Pubkey,
-    master_edition: &Pubkey,
-    master_metadata: &Pubkey,
-    mint: &Pubkey,
-    source: &Pubkey,
-    token_account: &Pubkey,
-    store: &Pubkey,
-    args: AddCardToPackArgs,
-) -> Instruction {
-    let (program_authority, _) = find_program_authority(program_id);
-    let (pack_card, _) = find_pack_card_program_address(program_id, pack_set, args.index);
-    let (pack_config, _) = find_pack_config_program_address(program_id, pack_set);
+    /// SetTreasuryRecipients
+    ///
+    /// Set list of recipients which get their shares of pack treasury on withdraw.
+    /// Can be called only while pack isn't activated.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    ///
+    /// Parameters:
+    /// - recipients       Vec<TreasuryRecipient>
+    SetTreasuryRecipients(SetTreasuryRecipientsArgs),
 
-    let accounts = vec![
-        AccountMeta::new(*pack_set, false),
-        AccountMeta::new(pack_config, false),
-        AccountMeta::new(pack_card, false),
-        AccountMeta::new(*authority, true),
-        AccountMeta::new_readonly(*master_edition, false),
-        AccountMeta::new_readonly(*master_metadata, false),
-        AccountMeta::new_readonly(*mint, false),
-        AccountMeta::new(*source, false),
-        AccountMeta::new(*token_account, false),
-        AccountMeta::new(program_authority, false),
-        AccountMeta::new_readonly(*store, false),
-        AccountMeta::new_readonly(sysvar::rent::id(), false),
-        AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(spl_token::id(), false),
-    ];
+    /// WithdrawTreasury
+    ///
+    /// Split pack treasury between treasury recipients, what's left goes to authority.
+    ///
+    /// Accounts:
+    /// - read             pack_set
+    /// - signer, write    authority
+    /// - write            treasury (PDA, ['treasury', pack])
+    /// - read             system_program
+    /// - write            treasury_token_account optional, required if pack is sold for SPL tokens (PDA, ['treasury', pack, payment_mint])
+    /// - write            authority_token_account optional, required if pack is sold for SPL tokens
+    /// - read             spl_token program optional, required if pack is sold for SPL tokens
+    /// - write            recipient accounts, wallet or token account for each treasury recipient in the same order
+    WithdrawTreasury,
 
-    Instruction::new_with_borsh(
-        *program_id,
-        &NFTPacksInstruction::AddCardToPack(args),
-        accounts,
-    )
-}
+    /// OpenPack
+    ///
+    /// Burns voucher on first call, selects next card to redeem, applies card supply change to pack config
+    /// and mints new card edition to user in one call.
+    /// Card accounts are passed for every card which can be selected, opening fails with `MissingCardAccounts`
+    /// if selected card accounts are missing. Gate accounts go before card accounts.
+    ///
+    /// Accounts:
+    /// - write                    pack_set
+    /// - write                    pack_config (PDA, ['config', pack] if pack set has no pack config key)
+    /// - read                     store
+    /// - read                     edition
+    /// - write                    edition_mint
+    /// - read                     pack_voucher (PDA, ['voucher', pack, index]) or voucher_lookup (PDA, ['voucher_master', pack, voucher_master])
+    /// - write                    proving_process (PDA, ['proving', pack, user_wallet, edition_mint])
+    /// - signer, write            user_wallet
+    /// - read                     randomness_oracle (instructions sysvar if pack randomness source is SignedValue)
+    /// - read                     clock
+    /// - read                     rent
+    /// - read                     spl_token program
+    /// - read                     system_program
+    /// - write                    user_token_account (voucher token account, burned on first call)
+    /// - read                     program_authority
+    /// - write                    new_metadata_acc
+    /// - write                    new_edition_acc
+    /// - write                    new_mint_account
+    /// - signer                   new_mint_authority_acc
+    /// - read                     metaplex_token_metadata program
+    /// - write                    wallet_record (PDA, ['wallet', pack, user_wallet]), used only if pack has pity rule or wallet limits
+    /// - write                    gate_token_account optional, required on first call if active phase has gate
+    /// - write                    gate_mint optional, required on first call if active phase has allowlist gate
+    /// - read                     gate_nft_token_account optional, required on first call if pack has holder gate
+    /// - read                     gate_nft_metadata optional, required on first call if pack has holder gate
+    /// - write                    gate_usage (PDA, ['gate_usage', pack, gate_nft_mint]) optional, required on first call if pack has holder gate
+    /// - for every card:
+    ///     - write                pack_card (PDA, ['card', pack, index])
+    ///     - read                 token_account (program account which holds card MasterEdition token)
+    ///     - write                master_edition_acc
+    ///     - write                metadata_acc
+    ///     - read                 metadata_mint_acc
+    ///     - write                edition_marker
+    ///
+    /// Parameters:
+    /// - index    u32
+    /// - allowlist_proof    Option<AllowlistProof>
+    OpenPack(OpenPackArgs),
 
-/// Creates `AddVoucherToPack` instruction
-#[allow(clippy::too_many_arguments)]
-pub fn add_voucher_to_pack(
-    program_id: &Pubkey,
-    pack_set: &Pubkey,
-    pack_voucher: &Pubkey,
-    authority: &Pubkey,
-    voucher_owner: &Pubkey,
-    master_edition: &Pubkey,
-    master_metadata: &Pubkey,
-    mint: &Pubkey,
-    source: &Pubkey,
-    store: &Pubkey,
-) -> Instruction {
-    let accounts = vec![
-        AccountMeta::new(*pack_set, false),
-        AccountMeta::new(*pack_voucher, false),
-        AccountMeta::new(*authority, true),
-        AccountMeta::new_readonly(*voucher_owner, true),
-        AccountMeta::new_readonly(*master_edition, false),
-        AccountMeta::new_readonly(*master_metadata, false),
-        AccountMeta::new_readonly(*mint, false),
-        AccountMeta::new(*source, false),
-        AccountMeta::new_readonly(*store, false),
-        AccountMeta::new_readonly(sysvar::rent::id(), false),
-        AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(spl_token::id(), false),
-    ];
+    /// RequestCardsForRedeem
+    ///
+    /// Same as RequestCardForRedeem but draws `count` cards in one call.
+    /// Card supply changes are applied to pack config after every draw so CleanUp isn't required.
+    ///
+    /// Accounts:
+    /// - write                    pack_set
+    /// - read, write              pack_config (PDA, ['config', pack] if pack set has no pack config key)
+    /// - read                     store
+    /// - read                     edition
+    /// - read                     edition_mint
+    /// - read                     pack_voucher (PDA, ['voucher', pack, index]) or voucher_lookup (PDA, ['voucher_master', pack, voucher_master])
+    /// - read, write              proving_process (PDA, ['proving', pack, user_wallet])
+    /// - signer                   user_wallet
+    /// - read                     randomness_oracle (instructions sysvar if pack randomness source is SignedValue)
+    /// - read                     clock
+    /// - read                     rent
+    /// - read                     spl_token program
+    /// - read                     system_program
+    /// - read                     user_token_account optional, required on voucher opening
+    /// - write                    wallet_record (PDA, ['wallet', pack, user_wallet]) optional, required if pack has pity rule or wallet limits or if gate accounts are passed
+    /// - write                    gate_token_account optional, required on voucher opening if active phase has gate
+    /// - write                    gate_mint optional, required on voucher opening if active phase has allowlist gate
+    /// - read                     gate_nft_token_account optional, required on voucher opening if pack has holder gate
+    /// - read                     gate_nft_metadata optional, required on voucher opening if pack has holder gate
+    /// - write                    gate_usage (PDA, ['gate_usage', pack, gate_nft_mint]) optional, required on voucher opening if pack has holder gate
+    ///
+    /// Parameters:
+    /// - index    u32
+    /// - count    u32
+    /// - allowlist_proof    Option<AllowlistProof>
+    RequestCardsForRedeem(RequestCardsToRedeemArgs),
 
-    Instruction::new_with_borsh(
-        *program_id,
-        &NFTPacksInstruction::AddVoucherToPack,
-        accounts,
+    /// ClaimPacks
+    ///
+    /// Same as ClaimPack but mints new editions for several cards in one call.
+    ///
+    /// Accounts:
+    /// - read              pack_set
+    /// - read, write       proving_process (PDA, ['proving', pack, user_wallet])
+    /// - signer, write     user_wallet
+    /// - read              program_authority
+    /// - signer            new_mint_authority_acc
+    /// - read              rent program
+    /// - read              metaplex_token_metadata program
+    /// - read              spl_token program
+    /// - read              system program
+    /// - for every card index:
+    ///     - write         pack_card (PDA, ['card', pack, index])
+    ///     - read          token_account (program account which holds card MasterEdition token)
+    ///     - write         new_mint_account
+    ///     - write         new_metadata_acc
+    ///     - write         new_edition_acc
+    ///     - write         edition_marker
+    ///     - write         master_edition_acc
+    ///     - write         metadata_acc
+    ///     - read          metadata_mint_acc
+    ///
+    /// Parameters:
+    /// - indexes           Vec<u32>
+    ClaimPacks(ClaimPacksArgs),
+
+    /// CommitRandomness
+    ///
+    /// Open new randomness round of pack with `RandomnessSource::CommitReveal` with hash of authority secret,
+    /// draws requested while round is open are resolved with its secret. Previous round should be revealed.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer, write    authority
+    /// - write            randomness_round (PDA, ['round', pack, round_index]), index is amount of committed rounds
+    /// - read             Rent account
+    /// - read             System program
+    ///
+    /// Parameters:
+    /// - commitment       [u8; 32]
+    CommitRandomness(CommitRandomnessArgs),
+
+    /// RevealRandomness
+    ///
+    /// Reveal authority secret of randomness round, after that its pending draws can be resolved
+    /// and new draws wait for the next round. Round which hash of reveal slot expired before
+    /// any draw was resolved can be revealed again.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    /// - write            randomness_round (PDA, ['round', pack, round_index])
+    /// - read             SlotHashes account
+    ///
+    /// Parameters:
+    /// - secret           [u8; 32]
+    RevealRandomness(RevealRandomnessArgs),
+
+    /// ResolveDraws
+    ///
+    /// Draw cards for all pending draws of proving process with seed of their randomness round.
+    /// The first call after reveal mixes round secret with hash of reveal slot, it should be made
+    /// while the slot is in SlotHashes. Can be called by anyone.
+    ///
+    /// Accounts:
+    /// - read             pack_set
+    /// - write            pack_config
+    /// - write            proving_process (PDA, ['proving', pack, user_wallet, edition_mint])
+    /// - write            wallet_record (PDA, ['wallet', pack, user_wallet]), used only if pack has pity rule
+    /// - write            randomness_round (PDA, ['round', pack, round_index]), round of pending draws
+    /// - read             SlotHashes account
+    ResolveDraws,
+
+    /// SetRarityTiers
+    ///
+    /// Set list of rarity tiers. Draw picks a tier with its probability and then a card within it,
+    /// tiers without cards left are skipped and their probability is split between other tiers.
+    /// Can be called only while pack isn't activated and its pack config isn't initialized with the first card.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    /// - read             pack_config
+    ///
+    /// Parameters:
+    /// - tiers            Vec<RarityTier>
+    SetRarityTiers(SetRarityTiersArgs),
+
+    /// SetPackSlots
+    ///
+    /// Set slot layout. When proving process has n cards to redeem next draw uses n-th slot
+    /// and picks a card only from rarity tiers allowed by this slot. Draws after the last slot use all tiers.
+    /// Can be called only while pack isn't activated and requires rarity tiers.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    ///
+    /// Parameters:
+    /// - slots            Vec<PackSlot>
+    SetPackSlots(SetPackSlotsArgs),
+
+    /// SetPityRule
+    ///
+    /// Set bad luck protection rule. Draws without rare card are counted in wallet record of every user
+    /// and after `max_misses` of them next draw picks only from rare cards, counter is reset once rare card is drawn.
+    /// Can be called only while pack isn't activated and its pack config isn't initialized with the first card,
+    /// rule can't be set for pack with MaxSupply distribution type.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    /// - read             pack_config
+    ///
+    /// Parameters:
+    /// - pity_rule        Option<PityRule>
+    SetPityRule(SetPityRuleArgs),
+
+    /// MigrateAccount
+    ///
+    /// Upgrade account created before versioning to current layout and write current version to it.
+    /// Account is resized to its current size, payer tops up its balance to keep it rent exempt.
+    /// Pack set serialized with borsh is converted to fixed layout. Legacy pack config PDA keeps its layout.
+    /// Can be called by anyone.
+    ///
+    /// Accounts:
+    /// - write            account (pack_set, pack_card, pack_voucher, proving_process, pack_config, wallet_record, voucher_lookup or gate_usage)
+    /// - signer, write    payer
+    /// - read             system_program
+    /// - read             pack_set, only for pack_config
+    MigrateAccount,
+
+    /// EditPackCard
+    ///
+    /// Change max supply or weight of added card. PackConfig entry and pack totals are updated,
+    /// in deactivated pack cards already drawn but not claimed are kept and new max supply can't be less than their amount.
+    /// Can be called only while pack isn't activated or if it's deactivated and mutable.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - write            pack_config
+    /// - write            pack_card
+    /// - signer           authority
+    /// - read             master_edition
+    /// - read             store
+    ///
+    /// Parameters:
+    /// - index            u32
+    /// - max_supply       Option<u32>
+    /// - weight           Option<u16>
+    EditPackCard(EditPackCardArgs),
+
+    /// SetPackPhases
+    ///
+    /// Split redemption into phases, each phase has its own time window, gate and limit of vouchers
+    /// opened by one wallet. Once pack has phases cards can be requested only in active phase,
+    /// gate and wallet limit are checked when voucher is opened. Allowlist gate burns one allowlist token,
+    /// token holder gate only requires wallet to hold the token.
+    /// Pack sets created before phases were introduced have no space for them.
+    /// Can be called only while pack isn't activated.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    ///
+    /// Parameters:
+    /// - phases           Vec<PackPhase>
+    SetPackPhases(SetPackPhasesArgs),
+
+    /// SetAllowlistRoot
+    ///
+    /// Set merkle root of wallets allowed to open vouchers, every leaf is sha256 of wallet key
+    /// and its optional quota of vouchers serialized with borsh. Allowlisted wallet passes its proof
+    /// to open voucher, vouchers opened with quota are counted in its wallet record.
+    /// Pack sets created before allowlist was introduced have no space for it.
+    /// Can be called only while pack isn't activated.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    ///
+    /// Parameters:
+    /// - root             Option<[u8; 32]>
+    SetAllowlistRoot(SetAllowlistRootArgs),
+
+    /// SetHolderGate
+    ///
+    /// Allow only holders of NFT from verified Metaplex collection or with verified creator
+    /// to open vouchers. Holder passes token account of the NFT and its metadata when voucher is opened,
+    /// every NFT can be used `max_uses` times which are counted in its gate usage account.
+    /// Pack sets created before holder gate was introduced have no space for it.
+    /// Can be called only while pack isn't activated.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    ///
+    /// Parameters:
+    /// - holder_gate      Option<HolderGate>
+    SetHolderGate(SetHolderGateArgs),
+
+    /// SetWalletVoucherLimit
+    ///
+    /// Limit amount of vouchers one wallet can open over all of its vouchers. Opened vouchers are counted
+    /// in wallet record which is created on first voucher opening.
+    /// Pack sets created before the limit was introduced have no space for it.
+    /// Can be called only while pack isn't activated.
+    ///
+    /// Accounts:
+    /// - write            pack_set
+    /// - signer           authority
+    ///
+    /// Parameters:
+    /// - max_vouchers_per_wallet    Option<u32>
+    SetWalletVoucherLimit(SetWalletVoucherLimitArgs),
+}
+
+/// Card accounts for `ClaimPacks` instruction
+#[derive(Debug, Clone)]
+pub struct ClaimCardAccounts {
+    /// Card index
+    pub index: u32,
+    /// Program token account which holds MasterEdition token
+    pub token_account: Pubkey,
+    /// New edition mint
+    pub new_mint: Pubkey,
+    /// New edition metadata
+    pub new_metadata: Pubkey,
+    /// New edition
+    pub new_edition: Pubkey,
+    /// Master edition
+    pub master_edition: Pubkey,
+    /// Master metadata
+    pub metadata: Pubkey,
+    /// Master mint
+    pub metadata_mint: Pubkey,
+    /// Number of new edition, used to find edition marker
+    pub edition: u64,
+}
+
+/// Card accounts for `OpenPack` instruction
+#[derive(Debug, Clone)]
+pub struct OpenPackCardAccounts {
+    /// Card index
+    pub index: u32,
+    /// Program token account which holds MasterEdition token
+    pub token_account: Pubkey,
+    /// Master edition
+    pub master_edition: Pubkey,
+    /// Master metadata
+    pub metadata: Pubkey,
+    /// Master mint
+    pub metadata_mint: Pubkey,
+    /// Number of new edition, used to find edition marker
+    pub edition: u64,
+}
+
+/// Create `InitPack` instruction
+pub fn init_pack(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    authority: &Pubkey,
+    store: &Pubkey,
+    whitelisted_creator: &Pubkey,
+    args: InitPackSetArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*pack_config, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(*store, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(*whitelisted_creator, false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::InitPack(args), accounts)
+}
+
+/// Creates 'AddCardToPack' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn add_card_to_pack(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    authority: &Pubkey,
+    master_edition: &Pubkey,
+    master_metadata: &Pubkey,
+    mint: &Pubkey,
+    source: &Pubkey,
+    token_account: &Pubkey,
+    store: &Pubkey,
+    args: AddCardToPackArgs,
+) -> Instruction {
+    let (program_authority, _) = find_program_authority(program_id);
+    let (pack_card, _) = find_pack_card_program_address(program_id, pack_set, args.index);
+
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new(*pack_config, false),
+        AccountMeta::new(pack_card, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(*master_edition, false),
+        AccountMeta::new_readonly(*master_metadata, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(program_authority, false),
+        AccountMeta::new_readonly(*store, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::AddCardToPack(args),
+        accounts,
+    )
+}
+
+/// Creates `AddVoucherToPack` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn add_voucher_to_pack(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_voucher: &Pubkey,
+    authority: &Pubkey,
+    voucher_owner: &Pubkey,
+    master_edition: &Pubkey,
+    master_metadata: &Pubkey,
+    mint: &Pubkey,
+    source: &Pubkey,
+    store: &Pubkey,
+    token_account: &Option<Pubkey>,
+) -> Instruction {
+    let (voucher_lookup, _) =
+        find_voucher_lookup_program_address(program_id, pack_set, master_edition);
+
+    let mut accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new(*pack_voucher, false),
+        AccountMeta::new(voucher_lookup, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(*voucher_owner, true),
+        AccountMeta::new_readonly(*master_edition, false),
+        AccountMeta::new_readonly(*master_metadata, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new(*source, false),
+        AccountMeta::new_readonly(*store, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    if let Some(token_account) = token_account {
+        let (program_authority, _) = find_program_authority(program_id);
+
+        accounts.push(AccountMeta::new(*token_account, false));
+        accounts.push(AccountMeta::new_readonly(program_authority, false));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::AddVoucherToPack,
+        accounts,
     )
 }
 
@@ -547,20 +1282,28 @@ pub fn delete_pack(
 }
 
 /// Create `DeletePackCard` instruction
+///
+/// `pack_config` isn't set for pack sets created before pack config key was stored
+#[allow(clippy::too_many_arguments)]
 pub fn delete_pack_card(
     program_id: &Pubkey,
     pack_set: &Pubkey,
-    pack_card: &Pubkey,
+    pack_config: &Option<Pubkey>,
     authority: &Pubkey,
     refunder: &Pubkey,
     new_master_edition_owner: &Pubkey,
     token_account: &Pubkey,
+    last_index: u32,
+    args: DeletePackCardArgs,
 ) -> Instruction {
     let (program_authority, _) = find_program_authority(program_id);
+    let index = args.index.unwrap_or(last_index);
+    let (pack_card, _) = find_pack_card_program_address(program_id, pack_set, index);
+    let (last_pack_card, _) = find_pack_card_program_address(program_id, pack_set, last_index);
 
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new(*pack_set, false),
-        AccountMeta::new(*pack_card, false),
+        AccountMeta::new(pack_card, false),
         AccountMeta::new_readonly(*authority, true),
         AccountMeta::new(*refunder, false),
         AccountMeta::new(*new_master_edition_owner, false),
@@ -569,28 +1312,70 @@ pub fn delete_pack_card(
         AccountMeta::new_readonly(sysvar::rent::id(), false),
         AccountMeta::new_readonly(spl_token::id(), false),
     ];
+    if let Some(pack_config) = pack_config {
+        accounts.push(AccountMeta::new(*pack_config, false));
+    }
+    if index != last_index {
+        accounts.push(AccountMeta::new(last_pack_card, false));
+    }
 
-    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::DeletePackCard, accounts)
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::DeletePackCard(args),
+        accounts,
+    )
 }
 
 /// Create `DeletePackVoucher` instruction
+///
+/// `master_token_accounts` are new MasterEdition owner and program token account,
+/// they are required only if voucher MasterEdition is held by the program
+#[allow(clippy::too_many_arguments)]
 pub fn delete_pack_voucher(
     program_id: &Pubkey,
     pack_set: &Pubkey,
-    pack_voucher: &Pubkey,
     authority: &Pubkey,
     refunder: &Pubkey,
+    master_token_accounts: &Option<(Pubkey, Pubkey)>,
+    voucher_master: &Pubkey,
+    last_voucher_master: &Pubkey,
+    last_index: u32,
+    args: DeletePackVoucherArgs,
 ) -> Instruction {
-    let accounts = vec![
+    let index = args.index.unwrap_or(last_index);
+    let (pack_voucher, _) = find_pack_voucher_program_address(program_id, pack_set, index);
+    let (last_pack_voucher, _) =
+        find_pack_voucher_program_address(program_id, pack_set, last_index);
+    let (voucher_lookup, _) =
+        find_voucher_lookup_program_address(program_id, pack_set, voucher_master);
+    let (last_voucher_lookup, _) =
+        find_voucher_lookup_program_address(program_id, pack_set, last_voucher_master);
+
+    let mut accounts = vec![
         AccountMeta::new(*pack_set, false),
-        AccountMeta::new(*pack_voucher, false),
+        AccountMeta::new(pack_voucher, false),
         AccountMeta::new_readonly(*authority, true),
         AccountMeta::new(*refunder, false),
     ];
+    if let Some((new_master_edition_owner, token_account)) = master_token_accounts {
+        let (program_authority, _) = find_program_authority(program_id);
+
+        accounts.push(AccountMeta::new(*new_master_edition_owner, false));
+        accounts.push(AccountMeta::new(*token_account, false));
+        accounts.push(AccountMeta::new_readonly(program_authority, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    }
+    if args.index.is_some() {
+        accounts.push(AccountMeta::new(last_pack_voucher, false));
+        accounts.push(AccountMeta::new(voucher_lookup, false));
+        accounts.push(AccountMeta::new(last_voucher_lookup, false));
+    } else {
+        accounts.push(AccountMeta::new(voucher_lookup, false));
+    }
 
     Instruction::new_with_borsh(
         *program_id,
-        &NFTPacksInstruction::DeletePackVoucher,
+        &NFTPacksInstruction::DeletePackVoucher(args),
         accounts,
     )
 }
@@ -615,6 +1400,7 @@ pub fn edit_pack(
 pub fn request_card_for_redeem(
     program_id: &Pubkey,
     pack_set: &Pubkey,
+    pack_config: &Pubkey,
     store: &Pubkey,
     edition: &Pubkey,
     edition_mint: &Pubkey,
@@ -622,17 +1408,17 @@ pub fn request_card_for_redeem(
     user_token_acc: &Option<Pubkey>,
     random_oracle: &Pubkey,
     index: u32,
+    allowlist_proof: Option<AllowlistProof>,
 ) -> Instruction {
     let (proving_process, _) =
         find_proving_process_program_address(program_id, pack_set, user_wallet, edition_mint);
 
-    let (pack_config, _) = find_pack_config_program_address(program_id, pack_set);
-
     let (pack_voucher, _) = find_pack_voucher_program_address(program_id, pack_set, index);
+    let (wallet_record, _) = find_wallet_record_program_address(program_id, pack_set, user_wallet);
 
     let mut accounts = vec![
         AccountMeta::new(*pack_set, false),
-        AccountMeta::new(pack_config, false),
+        AccountMeta::new(*pack_config, false),
         AccountMeta::new_readonly(*store, false),
         AccountMeta::new_readonly(*edition, false),
         AccountMeta::new(*edition_mint, false),
@@ -648,39 +1434,94 @@ pub fn request_card_for_redeem(
     if let Some(user_token_account) = user_token_acc {
         accounts.push(AccountMeta::new(*user_token_account, false))
     }
+    accounts.push(AccountMeta::new(wallet_record, false));
 
     Instruction::new_with_borsh(
         *program_id,
-        &NFTPacksInstruction::RequestCardForRedeem(RequestCardToRedeemArgs { index }),
+        &NFTPacksInstruction::RequestCardForRedeem(RequestCardToRedeemArgs {
+            index,
+            allowlist_proof,
+        }),
         accounts,
     )
 }
 
-/// Create `CleanUp` instruction
+/// Create `RequestCardsForRedeem` instruction
 #[allow(clippy::too_many_arguments)]
-pub fn clean_up(program_id: &Pubkey, pack_set: &Pubkey) -> Instruction {
-    let (pack_config, _) = find_pack_config_program_address(program_id, pack_set);
-
-    let accounts = vec![
-        AccountMeta::new(*pack_set, false),
-        AccountMeta::new(pack_config, false),
-    ];
-
-    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::CleanUp, accounts)
-}
-
-/// Create `DeletePackConfig` instruction
-pub fn delete_pack_config(
+pub fn request_cards_for_redeem(
     program_id: &Pubkey,
     pack_set: &Pubkey,
-    authority: &Pubkey,
-    refunder: &Pubkey,
+    pack_config: &Pubkey,
+    store: &Pubkey,
+    edition: &Pubkey,
+    edition_mint: &Pubkey,
+    user_wallet: &Pubkey,
+    user_token_acc: &Option<Pubkey>,
+    random_oracle: &Pubkey,
+    index: u32,
+    count: u32,
+    allowlist_proof: Option<AllowlistProof>,
 ) -> Instruction {
-    let (pack_config, _) = find_pack_config_program_address(program_id, pack_set);
+    let (proving_process, _) =
+        find_proving_process_program_address(program_id, pack_set, user_wallet, edition_mint);
+
+    let (pack_voucher, _) = find_pack_voucher_program_address(program_id, pack_set, index);
+    let (wallet_record, _) = find_wallet_record_program_address(program_id, pack_set, user_wallet);
+
+    let mut accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new(*pack_config, false),
+        AccountMeta::new_readonly(*store, false),
+        AccountMeta::new_readonly(*edition, false),
+        AccountMeta::new(*edition_mint, false),
+        AccountMeta::new_readonly(pack_voucher, false),
+        AccountMeta::new(proving_process, false),
+        AccountMeta::new(*user_wallet, true),
+        AccountMeta::new_readonly(*random_oracle, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(user_token_account) = user_token_acc {
+        accounts.push(AccountMeta::new(*user_token_account, false))
+    }
+    accounts.push(AccountMeta::new(wallet_record, false));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::RequestCardsForRedeem(RequestCardsToRedeemArgs {
+            index,
+            count,
+            allowlist_proof,
+        }),
+        accounts,
+    )
+}
+
+/// Create `CleanUp` instruction for pack with `LegacyPackConfig`
+pub fn clean_up(program_id: &Pubkey, pack_set: &Pubkey) -> Instruction {
+    let (pack_config, _) = find_pack_config_program_address(program_id, pack_set);
 
     let accounts = vec![
-        AccountMeta::new_readonly(*pack_set, false),
+        AccountMeta::new(*pack_set, false),
         AccountMeta::new(pack_config, false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::CleanUp, accounts)
+}
+
+/// Create `DeletePackConfig` instruction
+pub fn delete_pack_config(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    authority: &Pubkey,
+    refunder: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*pack_set, false),
+        AccountMeta::new(*pack_config, false),
         AccountMeta::new(*refunder, false),
         AccountMeta::new_readonly(*authority, true),
     ];
@@ -691,3 +1532,539 @@ pub fn delete_pack_config(
         accounts,
     )
 }
+
+/// Create `BuyPack` instruction
+///
+/// `edition` is the number of new voucher edition, `payment` holds buyer token account
+/// and payment mint and is required only if pack is sold for SPL tokens
+#[allow(clippy::too_many_arguments)]
+pub fn buy_pack(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    buyer: &Pubkey,
+    token_account: &Pubkey,
+    new_metadata: &Pubkey,
+    new_edition: &Pubkey,
+    master_edition: &Pubkey,
+    new_mint: &Pubkey,
+    new_mint_authority: &Pubkey,
+    metadata: &Pubkey,
+    metadata_mint: &Pubkey,
+    edition: u64,
+    index: u32,
+    payment: &Option<(Pubkey, Pubkey)>,
+) -> Instruction {
+    let (pack_voucher, _) = find_pack_voucher_program_address(program_id, pack_set, index);
+    let (program_authority, _) = find_program_authority(program_id);
+    let (treasury, _) = find_treasury_program_address(program_id, pack_set);
+
+    let edition_number = edition
+        .checked_div(metaplex_token_metadata::state::EDITION_MARKER_BIT_SIZE)
+        .unwrap();
+    let as_string = edition_number.to_string();
+    let (edition_mark_pda, _) = Pubkey::find_program_address(
+        &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            metaplex_token_metadata::id().as_ref(),
+            metadata_mint.as_ref(),
+            metaplex_token_metadata::state::EDITION.as_bytes(),
+            as_string.as_bytes(),
+        ],
+        &metaplex_token_metadata::id(),
+    );
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*pack_set, false),
+        AccountMeta::new_readonly(pack_voucher, false),
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new_readonly(program_authority, false),
+        AccountMeta::new_readonly(*token_account, false),
+        AccountMeta::new(*new_metadata, false),
+        AccountMeta::new(*new_edition, false),
+        AccountMeta::new(*master_edition, false),
+        AccountMeta::new(*new_mint, false),
+        AccountMeta::new_readonly(*new_mint_authority, true),
+        AccountMeta::new(*metadata, false),
+        AccountMeta::new_readonly(*metadata_mint, false),
+        AccountMeta::new(edition_mark_pda, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(metaplex_token_metadata::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some((buyer_token_account, payment_mint)) = payment {
+        let (treasury_token_account, _) =
+            find_treasury_token_program_address(program_id, pack_set, payment_mint);
+
+        accounts.push(AccountMeta::new(*buyer_token_account, false));
+        accounts.push(AccountMeta::new(treasury_token_account, false));
+        accounts.push(AccountMeta::new_readonly(*payment_mint, false));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::BuyPack(BuyPackArgs { index }),
+        accounts,
+    )
+}
+
+/// Create `SetTreasuryRecipients` instruction
+pub fn set_treasury_recipients(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    args: SetTreasuryRecipientsArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetTreasuryRecipients(args),
+        accounts,
+    )
+}
+
+/// Create `WithdrawTreasury` instruction
+///
+/// `recipients` are wallets or token accounts of treasury recipients, `payment` holds
+/// authority token account and payment mint and is required only if pack is sold for SPL tokens
+pub fn withdraw_treasury(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    recipients: &[Pubkey],
+    payment: &Option<(Pubkey, Pubkey)>,
+) -> Instruction {
+    let (treasury, _) = find_treasury_program_address(program_id, pack_set);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*pack_set, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some((authority_token_account, payment_mint)) = payment {
+        let (treasury_token_account, _) =
+            find_treasury_token_program_address(program_id, pack_set, payment_mint);
+
+        accounts.push(AccountMeta::new(treasury_token_account, false));
+        accounts.push(AccountMeta::new(*authority_token_account, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    }
+    for recipient in recipients {
+        accounts.push(AccountMeta::new(*recipient, false));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::WithdrawTreasury,
+        accounts,
+    )
+}
+
+/// Create `OpenPack` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn open_pack(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    store: &Pubkey,
+    edition: &Pubkey,
+    edition_mint: &Pubkey,
+    user_wallet: &Pubkey,
+    user_token_acc: &Pubkey,
+    random_oracle: &Pubkey,
+    new_metadata: &Pubkey,
+    new_edition: &Pubkey,
+    new_mint: &Pubkey,
+    new_mint_authority: &Pubkey,
+    index: u32,
+    cards: &[OpenPackCardAccounts],
+    allowlist_proof: Option<AllowlistProof>,
+) -> Instruction {
+    let (proving_process, _) =
+        find_proving_process_program_address(program_id, pack_set, user_wallet, edition_mint);
+    let (pack_voucher, _) = find_pack_voucher_program_address(program_id, pack_set, index);
+    let (program_authority, _) = find_program_authority(program_id);
+    let (wallet_record, _) = find_wallet_record_program_address(program_id, pack_set, user_wallet);
+
+    let mut accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new(*pack_config, false),
+        AccountMeta::new_readonly(*store, false),
+        AccountMeta::new_readonly(*edition, false),
+        AccountMeta::new(*edition_mint, false),
+        AccountMeta::new_readonly(pack_voucher, false),
+        AccountMeta::new(proving_process, false),
+        AccountMeta::new(*user_wallet, true),
+        AccountMeta::new_readonly(*random_oracle, false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(*user_token_acc, false),
+        AccountMeta::new_readonly(program_authority, false),
+        AccountMeta::new(*new_metadata, false),
+        AccountMeta::new(*new_edition, false),
+        AccountMeta::new(*new_mint, false),
+        AccountMeta::new_readonly(*new_mint_authority, true),
+        AccountMeta::new_readonly(metaplex_token_metadata::id(), false),
+        AccountMeta::new(wallet_record, false),
+    ];
+    for card in cards {
+        let (pack_card, _) = find_pack_card_program_address(program_id, pack_set, card.index);
+
+        accounts.push(AccountMeta::new(pack_card, false));
+        accounts.push(AccountMeta::new_readonly(card.token_account, false));
+        accounts.push(AccountMeta::new(card.master_edition, false));
+        accounts.push(AccountMeta::new(card.metadata, false));
+        accounts.push(AccountMeta::new_readonly(card.metadata_mint, false));
+        accounts.push(AccountMeta::new(
+            find_edition_marker_address(&card.metadata_mint, card.edition),
+            false,
+        ));
+    }
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::OpenPack(OpenPackArgs {
+            index,
+            allowlist_proof,
+        }),
+        accounts,
+    )
+}
+
+/// Create `ClaimPacks` instruction
+pub fn claim_packs(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    user_wallet: &Pubkey,
+    voucher_mint: &Pubkey,
+    new_mint_authority: &Pubkey,
+    cards: &[ClaimCardAccounts],
+) -> Instruction {
+    let (proving_process, _) =
+        find_proving_process_program_address(program_id, pack_set, user_wallet, voucher_mint);
+    let (program_authority, _) = find_program_authority(program_id);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*pack_set, false),
+        AccountMeta::new(proving_process, false),
+        AccountMeta::new(*user_wallet, true),
+        AccountMeta::new_readonly(program_authority, false),
+        AccountMeta::new_readonly(*new_mint_authority, true),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(metaplex_token_metadata::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for card in cards {
+        let (pack_card, _) = find_pack_card_program_address(program_id, pack_set, card.index);
+
+        accounts.push(AccountMeta::new(pack_card, false));
+        accounts.push(AccountMeta::new_readonly(card.token_account, false));
+        accounts.push(AccountMeta::new(card.new_mint, false));
+        accounts.push(AccountMeta::new(card.new_metadata, false));
+        accounts.push(AccountMeta::new(card.new_edition, false));
+        accounts.push(AccountMeta::new(
+            find_edition_marker_address(&card.metadata_mint, card.edition),
+            false,
+        ));
+        accounts.push(AccountMeta::new(card.master_edition, false));
+        accounts.push(AccountMeta::new(card.metadata, false));
+        accounts.push(AccountMeta::new_readonly(card.metadata_mint, false));
+    }
+
+    let indexes = cards.iter().map(|card| card.index).collect();
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::ClaimPacks(ClaimPacksArgs { indexes }),
+        accounts,
+    )
+}
+
+/// Find metaplex_token_metadata edition marker address
+fn find_edition_marker_address(metadata_mint: &Pubkey, edition: u64) -> Pubkey {
+    let edition_number = edition
+        .checked_div(metaplex_token_metadata::state::EDITION_MARKER_BIT_SIZE)
+        .unwrap();
+    let as_string = edition_number.to_string();
+    let (edition_mark_pda, _) = Pubkey::find_program_address(
+        &[
+            metaplex_token_metadata::state::PREFIX.as_bytes(),
+            metaplex_token_metadata::id().as_ref(),
+            metadata_mint.as_ref(),
+            metaplex_token_metadata::state::EDITION.as_bytes(),
+            as_string.as_bytes(),
+        ],
+        &metaplex_token_metadata::id(),
+    );
+
+    edition_mark_pda
+}
+
+/// Create `CommitRandomness` instruction
+pub fn commit_randomness(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    round_index: u32,
+    args: CommitRandomnessArgs,
+) -> Instruction {
+    let (randomness_round, _) =
+        find_randomness_round_program_address(program_id, pack_set, round_index);
+
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(randomness_round, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::CommitRandomness(args),
+        accounts,
+    )
+}
+
+/// Create `RevealRandomness` instruction
+pub fn reveal_randomness(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    round_index: u32,
+    args: RevealRandomnessArgs,
+) -> Instruction {
+    let (randomness_round, _) =
+        find_randomness_round_program_address(program_id, pack_set, round_index);
+
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(randomness_round, false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::RevealRandomness(args),
+        accounts,
+    )
+}
+
+/// Create `ResolveDraws` instruction
+pub fn resolve_draws(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    proving_process: &Pubkey,
+    user_wallet: &Pubkey,
+    round_index: u32,
+) -> Instruction {
+    let (wallet_record, _) = find_wallet_record_program_address(program_id, pack_set, user_wallet);
+    let (randomness_round, _) =
+        find_randomness_round_program_address(program_id, pack_set, round_index);
+
+    let accounts = vec![
+        AccountMeta::new_readonly(*pack_set, false),
+        AccountMeta::new(*pack_config, false),
+        AccountMeta::new(*proving_process, false),
+        AccountMeta::new(wallet_record, false),
+        AccountMeta::new(randomness_round, false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::ResolveDraws, accounts)
+}
+
+/// Create `SetRarityTiers` instruction
+pub fn set_rarity_tiers(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    pack_config: &Pubkey,
+    args: SetRarityTiersArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(*pack_config, false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetRarityTiers(args),
+        accounts,
+    )
+}
+
+/// Create `SetPackSlots` instruction
+pub fn set_pack_slots(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    args: SetPackSlotsArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetPackSlots(args),
+        accounts,
+    )
+}
+
+/// Create `SetPityRule` instruction
+pub fn set_pity_rule(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    pack_config: &Pubkey,
+    args: SetPityRuleArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(*pack_config, false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetPityRule(args),
+        accounts,
+    )
+}
+
+/// Create `MigrateAccount` instruction, pack set is required for pack config
+pub fn migrate_account(
+    program_id: &Pubkey,
+    account: &Pubkey,
+    payer: &Pubkey,
+    pack_set: &Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*account, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(pack_set) = pack_set {
+        accounts.push(AccountMeta::new_readonly(*pack_set, false));
+    }
+
+    Instruction::new_with_borsh(*program_id, &NFTPacksInstruction::MigrateAccount, accounts)
+}
+
+/// Create `EditPackCard` instruction
+pub fn edit_pack_card(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    authority: &Pubkey,
+    master_edition: &Pubkey,
+    store: &Pubkey,
+    args: EditPackCardArgs,
+) -> Instruction {
+    let (pack_card, _) = find_pack_card_program_address(program_id, pack_set, args.index);
+
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new(*pack_config, false),
+        AccountMeta::new(pack_card, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(*master_edition, false),
+        AccountMeta::new_readonly(*store, false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::EditPackCard(args),
+        accounts,
+    )
+}
+
+/// Create `SetPackPhases` instruction
+pub fn set_pack_phases(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    args: SetPackPhasesArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetPackPhases(args),
+        accounts,
+    )
+}
+
+/// Create `SetAllowlistRoot` instruction
+pub fn set_allowlist_root(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    args: SetAllowlistRootArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetAllowlistRoot(args),
+        accounts,
+    )
+}
+
+/// Create `SetHolderGate` instruction
+pub fn set_holder_gate(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    args: SetHolderGateArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetHolderGate(args),
+        accounts,
+    )
+}
+
+/// Create `SetWalletVoucherLimit` instruction
+pub fn set_wallet_voucher_limit(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    authority: &Pubkey,
+    args: SetWalletVoucherLimitArgs,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*pack_set, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &NFTPacksInstruction::SetWalletVoucherLimit(args),
+        accounts,
+    )
+}
@@ -9,8 +9,9 @@ pub mod processor;
 pub mod state;
 pub mod utils;
 
-/// Current program version
-pub const PROGRAM_VERSION: u8 = 1;
+/// Current program version, written to every account as version of its layout.
+/// Accounts created before versioning have version 0 until they are migrated with `MigrateAccount`
+pub const PROGRAM_VERSION: u8 = 2;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
@@ -18,7 +19,10 @@ pub mod entrypoint;
 // Export current sdk types for downstream users building with a different sdk version
 pub use solana_program;
 use solana_program::pubkey::Pubkey;
-use state::{PackCard, PackConfig, PackVoucher, ProvingProcess, PREFIX};
+use state::{
+    GateUsage, LegacyPackConfig, PackCard, PackVoucher, ProvingProcess, RandomnessRound,
+    VoucherLookup, WalletRecord, PREFIX, TREASURY_PREFIX,
+};
 
 solana_program::declare_id!("packFeFNZzMfD9aVWL7QbGz1WcU7R9zpf6pvNsw2BLu");
 
@@ -53,10 +57,10 @@ pub fn find_pack_card_program_address(
     )
 }
 
-/// Generates pack config address
+/// Generates address of legacy pack config, see `LegacyPackConfig`
 pub fn find_pack_config_program_address(program_id: &Pubkey, pack: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
-        &[PackConfig::PREFIX.as_bytes(), &pack.to_bytes()],
+        &[LegacyPackConfig::PREFIX.as_bytes(), &pack.to_bytes()],
         program_id,
     )
 }
@@ -77,6 +81,22 @@ pub fn find_pack_voucher_program_address(
     )
 }
 
+/// Generates voucher lookup address
+pub fn find_voucher_lookup_program_address(
+    program_id: &Pubkey,
+    pack: &Pubkey,
+    master: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VoucherLookup::PREFIX.as_bytes(),
+            &pack.to_bytes(),
+            &master.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
 /// Generates proving process address
 pub fn find_proving_process_program_address(
     program_id: &Pubkey,
@@ -94,3 +114,72 @@ pub fn find_proving_process_program_address(
         program_id,
     )
 }
+
+/// Generates wallet record address
+pub fn find_wallet_record_program_address(
+    program_id: &Pubkey,
+    pack: &Pubkey,
+    wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            WalletRecord::PREFIX.as_bytes(),
+            &pack.to_bytes(),
+            &wallet.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates gate usage address of gating NFT
+pub fn find_gate_usage_program_address(
+    program_id: &Pubkey,
+    pack: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            GateUsage::PREFIX.as_bytes(),
+            &pack.to_bytes(),
+            &mint.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates randomness round address
+pub fn find_randomness_round_program_address(
+    program_id: &Pubkey,
+    pack: &Pubkey,
+    index: u32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            RandomnessRound::PREFIX.as_bytes(),
+            &pack.to_bytes(),
+            &index.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates pack treasury address which receives lamports
+pub fn find_treasury_program_address(program_id: &Pubkey, pack: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_PREFIX.as_bytes(), &pack.to_bytes()], program_id)
+}
+
+/// Generates pack treasury token account address which receives SPL tokens
+pub fn find_treasury_token_program_address(
+    program_id: &Pubkey,
+    pack: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TREASURY_PREFIX.as_bytes(),
+            &pack.to_bytes(),
+            &mint.to_bytes(),
+        ],
+        program_id,
+    )
+}
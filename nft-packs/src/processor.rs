@@ -5,35 +5,71 @@ use activate::activate_pack;
 use add_card_to_pack::add_card_to_pack;
 use add_voucher_to_pack::add_voucher_to_pack;
 use borsh::BorshDeserialize;
+use buy_pack::buy_pack;
 use change_authority::transfer_authority;
 use claim_pack::claim_pack;
+use claim_packs::claim_packs;
 use clean_up::clean_up;
 use close_pack::close_pack;
+use commit_randomness::commit_randomness;
 use deactivate::deactivate_pack;
 use delete_pack::delete_pack;
 use delete_pack_card::delete_pack_card;
 use delete_pack_config::delete_pack_config;
 use delete_pack_voucher::delete_pack_voucher;
 use edit_pack::edit_pack;
+use edit_pack_card::edit_pack_card;
 use init_pack::init_pack;
+use migrate_account::migrate_account;
+use open_pack::open_pack;
 use request_card_to_redeem::request_card_for_redeem;
+use request_cards_for_redeem::request_cards_for_redeem;
+use resolve_draws::resolve_draws;
+use reveal_randomness::reveal_randomness;
+use set_allowlist_root::set_allowlist_root;
+use set_holder_gate::set_holder_gate;
+use set_pack_phases::set_pack_phases;
+use set_pack_slots::set_pack_slots;
+use set_pity_rule::set_pity_rule;
+use set_rarity_tiers::set_rarity_tiers;
+use set_treasury_recipients::set_treasury_recipients;
+use set_wallet_voucher_limit::set_wallet_voucher_limit;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+use withdraw_treasury::withdraw_treasury;
 
 pub mod activate;
 pub mod add_card_to_pack;
 pub mod add_voucher_to_pack;
+pub mod buy_pack;
 pub mod change_authority;
 pub mod claim_pack;
+pub mod claim_packs;
 pub mod clean_up;
 pub mod close_pack;
+pub mod commit_randomness;
 pub mod deactivate;
 pub mod delete_pack;
 pub mod delete_pack_card;
 pub mod delete_pack_config;
 pub mod delete_pack_voucher;
 pub mod edit_pack;
+pub mod edit_pack_card;
 pub mod init_pack;
+pub mod migrate_account;
+pub mod open_pack;
 pub mod request_card_to_redeem;
+pub mod request_cards_for_redeem;
+pub mod resolve_draws;
+pub mod reveal_randomness;
+pub mod set_allowlist_root;
+pub mod set_holder_gate;
+pub mod set_pack_phases;
+pub mod set_pack_slots;
+pub mod set_pity_rule;
+pub mod set_rarity_tiers;
+pub mod set_treasury_recipients;
+pub mod set_wallet_voucher_limit;
+pub mod withdraw_treasury;
 
 /// Program state handler.
 pub struct Processor {}
@@ -82,13 +118,13 @@ impl Processor {
                 msg!("Instruction: DeletePack");
                 delete_pack(program_id, accounts)
             }
-            NFTPacksInstruction::DeletePackCard => {
+            NFTPacksInstruction::DeletePackCard(args) => {
                 msg!("Instruction: DeletePackCard");
-                delete_pack_card(program_id, accounts)
+                delete_pack_card(program_id, accounts, args)
             }
-            NFTPacksInstruction::DeletePackVoucher => {
+            NFTPacksInstruction::DeletePackVoucher(args) => {
                 msg!("Instruction: DeletePackVoucher");
-                delete_pack_voucher(program_id, accounts)
+                delete_pack_voucher(program_id, accounts, args)
             }
             NFTPacksInstruction::EditPack(args) => {
                 msg!("Instruction: EditPack");
@@ -106,6 +142,78 @@ impl Processor {
                 msg!("Instruction: DeletePackConfig");
                 delete_pack_config(program_id, accounts)
             }
+            NFTPacksInstruction::BuyPack(args) => {
+                msg!("Instruction: BuyPack");
+                buy_pack(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetTreasuryRecipients(args) => {
+                msg!("Instruction: SetTreasuryRecipients");
+                set_treasury_recipients(program_id, accounts, args)
+            }
+            NFTPacksInstruction::WithdrawTreasury => {
+                msg!("Instruction: WithdrawTreasury");
+                withdraw_treasury(program_id, accounts)
+            }
+            NFTPacksInstruction::OpenPack(args) => {
+                msg!("Instruction: OpenPack");
+                open_pack(program_id, accounts, args)
+            }
+            NFTPacksInstruction::RequestCardsForRedeem(args) => {
+                msg!("Instruction: RequestCardsForRedeem");
+                request_cards_for_redeem(program_id, accounts, args)
+            }
+            NFTPacksInstruction::ClaimPacks(args) => {
+                msg!("Instruction: ClaimPacks");
+                claim_packs(program_id, accounts, args)
+            }
+            NFTPacksInstruction::CommitRandomness(args) => {
+                msg!("Instruction: CommitRandomness");
+                commit_randomness(program_id, accounts, args)
+            }
+            NFTPacksInstruction::RevealRandomness(args) => {
+                msg!("Instruction: RevealRandomness");
+                reveal_randomness(program_id, accounts, args)
+            }
+            NFTPacksInstruction::ResolveDraws => {
+                msg!("Instruction: ResolveDraws");
+                resolve_draws(program_id, accounts)
+            }
+            NFTPacksInstruction::SetRarityTiers(args) => {
+                msg!("Instruction: SetRarityTiers");
+                set_rarity_tiers(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetPackSlots(args) => {
+                msg!("Instruction: SetPackSlots");
+                set_pack_slots(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetPityRule(args) => {
+                msg!("Instruction: SetPityRule");
+                set_pity_rule(program_id, accounts, args)
+            }
+            NFTPacksInstruction::MigrateAccount => {
+                msg!("Instruction: MigrateAccount");
+                migrate_account(program_id, accounts)
+            }
+            NFTPacksInstruction::EditPackCard(args) => {
+                msg!("Instruction: EditPackCard");
+                edit_pack_card(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetPackPhases(args) => {
+                msg!("Instruction: SetPackPhases");
+                set_pack_phases(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetAllowlistRoot(args) => {
+                msg!("Instruction: SetAllowlistRoot");
+                set_allowlist_root(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetHolderGate(args) => {
+                msg!("Instruction: SetHolderGate");
+                set_holder_gate(program_id, accounts, args)
+            }
+            NFTPacksInstruction::SetWalletVoucherLimit(args) => {
+                msg!("Instruction: SetWalletVoucherLimit");
+                set_wallet_voucher_limit(program_id, accounts, args)
+            }
         }
     }
 }
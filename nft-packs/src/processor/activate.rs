@@ -33,6 +33,10 @@ pub fn activate_pack(_program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramR
         return Err(NFTPacksError::CantActivatePack.into());
     }
 
+    pack_set.assert_randomness_committed()?;
+
+    pack_set.assert_enough_distinct_cards()?;
+
     pack_set.pack_state = PackSetState::Activated;
 
     PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
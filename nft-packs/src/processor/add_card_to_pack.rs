@@ -2,13 +2,10 @@
 
 use crate::{
     error::NFTPacksError,
-    find_pack_card_program_address, find_pack_config_program_address, find_program_authority,
+    find_pack_card_program_address, find_program_authority,
     instruction::AddCardToPackArgs,
     math::SafeMath,
-    state::{
-        CleanUpActions, InitPackCardParams, PackCard, PackConfig, PackDistributionType, PackSet,
-        PackSetState, MAX_PACK_CARDS_AMOUNT,
-    },
+    state::{InitPackCardParams, PackCard, PackConfig, PackDistributionType, PackSet},
     utils::*,
 };
 use metaplex::state::Store;
@@ -21,7 +18,6 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
     sysvar::{rent::Rent, Sysvar},
@@ -51,6 +47,7 @@ pub fn add_card_to_pack(
 
     assert_signer(authority_info)?;
     assert_owned_by(pack_set_info, program_id)?;
+    assert_owned_by(pack_config_info, program_id)?;
     assert_owned_by(store_info, &metaplex::id())?;
 
     let store = Store::from_account_info(store_info)?;
@@ -62,37 +59,34 @@ pub fn add_card_to_pack(
         max_supply,
         weight,
         index: _,
+        tier,
     } = args;
 
     let mut pack_set = PackSet::unpack(&pack_set_info.data.borrow_mut())?;
     assert_account_key(authority_info, &pack_set.authority)?;
     assert_account_key(store_info, &pack_set.store)?;
 
-    if pack_set.pack_state != PackSetState::NotActivated {
-        return Err(NFTPacksError::WrongPackState.into());
-    }
-
-    if pack_set.pack_cards.error_add(1)? > MAX_PACK_CARDS_AMOUNT {
-        return Err(NFTPacksError::PackIsFullWithCards.into());
-    }
-
-    let (pack_config_pubkey, config_bump_seed) =
-        find_pack_config_program_address(program_id, pack_set_info.key);
-    assert_account_key(pack_config_info, &pack_config_pubkey)?;
-
-    let pack_config_seeds = &[
-        PackConfig::PREFIX.as_bytes(),
-        &pack_set_info.key.to_bytes()[..32],
-    ];
-
-    let mut pack_config = get_pack_config_data(
-        program_id,
-        pack_config_info,
-        authority_info,
-        pack_config_seeds,
-        config_bump_seed,
-        rent,
-    )?;
+    pack_set.assert_able_to_edit_cards()?;
+
+    pack_set.assert_card_tier(tier)?;
+
+    assert_account_key(pack_config_info, &pack_set.pack_config)?;
+
+    let mut pack_config_data = pack_config_info.data.borrow_mut();
+    let mut pack_config = if PackConfig::is_initialized(&pack_config_data) {
+        PackConfig::load(&mut pack_config_data, pack_set_info.key)?
+    } else {
+        msg!("Initializing pack config...");
+        PackConfig::init(
+            &mut pack_config_data,
+            pack_set_info.key,
+            pack_set.rarity_tiers.len(),
+            pack_set
+                .pity_rule
+                .as_ref()
+                .map(|rule| rule.rare_weight_threshold),
+        )?
+    };
 
     // new pack card index
     let index = pack_set.pack_cards.error_increment()?;
@@ -104,22 +98,20 @@ pub fn add_card_to_pack(
             }
 
             // set max supply to 0 because we use it as weight already
-            pack_config.weights.push((index, max_supply, 0));
+            pack_config.add_card(index, max_supply, 0, tier)?;
         }
         PackDistributionType::Fixed => {
             if max_supply == 0 {
                 return Err(NFTPacksError::WrongMaxSupply.into());
             }
 
-            pack_config.weights.push((index, weight as u32, max_supply));
+            pack_config.add_card(index, weight as u32, max_supply, tier)?;
         }
         PackDistributionType::Unlimited => {
-            pack_config.weights.push((index, weight as u32, 0));
+            pack_config.add_card(index, weight as u32, 0, tier)?;
         }
     }
 
-    pack_config.action_to_do = CleanUpActions::Sort;
-
     let (pack_card_pubkey, bump_seed) =
         find_pack_card_program_address(program_id, pack_set_info.key, index);
     assert_account_key(pack_card_info, &pack_card_pubkey)?;
@@ -195,47 +187,15 @@ pub fn add_card_to_pack(
         token_account: *token_account_info.key,
         max_supply,
         weight,
+        tier,
     });
 
     pack_set.add_pack_card()?;
 
+    pack_set.record_restock(index, max_supply, weight)?;
+
     PackCard::pack(pack_card, *pack_card_info.data.borrow_mut())?;
     PackSet::pack(pack_set, *pack_set_info.data.borrow_mut())?;
-    PackConfig::pack(pack_config, *pack_config_info.data.borrow_mut())?;
 
     Ok(())
 }
-
-/// Returns deserialized pack config data or initialized if it wasn't initialized yet
-pub fn get_pack_config_data<'a>(
-    program_id: &Pubkey,
-    account_info: &AccountInfo<'a>,
-    user_wallet: &AccountInfo<'a>,
-    signers_seeds: &[&[u8]],
-    bump_seed: u8,
-    rent: &Rent,
-) -> Result<PackConfig, ProgramError> {
-    let unpack = PackConfig::unpack(&account_info.data.borrow_mut());
-
-    let proving_process = match unpack {
-        Ok(data) => Ok(data),
-        Err(_) => {
-            create_account::<PackConfig>(
-                program_id,
-                user_wallet.clone(),
-                account_info.clone(),
-                &[&[signers_seeds, &[&[bump_seed]]].concat()],
-                rent,
-            )?;
-
-            msg!("New pack config account was created");
-
-            let mut data = PackConfig::unpack_unchecked(&account_info.data.borrow_mut())?;
-
-            data.init();
-            Ok(data)
-        }
-    };
-
-    proving_process
-}
@@ -2,9 +2,12 @@
 
 use crate::{
     error::NFTPacksError,
-    find_pack_voucher_program_address,
+    find_pack_voucher_program_address, find_program_authority, find_voucher_lookup_program_address,
     math::SafeMath,
-    state::{InitPackVoucherParams, PackSet, PackSetState, PackVoucher},
+    state::{
+        InitPackVoucherParams, InitVoucherLookupParams, PackSet, PackSetState, PackVoucher,
+        VoucherLookup,
+    },
     utils::*,
 };
 use metaplex::state::Store;
@@ -28,6 +31,7 @@ pub fn add_voucher_to_pack(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     let account_info_iter = &mut accounts.iter();
     let pack_set_info = next_account_info(account_info_iter)?;
     let pack_voucher_info = next_account_info(account_info_iter)?;
+    let voucher_lookup_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
     let voucher_owner_info = next_account_info(account_info_iter)?;
     let master_edition_info = next_account_info(account_info_iter)?;
@@ -37,6 +41,9 @@ pub fn add_voucher_to_pack(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     let store_info = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
     let rent = &Rent::from_account_info(rent_info)?;
+    let _system_program_info = next_account_info(account_info_iter)?;
+    let _spl_token_program_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter).ok();
 
     assert_signer(authority_info)?;
     assert_signer(voucher_owner_info)?;
@@ -82,6 +89,31 @@ pub fn add_voucher_to_pack(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     let mut pack_voucher = PackVoucher::unpack_unchecked(&pack_voucher_info.data.borrow_mut())?;
     assert_uninitialized(&pack_voucher)?;
 
+    let (voucher_lookup_pubkey, lookup_bump_seed) =
+        find_voucher_lookup_program_address(program_id, pack_set_info.key, master_edition_info.key);
+    assert_account_key(voucher_lookup_info, &voucher_lookup_pubkey)?;
+
+    let lookup_signers_seeds = &[
+        VoucherLookup::PREFIX.as_bytes(),
+        &pack_set_info.key.to_bytes()[..32],
+        &master_edition_info.key.to_bytes()[..32],
+        &[lookup_bump_seed],
+    ];
+
+    // fails if pack already has voucher with this master edition
+    msg!("Creating voucher lookup account...");
+    create_account::<VoucherLookup>(
+        program_id,
+        authority_info.clone(),
+        voucher_lookup_info.clone(),
+        &[lookup_signers_seeds],
+        rent,
+    )?;
+
+    let mut voucher_lookup =
+        VoucherLookup::unpack_unchecked(&voucher_lookup_info.data.borrow_mut())?;
+    assert_uninitialized(&voucher_lookup)?;
+
     let token_metadata_program_id = metaplex_token_metadata::id();
 
     // Check for v2
@@ -113,15 +145,46 @@ pub fn add_voucher_to_pack(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
         return Err(NFTPacksError::WrongVoucherOwner.into());
     }
 
+    if let Some(token_account_info) = token_account_info {
+        let program_authority_info = next_account_info(account_info_iter)?;
+        let (program_authority, _) = find_program_authority(program_id);
+        assert_account_key(program_authority_info, &program_authority)?;
+
+        // Initialize token account
+        spl_initialize_account(
+            token_account_info.clone(),
+            mint_info.clone(),
+            program_authority_info.clone(),
+            rent_info.clone(),
+        )?;
+
+        // Transfer from source to token account
+        spl_token_transfer(
+            source_info.clone(),
+            token_account_info.clone(),
+            voucher_owner_info.clone(),
+            1, // transfer master edition
+            &[],
+        )?;
+    }
+
     pack_voucher.init(InitPackVoucherParams {
         pack_set: *pack_set_info.key,
         master: *master_edition_info.key,
         metadata: *master_metadata_info.key,
+        token_account: token_account_info.map(|account| *account.key),
+    });
+
+    voucher_lookup.init(InitVoucherLookupParams {
+        pack_set: *pack_set_info.key,
+        master: *master_edition_info.key,
+        index,
     });
 
     pack_set.add_pack_voucher()?;
 
     PackVoucher::pack(pack_voucher, *pack_voucher_info.data.borrow_mut())?;
+    VoucherLookup::pack(voucher_lookup, *voucher_lookup_info.data.borrow_mut())?;
     PackSet::pack(pack_set, *pack_set_info.data.borrow_mut())?;
 
     Ok(())
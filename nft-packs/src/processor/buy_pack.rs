@@ -0,0 +1,168 @@
+//! Buy pack instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    find_pack_voucher_program_address, find_program_authority, find_treasury_program_address,
+    find_treasury_token_program_address,
+    instruction::BuyPackArgs,
+    math::SafeMath,
+    state::{PackSet, PackVoucher, PREFIX, TREASURY_PREFIX},
+    utils::*,
+};
+use metaplex_token_metadata::state::{MasterEditionV2, Metadata};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+use spl_token::state::Account;
+
+/// Process BuyPack instruction
+pub fn buy_pack(program_id: &Pubkey, accounts: &[AccountInfo], args: BuyPackArgs) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let voucher_account = next_account_info(account_info_iter)?;
+    let buyer_account = next_account_info(account_info_iter)?;
+    let treasury_account = next_account_info(account_info_iter)?;
+    let program_authority_account = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let new_metadata_account = next_account_info(account_info_iter)?;
+    let new_edition_account = next_account_info(account_info_iter)?;
+    let master_edition_account = next_account_info(account_info_iter)?;
+    let new_mint_account = next_account_info(account_info_iter)?;
+    let new_mint_authority_account = next_account_info(account_info_iter)?;
+    let metadata_account = next_account_info(account_info_iter)?;
+    let metadata_mint_account = next_account_info(account_info_iter)?;
+    let edition_marker_account = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let rent = &Rent::from_account_info(rent_account)?;
+    let _token_metadata_account = next_account_info(account_info_iter)?;
+    let token_program_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+
+    // Validate owners
+    assert_owned_by(pack_set_account, program_id)?;
+    assert_owned_by(voucher_account, program_id)?;
+
+    assert_signer(buyer_account)?;
+
+    let pack_set = PackSet::unpack(&pack_set_account.data.borrow())?;
+    pack_set.assert_activated()?;
+    let price = pack_set.get_price()?;
+
+    let (pack_voucher_key, _) =
+        find_pack_voucher_program_address(program_id, pack_set_account.key, args.index);
+    assert_account_key(voucher_account, &pack_voucher_key)?;
+
+    let voucher = PackVoucher::unpack(&voucher_account.data.borrow())?;
+    assert_account_key(pack_set_account, &voucher.pack_set)?;
+    assert_account_key(master_edition_account, &voucher.master)?;
+    assert_account_key(metadata_account, &voucher.metadata)?;
+
+    let voucher_token_account = voucher
+        .token_account
+        .ok_or(NFTPacksError::VoucherNotInCustody)?;
+    assert_account_key(token_account, &voucher_token_account)?;
+
+    let master_metadata = Metadata::from_account_info(metadata_account)?;
+    assert_account_key(metadata_mint_account, &master_metadata.mint)?;
+
+    let master_edition = MasterEditionV2::from_account_info(master_edition_account)?;
+
+    let (program_authority_key, bump_seed) = find_program_authority(program_id);
+    assert_account_key(program_authority_account, &program_authority_key)?;
+
+    let (treasury_key, _) = find_treasury_program_address(program_id, pack_set_account.key);
+    assert_account_key(treasury_account, &treasury_key)?;
+
+    // Take payment
+    match pack_set.payment_mint {
+        Some(payment_mint) => {
+            let buyer_token_account = next_account_info(account_info_iter)?;
+            let treasury_token_account = next_account_info(account_info_iter)?;
+            let payment_mint_account = next_account_info(account_info_iter)?;
+            assert_account_key(payment_mint_account, &payment_mint)?;
+
+            let (treasury_token_key, treasury_token_bump_seed) =
+                find_treasury_token_program_address(
+                    program_id,
+                    pack_set_account.key,
+                    &payment_mint,
+                );
+            assert_account_key(treasury_token_account, &treasury_token_key)?;
+
+            if treasury_token_account.data_is_empty() {
+                msg!("Creating pack treasury token account...");
+                create_account::<Account>(
+                    &spl_token::id(),
+                    buyer_account.clone(),
+                    treasury_token_account.clone(),
+                    &[&[
+                        TREASURY_PREFIX.as_bytes(),
+                        &pack_set_account.key.to_bytes()[..32],
+                        &payment_mint.to_bytes()[..32],
+                        &[treasury_token_bump_seed],
+                    ]],
+                    rent,
+                )?;
+
+                spl_initialize_account(
+                    treasury_token_account.clone(),
+                    payment_mint_account.clone(),
+                    treasury_account.clone(),
+                    rent_account.clone(),
+                )?;
+            }
+
+            let buyer_token = Account::unpack(&buyer_token_account.data.borrow())?;
+            if buyer_token.mint != payment_mint {
+                return Err(NFTPacksError::WrongPaymentMint.into());
+            }
+
+            spl_token_transfer(
+                buyer_token_account.clone(),
+                treasury_token_account.clone(),
+                buyer_account.clone(),
+                price,
+                &[],
+            )?;
+        }
+        None => {
+            invoke(
+                &system_instruction::transfer(buyer_account.key, treasury_account.key, price),
+                &[
+                    buyer_account.clone(),
+                    treasury_account.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        }
+    }
+
+    // Mint voucher edition
+    spl_token_metadata_mint_new_edition_from_master_edition_via_token(
+        new_metadata_account,
+        new_edition_account,
+        new_mint_account,
+        new_mint_authority_account,
+        buyer_account,
+        program_authority_account,
+        token_account,
+        metadata_account,
+        master_edition_account,
+        metadata_mint_account,
+        edition_marker_account,
+        token_program_account,
+        system_program_account,
+        rent_account,
+        master_edition.supply.error_increment()?,
+        &[PREFIX.as_bytes(), program_id.as_ref(), &[bump_seed]],
+    )?;
+
+    Ok(())
+}
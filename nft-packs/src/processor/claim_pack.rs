@@ -5,7 +5,9 @@ use crate::{
     find_pack_card_program_address, find_program_authority,
     instruction::ClaimPackArgs,
     math::SafeMath,
-    state::{PackCard, PackDistributionType, PackSet, ProvingProcess, PREFIX},
+    state::{
+        PackCard, PackDistributionType, PackSetView, ProvingProcess, RandomnessSource, PREFIX,
+    },
     utils::*,
 };
 use metaplex_token_metadata::state::{MasterEditionV2, Metadata};
@@ -47,22 +49,80 @@ pub fn claim_pack(
 
     // Validate owners
     assert_owned_by(pack_set_account, program_id)?;
-    assert_owned_by(randomness_oracle_account, &randomness_oracle_program::id())?;
 
     assert_signer(&user_wallet_account)?;
 
-    let pack_set = PackSet::unpack(&pack_set_account.data.borrow())?;
+    let pack_set_data = pack_set_account.data.borrow();
+    let pack_set = PackSetView::load(&pack_set_data)?;
+
+    // Packs with other randomness sources don't read the oracle
+    if pack_set.randomness_source()? == RandomnessSource::Oracle {
+        assert_owned_by(randomness_oracle_account, &randomness_oracle_program::id())?;
+    }
     let mut proving_process = ProvingProcess::unpack(&proving_process_account.data.borrow_mut())?;
     let ClaimPackArgs { index } = args;
 
     assert_account_key(user_wallet_account, &proving_process.wallet_key)?;
     assert_account_key(pack_set_account, &proving_process.pack_set)?;
 
+    claim_card(
+        program_id,
+        pack_set_account,
+        &pack_set,
+        &mut proving_process,
+        pack_card_account,
+        user_wallet_account,
+        program_authority_account,
+        user_token_account,
+        new_metadata_account,
+        new_edition_account,
+        master_edition_account,
+        new_mint_account,
+        new_mint_authority_account,
+        metadata_account,
+        metadata_mint_account,
+        edition_marker_account,
+        token_program_account,
+        system_program_account,
+        rent_account,
+        index,
+    )?;
+
+    // Update state
+    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
+
+    Ok(())
+}
+
+/// Mint new edition of card from proving process to user
+#[allow(clippy::too_many_arguments)]
+pub fn claim_card<'a, D: AsRef<[u8]>>(
+    program_id: &Pubkey,
+    pack_set_account: &AccountInfo<'a>,
+    pack_set: &PackSetView<D>,
+    proving_process: &mut ProvingProcess,
+    pack_card_account: &AccountInfo<'a>,
+    user_wallet_account: &AccountInfo<'a>,
+    program_authority_account: &AccountInfo<'a>,
+    user_token_account: &AccountInfo<'a>,
+    new_metadata_account: &AccountInfo<'a>,
+    new_edition_account: &AccountInfo<'a>,
+    master_edition_account: &AccountInfo<'a>,
+    new_mint_account: &AccountInfo<'a>,
+    new_mint_authority_account: &AccountInfo<'a>,
+    metadata_account: &AccountInfo<'a>,
+    metadata_mint_account: &AccountInfo<'a>,
+    edition_marker_account: &AccountInfo<'a>,
+    token_program_account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    rent_account: &AccountInfo<'a>,
+    index: u32,
+) -> ProgramResult {
     // Increment total redeemed cards
     proving_process.cards_redeemed = proving_process.cards_redeemed.error_increment()?;
 
     // Check if cards are exhausted
-    if pack_set.allowed_amount_to_redeem == proving_process.cards_redeemed {
+    if pack_set.allowed_amount_to_redeem() == proving_process.cards_redeemed {
         proving_process.is_exhausted = true;
     }
 
@@ -73,6 +133,9 @@ pub fn claim_pack(
 
     let mut pack_card = PackCard::unpack(&pack_card_account.data.borrow())?;
     assert_account_key(pack_set_account, &pack_card.pack_set)?;
+    assert_account_key(master_edition_account, &pack_card.master)?;
+    assert_account_key(metadata_account, &pack_card.metadata)?;
+    assert_account_key(user_token_account, &pack_card.token_account)?;
 
     // Obtain master metadata instance
     let master_metadata = Metadata::from_account_info(metadata_account)?;
@@ -92,7 +155,7 @@ pub fn claim_pack(
         return Err(NFTPacksError::UserCantRedeemThisCard.into());
     }
 
-    if pack_set.distribution_type != PackDistributionType::Unlimited {
+    if pack_set.distribution_type()? != PackDistributionType::Unlimited {
         pack_card.decrement_supply()?;
     }
 
@@ -116,8 +179,6 @@ pub fn claim_pack(
         &[PREFIX.as_bytes(), program_id.as_ref(), &[bump_seed]],
     )?;
 
-    // Update state
-    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
     PackCard::pack(pack_card, *pack_card_account.data.borrow_mut())?;
 
     Ok(())
@@ -0,0 +1,92 @@
+//! Claim packs instruction processing
+
+use crate::{
+    find_program_authority,
+    instruction::ClaimPacksArgs,
+    processor::claim_pack::claim_card,
+    state::{PackSetView, ProvingProcess},
+    utils::*,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Amount of accounts passed for every claimed card
+const CARD_ACCOUNTS_LEN: usize = 9;
+
+/// Process ClaimPacks instruction
+pub fn claim_packs(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClaimPacksArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let proving_process_account = next_account_info(account_info_iter)?;
+    let user_wallet_account = next_account_info(account_info_iter)?;
+    let program_authority_account = next_account_info(account_info_iter)?;
+    let new_mint_authority_account = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let _token_metadata_account = next_account_info(account_info_iter)?;
+    let token_program_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+
+    // Validate owners
+    assert_owned_by(pack_set_account, program_id)?;
+    assert_owned_by(proving_process_account, program_id)?;
+
+    assert_signer(&user_wallet_account)?;
+
+    let pack_set_data = pack_set_account.data.borrow();
+    let pack_set = PackSetView::load(&pack_set_data)?;
+    let mut proving_process = ProvingProcess::unpack(&proving_process_account.data.borrow())?;
+
+    assert_account_key(user_wallet_account, &proving_process.wallet_key)?;
+    assert_account_key(pack_set_account, &proving_process.pack_set)?;
+
+    let (program_authority_key, _) = find_program_authority(program_id);
+    assert_account_key(program_authority_account, &program_authority_key)?;
+
+    let cards_accounts = account_info_iter.as_slice();
+    if args.indexes.is_empty() || cards_accounts.len() != args.indexes.len() * CARD_ACCOUNTS_LEN {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    for (index, card_accounts) in args
+        .indexes
+        .iter()
+        .zip(cards_accounts.chunks_exact(CARD_ACCOUNTS_LEN))
+    {
+        claim_card(
+            program_id,
+            pack_set_account,
+            &pack_set,
+            &mut proving_process,
+            &card_accounts[0],
+            user_wallet_account,
+            program_authority_account,
+            &card_accounts[1],
+            &card_accounts[3],
+            &card_accounts[4],
+            &card_accounts[6],
+            &card_accounts[2],
+            new_mint_authority_account,
+            &card_accounts[7],
+            &card_accounts[8],
+            &card_accounts[5],
+            token_program_account,
+            system_program_account,
+            rent_account,
+            *index,
+        )?;
+    }
+
+    // Update state
+    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
+
+    Ok(())
+}
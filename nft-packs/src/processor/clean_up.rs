@@ -2,13 +2,13 @@
 
 use crate::{
     error::NFTPacksError,
-    find_pack_config_program_address,
-    state::{CleanUpActions, PackConfig, PackDistributionType, PackSet, PackSetState},
+    state::{CleanUpActions, LegacyPackConfig, PackDistributionType, PackSetState, PackSetView},
     utils::*,
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    msg,
     program_pack::Pack,
     pubkey::Pubkey,
 };
@@ -19,21 +19,33 @@ pub fn clean_up(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult
     let pack_set_info = next_account_info(account_info_iter)?;
     let pack_config_info = next_account_info(account_info_iter)?;
 
-    let mut pack_set = PackSet::unpack(&pack_set_info.data.borrow_mut())?;
+    assert_owned_by(pack_set_info, program_id)?;
 
-    if pack_set.pack_state == PackSetState::NotActivated {
+    let mut pack_set_data = pack_set_info.data.borrow_mut();
+    let mut pack_set = PackSetView::load_mut(&mut pack_set_data)?;
+
+    if pack_set.pack_state()? == PackSetState::NotActivated {
         return Err(NFTPacksError::WrongPackState.into());
     }
 
-    let (pack_config_pubkey, _) = find_pack_config_program_address(program_id, pack_set_info.key);
-    assert_account_key(pack_config_info, &pack_config_pubkey)?;
-    let mut pack_config = PackConfig::unpack(&pack_config_info.data.borrow_mut())?;
+    let is_legacy_pack_config = assert_pack_config(
+        program_id,
+        pack_set_info.key,
+        &pack_set.pack_config(),
+        pack_config_info,
+    )?;
+    if !is_legacy_pack_config {
+        msg!("Pack config is updated on draw and doesn't need clean up");
+        return Ok(());
+    }
+
+    let mut pack_config = LegacyPackConfig::unpack(&pack_config_info.data.borrow())?;
     match pack_config.action_to_do {
         CleanUpActions::Change(card_index, new_value) => {
             if new_value == 0 {
                 pack_config.remove_at(card_index);
             } else {
-                match pack_set.distribution_type {
+                match pack_set.distribution_type()? {
                     PackDistributionType::MaxSupply => {
                         pack_config.change_weight(card_index, new_value)?;
                     }
@@ -43,19 +55,15 @@ pub fn clean_up(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult
                 }
             }
             pack_set.decrement_supply()?;
-            pack_config.action_to_do = CleanUpActions::None;
-            PackSet::pack(pack_set, *pack_set_info.data.borrow_mut())?;
-            PackConfig::pack(pack_config, *pack_config_info.data.borrow_mut())?;
-
-            Ok(())
         }
         CleanUpActions::Sort => {
             pack_config.sort();
-            pack_config.action_to_do = CleanUpActions::None;
-            PackConfig::pack(pack_config, *pack_config_info.data.borrow_mut())?;
-
-            Ok(())
         }
-        CleanUpActions::None => Ok(()),
+        CleanUpActions::None => return Ok(()),
     }
+
+    pack_config.action_to_do = CleanUpActions::None;
+    LegacyPackConfig::pack(pack_config, *pack_config_info.data.borrow_mut())?;
+
+    Ok(())
 }
@@ -0,0 +1,76 @@
+//! Commit randomness instruction processing
+
+use crate::{
+    find_randomness_round_program_address,
+    instruction::CommitRandomnessArgs,
+    state::{InitRandomnessRoundParams, PackSet, RandomnessRound},
+    utils::*,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+/// Process CommitRandomness instruction
+pub fn commit_randomness(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CommitRandomnessArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+    let randomness_round_account = next_account_info(account_info_iter)?;
+    let rent_account = next_account_info(account_info_iter)?;
+    let rent = &Rent::from_account_info(rent_account)?;
+    let _system_program_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+
+    assert_signer(&authority_account)?;
+
+    let mut pack_set = PackSet::unpack(&pack_set_account.data.borrow_mut())?;
+    assert_account_key(authority_account, &pack_set.authority)?;
+
+    let index = pack_set.commit_randomness(args.commitment)?;
+
+    let (randomness_round_key, bump_seed) =
+        find_randomness_round_program_address(program_id, pack_set_account.key, index);
+    assert_account_key(randomness_round_account, &randomness_round_key)?;
+
+    let signers_seeds = &[
+        RandomnessRound::PREFIX.as_bytes(),
+        &pack_set_account.key.to_bytes()[..32],
+        &index.to_le_bytes(),
+        &[bump_seed],
+    ];
+
+    create_account::<RandomnessRound>(
+        program_id,
+        authority_account.clone(),
+        randomness_round_account.clone(),
+        &[signers_seeds],
+        rent,
+    )?;
+
+    let mut randomness_round =
+        RandomnessRound::unpack_unchecked(&randomness_round_account.data.borrow_mut())?;
+    assert_uninitialized(&randomness_round)?;
+
+    randomness_round.init(InitRandomnessRoundParams {
+        pack_set: *pack_set_account.key,
+        index,
+        commitment: args.commitment,
+    });
+
+    RandomnessRound::pack(
+        randomness_round,
+        *randomness_round_account.data.borrow_mut(),
+    )?;
+    PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
+
+    Ok(())
+}
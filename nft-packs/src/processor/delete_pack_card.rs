@@ -3,19 +3,25 @@
 use crate::{
     error::NFTPacksError,
     find_pack_card_program_address, find_program_authority,
+    instruction::DeletePackCardArgs,
     math::SafeMath,
-    state::{PackCard, PackSet, PackSetState, PREFIX},
+    state::{PackCard, PackConfig, PackDistributionType, PackSet, PackSetState, PREFIX},
     utils::*,
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
 };
 
 /// Process DeletePackCard instruction
-pub fn delete_pack_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+pub fn delete_pack_card(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: DeletePackCardArgs,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let pack_set_account = next_account_info(account_info_iter)?;
     let pack_card_account = next_account_info(account_info_iter)?;
@@ -24,6 +30,8 @@ pub fn delete_pack_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
     let new_master_edition_owner_account = next_account_info(account_info_iter)?;
     let token_account = next_account_info(account_info_iter)?;
     let program_authority_account = next_account_info(account_info_iter)?;
+    let _rent_account = next_account_info(account_info_iter)?;
+    let _spl_token_account = next_account_info(account_info_iter)?;
 
     // Validate owners
     assert_owned_by(pack_set_account, program_id)?;
@@ -46,22 +54,87 @@ pub fn delete_pack_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
         return Err(NFTPacksError::WrongPackState.into());
     }
 
-    // Ensure, that PackCard is last
-    let index = pack_set.pack_cards;
-    let (last_pack_card, _) =
+    // Without index the last PackCard is deleted
+    let last_index = pack_set.pack_cards;
+    let index = args.index.unwrap_or(last_index);
+    let (pack_card_key, _) =
         find_pack_card_program_address(program_id, pack_set_account.key, index);
-    assert_account_key(pack_card_account, &last_pack_card)?;
+    assert_account_key(pack_card_account, &pack_card_key)?;
 
     // Obtain PackCard instance
     let pack_card = PackCard::unpack(&pack_card_account.data.borrow())?;
     assert_account_key(pack_set_account, &pack_card.pack_set)?;
     assert_account_key(token_account, &pack_card.token_account)?;
 
-    // this check will work if someone forgot to claim card
-    if pack_card.max_supply != 0 {
-        return Err(NFTPacksError::NotEmptyPackSet.into());
+    // Pack sets created before pack config key was stored don't have PackConfig to update
+    let pack_config_account = if pack_set.pack_config != Pubkey::default() {
+        let pack_config_account = next_account_info(account_info_iter)?;
+        assert_owned_by(pack_config_account, program_id)?;
+        assert_account_key(pack_config_account, &pack_set.pack_config)?;
+        Some(pack_config_account)
+    } else {
+        None
+    };
+    let mut pack_config_data = pack_config_account.map(|account| account.data.borrow_mut());
+    let mut pack_config = match pack_config_data.as_mut() {
+        Some(data) => Some(PackConfig::load(data, pack_set_account.key)?),
+        None => None,
+    };
+
+    // Cards are drawn only after activation, so cards of not activated pack can be deleted with any supply
+    let is_ended = pack_set.pack_state == PackSetState::Ended;
+    if is_ended {
+        assert_draws_claimed(
+            &pack_card,
+            index,
+            index == last_index,
+            pack_config.as_ref(),
+            &pack_set.distribution_type,
+        )?;
+    }
+
+    if index != last_index {
+        // weights of moved card can't be updated in pack without PackConfig
+        if pack_config.is_none() {
+            return Err(NFTPacksError::CardCantBeMoved.into());
+        }
+
+        let last_pack_card_account = next_account_info(account_info_iter)?;
+        assert_owned_by(last_pack_card_account, program_id)?;
+
+        // Last PackCard is moved to index of deleted one
+        let (last_pack_card_key, _) =
+            find_pack_card_program_address(program_id, pack_set_account.key, last_index);
+        assert_account_key(last_pack_card_account, &last_pack_card_key)?;
+
+        let last_pack_card = PackCard::unpack(&last_pack_card_account.data.borrow())?;
+        assert_account_key(pack_set_account, &last_pack_card.pack_set)?;
+
+        // draws are claimed by card index, so moved card can't have draws which aren't claimed yet
+        if is_ended {
+            assert_draws_claimed(
+                &last_pack_card,
+                last_index,
+                false,
+                pack_config.as_ref(),
+                &pack_set.distribution_type,
+            )?;
+        }
+
+        PackCard::pack(last_pack_card, *pack_card_account.data.borrow_mut())?;
+
+        // Transfer all SOL from the last PackCard and delete its account
+        empty_account_balance(last_pack_card_account, refunder_account)?;
+    } else {
+        // Transfer all SOL from PackCard and delete PackCard account
+        empty_account_balance(pack_card_account, refunder_account)?;
     }
 
+    if let Some(pack_config) = pack_config.as_mut() {
+        pack_config.remove_card(index)?;
+    }
+    pack_set.remove_card_volume(pack_card.weight.into(), pack_card.max_supply)?;
+
     // Obtain PackCard token account instance
     let pack_card_token_account = spl_token::state::Account::unpack(&token_account.data.borrow())?;
 
@@ -77,10 +150,44 @@ pub fn delete_pack_card(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
         &[&[PREFIX.as_bytes(), program_id.as_ref(), &[bump_seed]]],
     )?;
 
-    // Transfer all SOL from PackCard and delete PackCard account
-    empty_account_balance(pack_card_account, refunder_account)?;
-
     // Update state
     PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
     Ok(())
 }
+
+/// Assert all drawn editions of card in ended pack are claimed.
+/// Claims can't be counted for packs without PackConfig and for unlimited packs,
+/// so only the last card of these packs can be deleted
+fn assert_draws_claimed(
+    pack_card: &PackCard,
+    index: u32,
+    is_last: bool,
+    pack_config: Option<&PackConfig>,
+    distribution_type: &PackDistributionType,
+) -> Result<(), ProgramError> {
+    let (_, value, max_supply) = match pack_config {
+        Some(pack_config) => pack_config.get_card(index)?,
+        None if is_last => {
+            // this check will work if someone forgot to claim card
+            if pack_card.max_supply != 0 {
+                return Err(NFTPacksError::NotEmptyPackSet.into());
+            }
+            return Ok(());
+        }
+        None => return Err(NFTPacksError::CardCantBeMoved.into()),
+    };
+
+    // supply of card which isn't drawn yet
+    let supply_to_draw = match distribution_type {
+        PackDistributionType::MaxSupply => value,
+        PackDistributionType::Fixed => max_supply,
+        PackDistributionType::Unlimited if is_last => return Ok(()),
+        PackDistributionType::Unlimited => return Err(NFTPacksError::CardCantBeMoved.into()),
+    };
+
+    if pack_card.max_supply != supply_to_draw {
+        return Err(NFTPacksError::UnclaimedCardDraws.into());
+    }
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 //! DeletePackConfig instruction processing
 
-use crate::{error::NFTPacksError, find_pack_config_program_address, state::PackSet, utils::*};
+use crate::{error::NFTPacksError, state::PackSet, utils::*};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
@@ -28,9 +28,12 @@ pub fn delete_pack_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> Prog
         return Err(NFTPacksError::NotEmptyPackSet.into());
     }
 
-    let (pack_config_pubkey, _) =
-        find_pack_config_program_address(program_id, pack_set_account.key);
-    assert_account_key(pack_config_account, &pack_config_pubkey)?;
+    assert_pack_config(
+        program_id,
+        pack_set_account.key,
+        &pack_set.pack_config,
+        pack_config_account,
+    )?;
 
     empty_account_balance(pack_config_account, refunder_account)?;
 
@@ -1,9 +1,10 @@
 //! Delete pack voucher instruction processing
 
 use crate::{
-    find_pack_voucher_program_address,
+    find_pack_voucher_program_address, find_program_authority, find_voucher_lookup_program_address,
+    instruction::DeletePackVoucherArgs,
     math::SafeMath,
-    state::{PackSet, PackVoucher},
+    state::{PackSet, PackVoucher, VoucherLookup, PREFIX},
     utils::*,
 };
 use solana_program::{
@@ -14,7 +15,11 @@ use solana_program::{
 };
 
 /// Process DeletePackVoucher instruction
-pub fn delete_pack_voucher(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+pub fn delete_pack_voucher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: DeletePackVoucherArgs,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let pack_set_account = next_account_info(account_info_iter)?;
     let pack_voucher_account = next_account_info(account_info_iter)?;
@@ -33,24 +38,129 @@ pub fn delete_pack_voucher(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
 
     pack_set.assert_ended()?;
 
-    // Ensure that provided PackVoucher is last
-    let index = pack_set.pack_vouchers;
-    let (last_pack_voucher, _) =
+    // Without index the last PackVoucher is deleted
+    let last_index = pack_set.pack_vouchers;
+    let index = args.index.unwrap_or(last_index);
+    let (pack_voucher_key, _) =
         find_pack_voucher_program_address(program_id, pack_set_account.key, index);
-    assert_account_key(pack_voucher_account, &last_pack_voucher)?;
+    assert_account_key(pack_voucher_account, &pack_voucher_key)?;
 
     // Obtain PackVoucher instance
     let pack_voucher = PackVoucher::unpack(&pack_voucher_account.data.borrow())?;
     assert_account_key(pack_set_account, &pack_voucher.pack_set)?;
 
+    // Return voucher MasterEdition if it's held by the program
+    if let Some(voucher_token_account) = pack_voucher.token_account {
+        let new_master_edition_owner_account = next_account_info(account_info_iter)?;
+        let token_account = next_account_info(account_info_iter)?;
+        let program_authority_account = next_account_info(account_info_iter)?;
+        let _spl_token_account = next_account_info(account_info_iter)?;
+        assert_account_key(token_account, &voucher_token_account)?;
+
+        let (valid_program_authority, bump_seed) = find_program_authority(program_id);
+        assert_account_key(program_authority_account, &valid_program_authority)?;
+
+        let voucher_token = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+
+        spl_token_transfer(
+            token_account.clone(),
+            new_master_edition_owner_account.clone(),
+            program_authority_account.clone(),
+            voucher_token.amount,
+            &[&[PREFIX.as_bytes(), program_id.as_ref(), &[bump_seed]]],
+        )?;
+    }
+
     // Decrement PackVoucher's counter in PackSet instance
     pack_set.pack_vouchers = pack_set.pack_vouchers.error_decrement()?;
 
-    // Transfer all SOL from PackVoucher and delete PackVoucher account
-    empty_account_balance(pack_voucher_account, refunder_account)?;
+    if args.index.is_none() {
+        let voucher_lookup_account = next_account_info(account_info_iter)?;
+        close_voucher_lookup(
+            program_id,
+            pack_set_account.key,
+            &pack_voucher.master,
+            voucher_lookup_account,
+            refunder_account,
+        )?;
+
+        // Transfer all SOL from PackVoucher and delete PackVoucher account
+        empty_account_balance(pack_voucher_account, refunder_account)?;
+
+        PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
+
+        return Ok(());
+    }
+
+    let last_pack_voucher_account = next_account_info(account_info_iter)?;
+    let voucher_lookup_account = next_account_info(account_info_iter)?;
+    let last_voucher_lookup_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(last_pack_voucher_account, program_id)?;
+
+    // Last PackVoucher is moved to index of deleted one
+    let (last_pack_voucher_key, _) =
+        find_pack_voucher_program_address(program_id, pack_set_account.key, last_index);
+    assert_account_key(last_pack_voucher_account, &last_pack_voucher_key)?;
+
+    let last_pack_voucher = PackVoucher::unpack(&last_pack_voucher_account.data.borrow())?;
+    assert_account_key(pack_set_account, &last_pack_voucher.pack_set)?;
+
+    let (last_voucher_lookup_key, _) = find_voucher_lookup_program_address(
+        program_id,
+        pack_set_account.key,
+        &last_pack_voucher.master,
+    );
+    assert_account_key(last_voucher_lookup_account, &last_voucher_lookup_key)?;
+
+    if index != last_index {
+        PackVoucher::pack(last_pack_voucher, *pack_voucher_account.data.borrow_mut())?;
+
+        // vouchers added before lookups were introduced don't have them
+        if last_voucher_lookup_account.owner == program_id {
+            let mut last_voucher_lookup =
+                VoucherLookup::unpack(&last_voucher_lookup_account.data.borrow())?;
+            last_voucher_lookup.index = index;
+            VoucherLookup::pack(
+                last_voucher_lookup,
+                *last_voucher_lookup_account.data.borrow_mut(),
+            )?;
+        }
+    }
+
+    close_voucher_lookup(
+        program_id,
+        pack_set_account.key,
+        &pack_voucher.master,
+        voucher_lookup_account,
+        refunder_account,
+    )?;
+
+    // Transfer all SOL from the last PackVoucher and delete its account
+    empty_account_balance(last_pack_voucher_account, refunder_account)?;
 
     // Update state
     PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
 
     Ok(())
 }
+
+/// Close lookup of deleted voucher and transfer its SOL to refunder,
+/// vouchers added before lookups were introduced don't have them
+fn close_voucher_lookup(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    voucher_master: &Pubkey,
+    voucher_lookup_account: &AccountInfo,
+    refunder_account: &AccountInfo,
+) -> ProgramResult {
+    let (voucher_lookup_key, _) =
+        find_voucher_lookup_program_address(program_id, pack_set, voucher_master);
+    assert_account_key(voucher_lookup_account, &voucher_lookup_key)?;
+
+    if voucher_lookup_account.owner == program_id {
+        empty_account_balance(voucher_lookup_account, refunder_account)?;
+    }
+
+    Ok(())
+}
@@ -12,6 +12,7 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
 };
 
 /// Process EditPack instruction
@@ -23,6 +24,7 @@ pub fn edit_pack(
     let account_info_iter = &mut accounts.iter();
     let pack_set_account = next_account_info(account_info_iter)?;
     let authority_account = next_account_info(account_info_iter)?;
+    let clock = Clock::get()?;
 
     assert_signer(&authority_account)?;
 
@@ -32,16 +34,18 @@ pub fn edit_pack(
 
     pack_set.assert_able_to_edit()?;
 
-    apply_changes(&mut pack_set, args)?;
-
-    pack_set.puff_out_data_fields();
+    apply_changes(&mut pack_set, args, clock.unix_timestamp as u64)?;
 
     PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
 
     Ok(())
 }
 
-fn apply_changes(pack_set: &mut PackSet, changes: EditPackSetArgs) -> Result<(), ProgramError> {
+fn apply_changes(
+    pack_set: &mut PackSet,
+    changes: EditPackSetArgs,
+    current_timestamp: u64,
+) -> Result<(), ProgramError> {
     if let Some(new_name) = changes.name {
         if new_name == pack_set.name {
             return Err(NFTPacksError::CantSetTheSameValue.into());
@@ -76,5 +80,17 @@ fn apply_changes(pack_set: &mut PackSet, changes: EditPackSetArgs) -> Result<(),
         pack_set.mutable = new_mutable_value;
     }
 
+    if let Some(allowed_amount_to_redeem) = changes.allowed_amount_to_redeem {
+        pack_set.set_allowed_amount_to_redeem(allowed_amount_to_redeem)?;
+    }
+
+    if changes.redeem_start_date.is_some() || changes.redeem_end_date.is_some() {
+        pack_set.set_redeem_dates(
+            changes.redeem_start_date,
+            changes.redeem_end_date,
+            current_timestamp,
+        )?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,123 @@
+//! EditPackCard instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    find_pack_card_program_address,
+    instruction::EditPackCardArgs,
+    math::SafeMath,
+    state::{PackCard, PackConfig, PackDistributionType, PackSet},
+    utils::*,
+};
+use metaplex::state::Store;
+use metaplex_token_metadata::state::MasterEditionV2;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Process EditPackCard instruction
+pub fn edit_pack_card(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: EditPackCardArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_info = next_account_info(account_info_iter)?;
+    let pack_config_info = next_account_info(account_info_iter)?;
+    let pack_card_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let master_edition_info = next_account_info(account_info_iter)?;
+    let store_info = next_account_info(account_info_iter)?;
+
+    assert_signer(authority_info)?;
+    assert_owned_by(pack_set_info, program_id)?;
+    assert_owned_by(pack_config_info, program_id)?;
+    assert_owned_by(pack_card_info, program_id)?;
+    assert_owned_by(store_info, &metaplex::id())?;
+
+    let store = Store::from_account_info(store_info)?;
+
+    assert_owned_by(master_edition_info, &store.token_metadata_program)?;
+
+    let mut pack_set = PackSet::unpack(&pack_set_info.data.borrow_mut())?;
+    assert_account_key(authority_info, &pack_set.authority)?;
+    assert_account_key(pack_config_info, &pack_set.pack_config)?;
+    assert_account_key(store_info, &pack_set.store)?;
+
+    pack_set.assert_able_to_edit_cards()?;
+
+    let (pack_card_pubkey, _) =
+        find_pack_card_program_address(program_id, pack_set_info.key, args.index);
+    assert_account_key(pack_card_info, &pack_card_pubkey)?;
+
+    let mut pack_card = PackCard::unpack(&pack_card_info.data.borrow())?;
+    assert_account_key(pack_set_info, &pack_card.pack_set)?;
+    assert_account_key(master_edition_info, &pack_card.master)?;
+
+    let max_supply = match args.max_supply {
+        Some(max_supply) if max_supply == pack_card.max_supply => {
+            return Err(NFTPacksError::CantSetTheSameValue.into());
+        }
+        Some(max_supply) => max_supply,
+        None => pack_card.max_supply,
+    };
+    let weight = match args.weight {
+        Some(weight) if weight == pack_card.weight => {
+            return Err(NFTPacksError::CantSetTheSameValue.into());
+        }
+        Some(weight) => weight,
+        None => pack_card.weight,
+    };
+
+    let mut pack_config_data = pack_config_info.data.borrow_mut();
+    let mut pack_config = PackConfig::load(&mut pack_config_data, pack_set_info.key)?;
+
+    // card max supply is decremented on claim and PackConfig supply on draw,
+    // cards drawn but not claimed yet stay in new max supply
+    let (_, value, config_max_supply) = pack_config.get_card(args.index)?;
+    let supply_left = match pack_set.distribution_type {
+        PackDistributionType::MaxSupply => value,
+        PackDistributionType::Fixed => config_max_supply,
+        PackDistributionType::Unlimited => 0,
+    };
+    let unclaimed_supply = pack_card.max_supply.error_sub(supply_left)?;
+    let new_supply_left = max_supply
+        .error_sub(unclaimed_supply)
+        .map_err(|_| NFTPacksError::WrongMaxSupply)?;
+
+    let master_edition = MasterEditionV2::from_account_info(master_edition_info)?;
+
+    pack_set.add_card_volume(weight.into(), max_supply, &master_edition)?;
+    pack_set.remove_card_volume(pack_card.weight.into(), pack_card.max_supply)?;
+
+    match pack_set.distribution_type {
+        PackDistributionType::MaxSupply => {
+            pack_config.set_value(args.index, new_supply_left)?;
+        }
+        PackDistributionType::Fixed => {
+            pack_config.set_max_supply(args.index, new_supply_left)?;
+            // card which ran out of supply is removed from draws
+            let value = if new_supply_left == 0 {
+                0
+            } else {
+                weight as u32
+            };
+            pack_config.set_value(args.index, value)?;
+        }
+        PackDistributionType::Unlimited => {
+            pack_config.set_value(args.index, weight as u32)?;
+        }
+    }
+
+    pack_card.max_supply = max_supply;
+    pack_card.weight = weight;
+
+    pack_set.record_restock(args.index, max_supply, weight)?;
+
+    PackCard::pack(pack_card, *pack_card_info.data.borrow_mut())?;
+    PackSet::pack(pack_set, *pack_set_info.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -3,7 +3,10 @@
 use crate::{
     error::NFTPacksError,
     instruction::InitPackSetArgs,
-    state::{InitPackSetParams, PackSet, MAX_DESCRIPTION_LEN, MAX_URI_LENGTH},
+    state::{
+        InitPackSetParams, PackConfig, PackSet, MAX_CARDS_TO_REDEEM, MAX_DESCRIPTION_LEN,
+        MAX_URI_LENGTH,
+    },
     utils::*,
 };
 use metaplex::state::{Store, WhitelistedCreator, PREFIX};
@@ -18,12 +21,13 @@ use solana_program::{
 
 /// Process InitPack instruction
 pub fn init_pack(
-    _program_id: &Pubkey,
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
     args: InitPackSetArgs,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let pack_set_account = next_account_info(account_info_iter)?;
+    let pack_config_account = next_account_info(account_info_iter)?;
     let authority_account = next_account_info(account_info_iter)?;
     let store_account = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
@@ -33,6 +37,8 @@ pub fn init_pack(
     let whitelisted_creator_account = next_account_info(account_info_iter).ok();
 
     assert_rent_exempt(rent, pack_set_account)?;
+    assert_rent_exempt(rent, pack_config_account)?;
+    assert_owned_by(pack_config_account, program_id)?;
     assert_signer(authority_account)?;
     assert_owned_by(store_account, &metaplex::id())?;
     assert_admin_whitelisted(
@@ -47,6 +53,11 @@ pub fn init_pack(
         return Err(ProgramError::AccountAlreadyInitialized);
     }
 
+    // Pack config is initialized with the first card once rarity tiers and pity rule are known
+    if PackConfig::is_initialized(&pack_config_account.data.borrow()) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
     if args.uri.len() > MAX_URI_LENGTH {
         return Err(NFTPacksError::UriTooLong.into());
     }
@@ -55,7 +66,7 @@ pub fn init_pack(
         return Err(NFTPacksError::DescriptionTooLong.into());
     }
 
-    if args.allowed_amount_to_redeem == 0 {
+    if args.allowed_amount_to_redeem == 0 || args.allowed_amount_to_redeem > MAX_CARDS_TO_REDEEM {
         return Err(NFTPacksError::WrongAllowedAmountToRedeem.into());
     }
 
@@ -73,6 +84,10 @@ pub fn init_pack(
         }
     }
 
+    if args.price == Some(0) || (args.price.is_none() && args.payment_mint.is_some()) {
+        return Err(NFTPacksError::WrongPrice.into());
+    }
+
     pack_set.init(InitPackSetParams {
         name: args.name,
         description: args.description,
@@ -84,10 +99,13 @@ pub fn init_pack(
         allowed_amount_to_redeem: args.allowed_amount_to_redeem,
         redeem_start_date: redeem_start_date,
         redeem_end_date: args.redeem_end_date,
+        price: args.price,
+        payment_mint: args.payment_mint,
+        randomness_source: args.randomness_source,
+        unique_cards: args.unique_cards,
+        pack_config: *pack_config_account.key,
     });
 
-    pack_set.puff_out_data_fields();
-
     PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
 
     Ok(())
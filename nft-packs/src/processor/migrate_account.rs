@@ -0,0 +1,114 @@
+//! Migrate account instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    find_pack_config_program_address,
+    state::{
+        AccountType, GateUsage, LegacyPackConfig, PackCard, PackConfig, PackSet, PackVoucher,
+        ProvingProcess, RandomnessRound, Versioned, VoucherLookup, WalletRecord,
+    },
+    utils::*,
+    PROGRAM_VERSION,
+};
+use borsh::BorshDeserialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+/// Process MigrateAccount instruction
+pub fn migrate_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let account = next_account_info(account_info_iter)?;
+    let payer_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+    let rent = &Rent::get()?;
+
+    assert_owned_by(account, program_id)?;
+    assert_signer(payer_account)?;
+
+    let account_type = AccountType::try_from_slice(
+        account
+            .data
+            .borrow()
+            .get(..1)
+            .ok_or(ProgramError::InvalidAccountData)?,
+    )?;
+
+    match account_type {
+        AccountType::PackSet | AccountType::PackSetV2 => {
+            migrate::<PackSet>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::PackCard => {
+            migrate::<PackCard>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::PackVoucher => {
+            migrate::<PackVoucher>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::ProvingProcess => {
+            migrate::<ProvingProcess>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::WalletRecord => {
+            migrate::<WalletRecord>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::VoucherLookup => {
+            migrate::<VoucherLookup>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::GateUsage => {
+            migrate::<GateUsage>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::RandomnessRound => {
+            migrate::<RandomnessRound>(account, payer_account, system_program_account, rent)
+        }
+        AccountType::PackConfig => {
+            let pack_set_account = next_account_info(account_info_iter)?;
+
+            // Legacy pack config PDA keeps its borsh layout, it's read with `LegacyPackConfig`
+            let (legacy_pack_config, _) =
+                find_pack_config_program_address(program_id, pack_set_account.key);
+            if *account.key == legacy_pack_config {
+                LegacyPackConfig::unpack(&account.data.borrow())?;
+                msg!("Legacy pack config is stored in its own layout");
+                return Err(NFTPacksError::AccountAlreadyMigrated.into());
+            }
+
+            let mut data = account.data.borrow_mut();
+            let mut pack_config = PackConfig::load(&mut data, pack_set_account.key)?;
+            if pack_config.version() >= PROGRAM_VERSION {
+                return Err(NFTPacksError::AccountAlreadyMigrated.into());
+            }
+
+            pack_config.set_version(PROGRAM_VERSION);
+
+            Ok(())
+        }
+        AccountType::Uninitialized => Err(ProgramError::UninitializedAccount),
+    }
+}
+
+/// Read account in any supported layout and write it back in current one, account created
+/// before versioning is resized to current size first
+fn migrate<'a, T: Pack + IsInitialized + Versioned>(
+    account: &AccountInfo<'a>,
+    payer_account: &AccountInfo<'a>,
+    system_program_account: &AccountInfo<'a>,
+    rent: &Rent,
+) -> ProgramResult {
+    let mut value = T::unpack(&account.data.borrow())?;
+    if value.get_version() >= PROGRAM_VERSION {
+        return Err(NFTPacksError::AccountAlreadyMigrated.into());
+    }
+
+    value.set_version(PROGRAM_VERSION);
+
+    if account.data_len() < T::LEN {
+        resize_account(account, payer_account, system_program_account, rent, T::LEN)?;
+    }
+
+    T::pack(value, &mut account.data.borrow_mut())
+}
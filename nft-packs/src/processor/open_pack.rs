@@ -0,0 +1,136 @@
+//! Open pack instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    find_pack_card_program_address,
+    instruction::{OpenPackArgs, RequestCardToRedeemArgs},
+    processor::{claim_pack::claim_card, request_card_to_redeem::request_card},
+    state::{PackSetView, ProvingProcess},
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+/// Amount of accounts passed for every card which can be minted
+const CARD_ACCOUNTS_LEN: usize = 6;
+
+/// Process OpenPack instruction
+pub fn open_pack(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: OpenPackArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let pack_config_account = next_account_info(account_info_iter)?;
+    let store_account = next_account_info(account_info_iter)?;
+    let edition_data_account = next_account_info(account_info_iter)?;
+    let edition_mint_account = next_account_info(account_info_iter)?;
+    let voucher_account = next_account_info(account_info_iter)?;
+    let proving_process_account = next_account_info(account_info_iter)?;
+    let user_wallet_account = next_account_info(account_info_iter)?;
+    let randomness_oracle_account = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let clock = Clock::from_account_info(clock_info)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let rent = &Rent::from_account_info(rent_info)?;
+    let token_program_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let program_authority_account = next_account_info(account_info_iter)?;
+    let new_metadata_account = next_account_info(account_info_iter)?;
+    let new_edition_account = next_account_info(account_info_iter)?;
+    let new_mint_account = next_account_info(account_info_iter)?;
+    let new_mint_authority_account = next_account_info(account_info_iter)?;
+    let _token_metadata_account = next_account_info(account_info_iter)?;
+    let wallet_record_account = next_account_info(account_info_iter)?;
+
+    // Voucher is burned only when pack is opened for the first time
+    let user_token_account = if proving_process_account.data_is_empty() {
+        Some(user_token_account)
+    } else {
+        None
+    };
+
+    let card_index = request_card(
+        program_id,
+        pack_set_account,
+        pack_config_account,
+        store_account,
+        edition_data_account,
+        edition_mint_account,
+        voucher_account,
+        proving_process_account,
+        user_wallet_account,
+        randomness_oracle_account,
+        Some(wallet_record_account),
+        &clock,
+        rent,
+        user_token_account,
+        // Gate accounts of active pack phase and holder gate go right after wallet record
+        account_info_iter,
+        RequestCardToRedeemArgs {
+            index: args.index,
+            allowlist_proof: args.allowlist_proof,
+        },
+    )?;
+
+    let card_index = match card_index {
+        Some(card_index) => card_index,
+        None => {
+            msg!("Card will be drawn with ResolveDraws once randomness is revealed");
+            return Ok(());
+        }
+    };
+
+    // Card accounts follow gate accounts, drawn card is minted with its accounts
+    let card_accounts = account_info_iter.as_slice();
+    if card_accounts.len() % CARD_ACCOUNTS_LEN != 0 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let (pack_card_key, _) =
+        find_pack_card_program_address(program_id, pack_set_account.key, card_index);
+    let card_accounts = card_accounts
+        .chunks_exact(CARD_ACCOUNTS_LEN)
+        .find(|card_accounts| *card_accounts[0].key == pack_card_key)
+        .ok_or(NFTPacksError::MissingCardAccounts)?;
+
+    let pack_set_data = pack_set_account.data.borrow();
+    let pack_set = PackSetView::load(&pack_set_data)?;
+    let mut proving_process = ProvingProcess::unpack(&proving_process_account.data.borrow())?;
+
+    claim_card(
+        program_id,
+        pack_set_account,
+        &pack_set,
+        &mut proving_process,
+        &card_accounts[0],
+        user_wallet_account,
+        program_authority_account,
+        &card_accounts[1],
+        new_metadata_account,
+        new_edition_account,
+        &card_accounts[2],
+        new_mint_account,
+        new_mint_authority_account,
+        &card_accounts[3],
+        &card_accounts[4],
+        &card_accounts[5],
+        token_program_account,
+        system_program_account,
+        rent_info,
+        card_index,
+    )?;
+
+    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
+
+    Ok(())
+}
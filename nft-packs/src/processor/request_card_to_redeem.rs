@@ -2,18 +2,20 @@
 
 use crate::{
     error::NFTPacksError,
-    find_pack_config_program_address,
     instruction::RequestCardToRedeemArgs,
     math::SafeMath,
     state::{
-        CleanUpActions, InitProvingProcessParams, PackConfig, PackDistributionType, PackSet,
-        PackVoucher, ProvingProcess,
+        CardFilter, CleanUpActions, GateUsage, HolderGate, HolderGateKind, InitGateUsageParams,
+        InitProvingProcessParams, InitWalletRecordParams, LegacyPackConfig, PackConfig,
+        PackDistributionType, PackSetView, PackVoucher, PhaseGate, ProvingProcess,
+        RandomnessSource, RandomnessVersion, VoucherLookup, WalletRecord,
     },
     utils::*,
 };
+use borsh::BorshDeserialize;
 use metaplex::state::Store;
 use metaplex_token_metadata::{
-    state::{Edition, EDITION, PREFIX as EDITION_PREFIX},
+    state::{Creator, Edition, Key, EDITION, PREFIX as METADATA_PREFIX},
     utils::assert_derivation,
 };
 use solana_program::{
@@ -27,6 +29,7 @@ use solana_program::{
     sysvar::{rent::Rent, Sysvar},
 };
 use spl_token::state::Account;
+use std::{convert::TryFrom, slice::Iter};
 
 /// Process RequestCardForRedeem instruction
 pub fn request_card_for_redeem(
@@ -50,10 +53,56 @@ pub fn request_card_for_redeem(
     let rent = &Rent::from_account_info(rent_info)?;
     let _spl_token_account_info = next_account_info(account_info_iter)?;
     let _system_account_info = next_account_info(account_info_iter)?;
-    let user_token_account = next_account_info(account_info_iter).ok();
+    let user_token_account = next_user_token_account(account_info_iter);
+    let wallet_record_account = account_info_iter.next();
 
+    request_card(
+        program_id,
+        pack_set_account,
+        pack_config_account,
+        store_account,
+        edition_data_account,
+        edition_mint_account,
+        voucher_account,
+        proving_process_account,
+        user_wallet_account,
+        randomness_oracle_account,
+        wallet_record_account,
+        &clock,
+        rent,
+        user_token_account,
+        account_info_iter,
+        args,
+    )?;
+
+    Ok(())
+}
+
+/// Select next card to redeem, save it to proving process and return its index.
+/// Card supply is decremented in pack config right away.
+/// If pack randomness isn't known yet draw is saved as pending and `None` is returned.
+/// Gate accounts of active pack phase and pack holder gate are checked only when voucher is opened,
+/// they are read from `gate_accounts_iter` in this order so accounts following them can be read after the call
+#[allow(clippy::too_many_arguments)]
+pub fn request_card<'a>(
+    program_id: &Pubkey,
+    pack_set_account: &AccountInfo<'a>,
+    pack_config_account: &AccountInfo<'a>,
+    store_account: &AccountInfo<'a>,
+    edition_data_account: &AccountInfo<'a>,
+    edition_mint_account: &AccountInfo<'a>,
+    voucher_account: &AccountInfo<'a>,
+    proving_process_account: &AccountInfo<'a>,
+    user_wallet_account: &AccountInfo<'a>,
+    randomness_oracle_account: &AccountInfo<'a>,
+    wallet_record_account: Option<&AccountInfo<'a>>,
+    clock: &Clock,
+    rent: &Rent,
+    user_token_account: Option<&AccountInfo<'a>>,
+    gate_accounts_iter: &mut Iter<AccountInfo<'a>>,
+    args: RequestCardToRedeemArgs,
+) -> Result<Option<u32>, ProgramError> {
     // Validate owners
-    assert_owned_by(randomness_oracle_account, &randomness_oracle_program::id())?;
     assert_owned_by(pack_set_account, program_id)?;
     assert_owned_by(store_account, &metaplex::id())?;
     assert_owned_by(edition_mint_account, &spl_token::id())?;
@@ -61,23 +110,31 @@ pub fn request_card_for_redeem(
         assert_owned_by(user_token_account, &spl_token::id())?;
     }
     assert_owned_by(voucher_account, program_id)?;
-    assert_owned_by(pack_config_account, program_id)?;
-
-    let (pack_config_pubkey, _) =
-        find_pack_config_program_address(program_id, pack_set_account.key);
-    assert_account_key(pack_config_account, &pack_config_pubkey)?;
-
-    let mut pack_config = PackConfig::unpack(&pack_config_account.data.borrow_mut())?;
-
-    pack_config.assert_cleaned_up()?;
 
     let store = Store::from_account_info(store_account)?;
 
     assert_owned_by(edition_data_account, &store.token_metadata_program)?;
     assert_signer(&user_wallet_account)?;
 
-    let pack_set = PackSet::unpack(&pack_set_account.data.borrow())?;
-    assert_account_key(store_account, &pack_set.store)?;
+    let mut pack_set_data = pack_set_account.data.borrow_mut();
+    let mut pack_set = PackSetView::load_mut(&mut pack_set_data)?;
+    assert_account_key(store_account, &pack_set.store())?;
+    let is_legacy_pack_config = assert_pack_config(
+        program_id,
+        pack_set_account.key,
+        &pack_set.pack_config(),
+        pack_config_account,
+    )?;
+
+    let mut pack_config_data = pack_config_account.data.borrow_mut();
+    let mut pack_config = if is_legacy_pack_config {
+        AnyPackConfig::Legacy(LegacyPackConfig::unpack(&pack_config_data)?)
+    } else {
+        AnyPackConfig::Current(PackConfig::load(
+            &mut pack_config_data,
+            pack_set_account.key,
+        )?)
+    };
 
     let proving_process_seeds = &[
         ProvingProcess::PREFIX.as_bytes(),
@@ -87,6 +144,8 @@ pub fn request_card_for_redeem(
     ];
     let bump_seed = assert_derivation(program_id, proving_process_account, proving_process_seeds)?;
 
+    let is_voucher_opened = proving_process_account.data_is_empty();
+
     let mut proving_process = get_proving_process_data(
         program_id,
         proving_process_account,
@@ -99,24 +158,11 @@ pub fn request_card_for_redeem(
         rent,
     )?;
 
-    assert_derivation(
-        program_id,
-        voucher_account,
-        &[
-            PackVoucher::PREFIX.as_bytes(),
-            pack_set_account.key.as_ref(),
-            &args.index.to_le_bytes(),
-        ],
-    )?;
-
-    let voucher = PackVoucher::unpack(&voucher_account.data.borrow_mut())?;
-    assert_account_key(pack_set_account, &voucher.pack_set)?;
-
     assert_derivation(
         &store.token_metadata_program,
         edition_data_account,
         &[
-            EDITION_PREFIX.as_bytes(),
+            METADATA_PREFIX.as_bytes(),
             store.token_metadata_program.as_ref(),
             edition_mint_account.key.as_ref(),
             EDITION.as_bytes(),
@@ -124,7 +170,14 @@ pub fn request_card_for_redeem(
     )?;
 
     let edition = Edition::from_account_info(edition_data_account)?;
-    if edition.parent != voucher.master {
+    let voucher_master = get_voucher_master(
+        program_id,
+        pack_set_account,
+        voucher_account,
+        &edition.parent,
+        args.index,
+    )?;
+    if edition.parent != voucher_master {
         return Err(NFTPacksError::WrongEdition.into());
     }
 
@@ -151,30 +204,307 @@ pub fn request_card_for_redeem(
     pack_set.assert_activated()?;
 
     let current_timestamp = clock.unix_timestamp as u64;
-    if current_timestamp < pack_set.redeem_start_date {
+    if current_timestamp < pack_set.redeem_start_date() {
         return Err(NFTPacksError::WrongRedeemDate.into());
     }
 
-    if let Some(redeem_end_date) = pack_set.redeem_end_date {
+    if let Some(redeem_end_date) = pack_set.redeem_end_date() {
         if current_timestamp > redeem_end_date {
             return Err(NFTPacksError::WrongRedeemDate.into());
         }
     }
 
-    // Check if user already get all the card indexes
-    if (proving_process.cards_to_redeem.len() as u32) == pack_set.allowed_amount_to_redeem {
+    // Phase is required for every draw, its gate and wallet limit apply to opened voucher only
+    let active_phase = pack_set
+        .get_active_phase(current_timestamp)?
+        .filter(|_| is_voucher_opened);
+    if let Some((_, phase)) = &active_phase {
+        assert_phase_gate(&phase.gate, user_wallet_account, gate_accounts_iter)?;
+    }
+
+    // Holder gate accounts follow phase gate accounts
+    if let Some(holder_gate) = pack_set.holder_gate()?.filter(|_| is_voucher_opened) {
+        use_holder_gate(
+            program_id,
+            pack_set_account.key,
+            &holder_gate,
+            &store.token_metadata_program,
+            user_wallet_account,
+            gate_accounts_iter,
+            rent,
+        )?;
+    }
+
+    // Allowlist is checked only when voucher is opened
+    let allowlist_quota = match pack_set.allowlist_root().filter(|_| is_voucher_opened) {
+        Some(root) => {
+            let proof = args
+                .allowlist_proof
+                .as_ref()
+                .ok_or(NFTPacksError::NotInAllowlist)?;
+            if !proof.verify(&root, user_wallet_account.key) {
+                return Err(NFTPacksError::NotInAllowlist.into());
+            }
+
+            proof.quota
+        }
+        None => None,
+    };
+
+    // Check if user already drew all the cards, the same card can be drawn several times
+    if proving_process.draw_counter >= pack_set.allowed_amount_to_redeem() {
         return Err(NFTPacksError::UserRedeemedAllCards.into());
     }
 
-    let random_value = get_random_oracle_value(randomness_oracle_account, &proving_process, &clock)?;
-    let weight_sum = if pack_set.distribution_type == PackDistributionType::MaxSupply {
-        pack_set.total_editions
+    // Wallet record is used by pity rule, wallet limits and allowlist quota
+    let has_phase_limit =
+        matches!(&active_phase, Some((_, phase)) if phase.max_per_wallet.is_some());
+    let max_vouchers_per_wallet = pack_set
+        .max_vouchers_per_wallet()
+        .filter(|_| is_voucher_opened);
+    let is_wallet_record_used = pack_set.pity_rule().is_some()
+        || has_phase_limit
+        || allowlist_quota.is_some()
+        || max_vouchers_per_wallet.is_some();
+    let mut wallet_record = if is_wallet_record_used {
+        Some(get_wallet_record_data(
+            program_id,
+            wallet_record_account.ok_or(ProgramError::NotEnoughAccountKeys)?,
+            user_wallet_account,
+            pack_set_account.key,
+            rent,
+        )?)
     } else {
-        pack_set.total_weight
+        None
     };
 
-    let (next_card_to_redeem, value, max_supply) =
-        pack_config.select_weighted_random(random_value, weight_sum)?;
+    if let (Some((index, phase)), Some(wallet_record)) = (&active_phase, wallet_record.as_mut()) {
+        if has_phase_limit {
+            wallet_record.add_phase_voucher(*index, phase.max_per_wallet)?;
+        }
+    }
+    if let (Some(quota), Some(wallet_record)) = (allowlist_quota, wallet_record.as_mut()) {
+        wallet_record.add_allowlist_voucher(quota)?;
+    }
+    if let (Some(max_vouchers), Some(wallet_record)) =
+        (max_vouchers_per_wallet, wallet_record.as_mut())
+    {
+        wallet_record.add_opened_voucher(max_vouchers)?;
+    }
+
+    // Edition is taken from pack supply on request, so pending draw can't run out of cards.
+    // Legacy pack config applies supply change with `CleanUp`
+    if let AnyPackConfig::Current(_) = &pack_config {
+        pack_set.reserve_edition()?;
+    }
+
+    let random_value = match pack_set.randomness_source()? {
+        RandomnessSource::Oracle => {
+            assert_owned_by(randomness_oracle_account, &randomness_oracle_program::id())?;
+
+            Some(get_random_oracle_value(
+                randomness_oracle_account,
+                pack_set_account.key,
+                &pack_set.randomness_version()?,
+                &proving_process,
+                clock,
+            )?)
+        }
+        RandomnessSource::CommitReveal => {
+            // Draw is resolved with seed of the open round which is known only after reveal
+            let round = pack_set
+                .open_randomness_round()
+                .ok_or(NFTPacksError::RandomnessNotCommitted)?;
+
+            if proving_process.pending_draws > 0 && proving_process.pending_round != round {
+                return Err(NFTPacksError::PendingDrawsNotResolved.into());
+            }
+
+            if proving_process
+                .draw_counter
+                .error_add(proving_process.pending_draws)?
+                >= pack_set.allowed_amount_to_redeem()
+            {
+                return Err(NFTPacksError::UserRedeemedAllCards.into());
+            }
+
+            proving_process.pending_draws = proving_process.pending_draws.error_increment()?;
+            proving_process.pending_round = round;
+
+            None
+        }
+        RandomnessSource::SignedValue { signer } => Some(get_signed_random_value(
+            randomness_oracle_account,
+            &signer,
+            pack_set_account.key,
+            proving_process_account.key,
+            &proving_process,
+        )?),
+    };
+
+    let next_card_to_redeem = match (random_value, &mut pack_config) {
+        (Some(random_value), AnyPackConfig::Legacy(pack_config)) => Some(draw_legacy_card(
+            &pack_set,
+            pack_config,
+            &mut proving_process,
+            random_value,
+        )?),
+        (Some(random_value), AnyPackConfig::Current(pack_config)) => Some(draw_card(
+            &pack_set,
+            pack_config,
+            &mut proving_process,
+            wallet_record.as_mut(),
+            random_value,
+        )?),
+        (None, _) => None,
+    };
+
+    // Legacy pack config is serialized, current one is already updated in place
+    let legacy_pack_config = match pack_config {
+        AnyPackConfig::Legacy(pack_config) => Some(pack_config),
+        AnyPackConfig::Current(_) => None,
+    };
+    if let Some(legacy_pack_config) = legacy_pack_config {
+        LegacyPackConfig::pack(legacy_pack_config, &mut pack_config_data)?;
+    }
+
+    // Update state
+    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
+    if let (Some(wallet_record), Some(wallet_record_account)) =
+        (wallet_record, wallet_record_account)
+    {
+        WalletRecord::pack(wallet_record, *wallet_record_account.data.borrow_mut())?;
+    }
+
+    Ok(next_card_to_redeem)
+}
+
+/// Select next card to redeem with random value, save it to proving process
+/// and decrement its supply in pack config, edition should be reserved in pack set on request.
+/// Wallet record is required if pack has pity rule
+pub fn draw_card<D: AsRef<[u8]>>(
+    pack_set: &PackSetView<D>,
+    pack_config: &mut PackConfig,
+    proving_process: &mut ProvingProcess,
+    wallet_record: Option<&mut WalletRecord>,
+    random_value: u64,
+) -> Result<u32, ProgramError> {
+    let pity_rule = pack_set.pity_rule();
+    if pity_rule.is_some() && wallet_record.is_none() {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let randomness_version = pack_set.randomness_version()?;
+    let (next_card_to_redeem, value, max_supply) = match randomness_version {
+        RandomnessVersion::V0 => pack_config
+            .select_weighted_random(v0_random_value(random_value)?, weight_sum(pack_set)?)?,
+        RandomnessVersion::V1 => {
+            let mut filter = CardFilter::default();
+
+            // Pack with unique cards doesn't give the same card twice for one voucher
+            if pack_set.unique_cards() {
+                filter.excluded_cards = proving_process.cards_to_redeem.keys().copied().collect();
+
+                let cards_left = pack_config.count_cards_left(&filter.excluded_cards)?;
+                let slots_left = pack_set
+                    .allowed_amount_to_redeem()
+                    .saturating_sub(proving_process.draw_counter);
+
+                if cards_left < slots_left {
+                    return Err(NFTPacksError::NotEnoughDistinctCards.into());
+                }
+            }
+
+            filter.tiers = pack_set
+                .get_pack_slot(proving_process.draw_counter as usize)?
+                .map(|slot| slot.tiers);
+
+            // After too many misses only rare cards can be drawn while any of them are left
+            if let (Some(rule), Some(wallet_record)) = (&pity_rule, &wallet_record) {
+                if wallet_record.pity_counter >= rule.max_misses {
+                    let rare_filter = CardFilter {
+                        rare_only: true,
+                        ..filter.clone()
+                    };
+
+                    if pack_config.has_cards(&rare_filter)? {
+                        filter = rare_filter;
+                    }
+                }
+            }
+
+            let rarity_tiers = pack_set.rarity_tiers()?;
+            if rarity_tiers.is_empty() {
+                pack_config.select_weighted_random_filtered(random_value, &filter)?
+            } else {
+                pack_config.select_tiered_random_filtered(random_value, &rarity_tiers, &filter)?
+            }
+        }
+    };
+
+    if let (Some(rule), Some(wallet_record)) = (&pity_rule, wallet_record) {
+        let is_rare = rule.is_rare(&(next_card_to_redeem, value, max_supply));
+        wallet_record.update_pity_counter(is_rare)?;
+    }
+
+    save_drawn_card(proving_process, next_card_to_redeem)?;
+
+    pack_config.decrement_supply(next_card_to_redeem, &pack_set.distribution_type()?)?;
+
+    Ok(next_card_to_redeem)
+}
+
+/// Random value derived with `RandomnessVersion::V0`, pack sets with legacy pack config always use it
+fn v0_random_value(random_value: u64) -> Result<u16, ProgramError> {
+    u16::try_from(random_value).map_err(|_| ProgramError::InvalidArgument)
+}
+
+/// Weight sum used by `RandomnessVersion::V0` selection, it's the pack set total and not the sum of weights left
+fn weight_sum<D: AsRef<[u8]>>(pack_set: &PackSetView<D>) -> Result<u64, ProgramError> {
+    Ok(match pack_set.distribution_type()? {
+        PackDistributionType::MaxSupply => pack_set.total_editions(),
+        _ => pack_set.total_weight(),
+    })
+}
+
+/// Select next card to redeem from legacy pack config and save it to proving process.
+/// Supply change is saved to pack config and applied with `CleanUp` which is required before the next draw
+fn draw_legacy_card(
+    pack_set: &PackSetView<&mut [u8]>,
+    pack_config: &mut LegacyPackConfig,
+    proving_process: &mut ProvingProcess,
+    random_value: u64,
+) -> Result<u32, ProgramError> {
+    pack_config.assert_cleaned_up()?;
+
+    let (next_card_to_redeem, value, max_supply) = pack_config
+        .select_weighted_random(v0_random_value(random_value)?, weight_sum(pack_set)?)?;
+
+    save_drawn_card(proving_process, next_card_to_redeem)?;
+
+    match pack_set.distribution_type()? {
+        PackDistributionType::MaxSupply => {
+            let new_value = value.error_decrement()?;
+            pack_config.action_to_do = CleanUpActions::Change(next_card_to_redeem, new_value);
+        }
+        PackDistributionType::Fixed => {
+            let new_supply = max_supply.error_decrement()?;
+            pack_config.action_to_do = CleanUpActions::Change(next_card_to_redeem, new_supply);
+        }
+        PackDistributionType::Unlimited => {
+            // do nothing because we shouldn't change any values here
+        }
+    }
+
+    Ok(next_card_to_redeem)
+}
+
+/// Count drawn card in proving process
+fn save_drawn_card(
+    proving_process: &mut ProvingProcess,
+    next_card_to_redeem: u32,
+) -> Result<(), ProgramError> {
+    proving_process.draw_counter = proving_process.draw_counter.error_increment()?;
 
     // Increment if card is already redeemed
     // Else insert new field
@@ -190,23 +520,189 @@ pub fn request_card_for_redeem(
         }
     };
 
-    match pack_set.distribution_type {
-        PackDistributionType::MaxSupply => {
-            let new_value = value.error_decrement()?;
-            pack_config.action_to_do = CleanUpActions::Change(next_card_to_redeem, new_value);
-        }
-        PackDistributionType::Fixed => {
-            let new_supply = max_supply.error_decrement()?;
-            pack_config.action_to_do = CleanUpActions::Change(next_card_to_redeem, new_supply);
+    Ok(())
+}
+
+/// Pack config in any supported layout
+enum AnyPackConfig<'a> {
+    Legacy(LegacyPackConfig),
+    Current(PackConfig<'a>),
+}
+
+/// Returns master edition of voucher passed by index or found with voucher lookup of edition parent
+fn get_voucher_master(
+    program_id: &Pubkey,
+    pack_set_account: &AccountInfo,
+    voucher_account: &AccountInfo,
+    edition_parent: &Pubkey,
+    index: u32,
+) -> Result<Pubkey, ProgramError> {
+    if VoucherLookup::is_lookup(&voucher_account.data.borrow()) {
+        assert_derivation(
+            program_id,
+            voucher_account,
+            &[
+                VoucherLookup::PREFIX.as_bytes(),
+                pack_set_account.key.as_ref(),
+                edition_parent.as_ref(),
+            ],
+        )?;
+
+        let voucher_lookup = VoucherLookup::unpack(&voucher_account.data.borrow())?;
+        assert_account_key(pack_set_account, &voucher_lookup.pack_set)?;
+
+        return Ok(voucher_lookup.master);
+    }
+
+    assert_derivation(
+        program_id,
+        voucher_account,
+        &[
+            PackVoucher::PREFIX.as_bytes(),
+            pack_set_account.key.as_ref(),
+            &index.to_le_bytes(),
+        ],
+    )?;
+
+    let voucher = PackVoucher::unpack(&voucher_account.data.borrow())?;
+    assert_account_key(pack_set_account, &voucher.pack_set)?;
+
+    Ok(voucher.master)
+}
+
+/// Check wallet passes pack phase gate, allowlist token is burned.
+/// Gate accounts are gate token account owned by wallet and gate mint required by allowlist only
+fn assert_phase_gate<'a>(
+    gate: &PhaseGate,
+    user_wallet_account: &AccountInfo<'a>,
+    gate_accounts_iter: &mut Iter<AccountInfo<'a>>,
+) -> ProgramResult {
+    let (mint, error) = match gate {
+        PhaseGate::Open => return Ok(()),
+        PhaseGate::Allowlist { mint } => (mint, NFTPacksError::NotInPhaseAllowlist),
+        PhaseGate::TokenHolder { mint } => (mint, NFTPacksError::NotPhaseTokenHolder),
+    };
+
+    let gate_token_account = next_account_info(gate_accounts_iter)?;
+    assert_owned_by(gate_token_account, &spl_token::id())?;
+
+    let gate_token = Account::unpack(&gate_token_account.data.borrow())?;
+    if gate_token.mint != *mint
+        || gate_token.owner != *user_wallet_account.key
+        || gate_token.amount == 0
+    {
+        return Err(error.into());
+    }
+
+    if let PhaseGate::Allowlist { .. } = gate {
+        let gate_mint_account = next_account_info(gate_accounts_iter)?;
+        assert_account_key(gate_mint_account, mint)?;
+
+        burn_tokens(
+            gate_token_account.clone(),
+            gate_mint_account.clone(),
+            user_wallet_account.clone(),
+            1,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Token metadata account in the layout written by token metadata program. Pinned `Metadata` doesn't
+/// know every field before collection, so the whole layout is read here instead of continuing after it.
+/// Fields after `is_mutable` were added later and are zeroed in older metadata, so they are read as empty
+#[derive(BorshDeserialize)]
+struct GateMetadata {
+    key: u8,
+    _update_authority: Pubkey,
+    mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    _uri: String,
+    _seller_fee_basis_points: u16,
+    creators: Option<Vec<Creator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    _token_standard: Option<u8>,
+    collection: Option<MetadataCollection>,
+}
+
+#[derive(BorshDeserialize)]
+struct MetadataCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Check wallet holds NFT which passes pack holder gate and count its use.
+/// Gate accounts are NFT token account owned by wallet, NFT metadata and its gate usage
+fn use_holder_gate<'a>(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    holder_gate: &HolderGate,
+    token_metadata_program: &Pubkey,
+    user_wallet_account: &AccountInfo<'a>,
+    gate_accounts_iter: &mut Iter<AccountInfo<'a>>,
+    rent: &Rent,
+) -> ProgramResult {
+    let nft_token_account = next_account_info(gate_accounts_iter)?;
+    let nft_metadata_account = next_account_info(gate_accounts_iter)?;
+    let gate_usage_account = next_account_info(gate_accounts_iter)?;
+
+    assert_owned_by(nft_token_account, &spl_token::id())?;
+    assert_owned_by(nft_metadata_account, token_metadata_program)?;
+
+    let nft_token = Account::unpack(&nft_token_account.data.borrow())?;
+    if nft_token.owner != *user_wallet_account.key || nft_token.amount == 0 {
+        return Err(NFTPacksError::NotGateHolder.into());
+    }
+
+    assert_derivation(
+        token_metadata_program,
+        nft_metadata_account,
+        &[
+            METADATA_PREFIX.as_bytes(),
+            token_metadata_program.as_ref(),
+            nft_token.mint.as_ref(),
+        ],
+    )?;
+
+    let nft_metadata = GateMetadata::deserialize(&mut &nft_metadata_account.data.borrow()[..])?;
+    // mint stored in metadata confirms the layout was read from its start
+    if nft_metadata.key != Key::MetadataV1 as u8 || nft_metadata.mint != nft_token.mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let is_holder = match &holder_gate.kind {
+        HolderGateKind::Collection { mint } => {
+            nft_metadata.collection.map_or(false, |collection| {
+                collection.verified && collection.key == *mint
+            })
         }
-        PackDistributionType::Unlimited => {
-            // do nothing because we shouldn't change any values here
+        HolderGateKind::VerifiedCreator { creator } => {
+            nft_metadata.creators.map_or(false, |creators| {
+                creators
+                    .iter()
+                    .any(|nft_creator| nft_creator.verified && nft_creator.address == *creator)
+            })
         }
+    };
+    if !is_holder {
+        return Err(NFTPacksError::NotGateHolder.into());
     }
 
-    // Update state
-    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
-    PackConfig::pack(pack_config, *pack_config_account.data.borrow_mut())?;
+    let mut gate_usage = get_gate_usage_data(
+        program_id,
+        gate_usage_account,
+        user_wallet_account,
+        pack_set,
+        &nft_token.mint,
+        rent,
+    )?;
+    gate_usage.add_use(holder_gate.max_uses)?;
+
+    GateUsage::pack(gate_usage, *gate_usage_account.data.borrow_mut())?;
 
     Ok(())
 }
@@ -282,3 +778,91 @@ pub fn get_proving_process_data<'a>(
 
     proving_process
 }
+
+/// Returns optional user token account which goes before wallet record and gate accounts.
+/// It's the only one of them owned by token program so it can be skipped after voucher is opened
+pub fn next_user_token_account<'a, 'b>(
+    account_info_iter: &mut Iter<'a, AccountInfo<'b>>,
+) -> Option<&'a AccountInfo<'b>> {
+    match account_info_iter.as_slice().first() {
+        Some(account) if *account.owner == spl_token::id() => account_info_iter.next(),
+        _ => None,
+    }
+}
+
+/// Returns deserialized wallet record data or initialized if it wasn't initialized yet
+pub fn get_wallet_record_data<'a>(
+    program_id: &Pubkey,
+    account_info: &AccountInfo<'a>,
+    user_wallet: &AccountInfo<'a>,
+    pack_set: &Pubkey,
+    rent: &Rent,
+) -> Result<WalletRecord, ProgramError> {
+    let wallet_record_seeds = &[
+        WalletRecord::PREFIX.as_bytes(),
+        pack_set.as_ref(),
+        user_wallet.key.as_ref(),
+    ];
+    let bump_seed = assert_derivation(program_id, account_info, wallet_record_seeds)?;
+
+    if !account_info.data_is_empty() {
+        return WalletRecord::unpack(&account_info.data.borrow());
+    }
+
+    // Create WalletRecord account on-chain
+    create_account::<WalletRecord>(
+        program_id,
+        user_wallet.clone(),
+        account_info.clone(),
+        &[&[wallet_record_seeds, &[&[bump_seed]]].concat()],
+        rent,
+    )?;
+
+    let mut data = WalletRecord::unpack_unchecked(&account_info.data.borrow())?;
+
+    data.init(InitWalletRecordParams {
+        pack_set: *pack_set,
+        wallet: *user_wallet.key,
+    });
+
+    Ok(data)
+}
+
+/// Returns deserialized gate usage data or initialized if it wasn't initialized yet
+pub fn get_gate_usage_data<'a>(
+    program_id: &Pubkey,
+    account_info: &AccountInfo<'a>,
+    user_wallet: &AccountInfo<'a>,
+    pack_set: &Pubkey,
+    mint: &Pubkey,
+    rent: &Rent,
+) -> Result<GateUsage, ProgramError> {
+    let gate_usage_seeds = &[
+        GateUsage::PREFIX.as_bytes(),
+        pack_set.as_ref(),
+        mint.as_ref(),
+    ];
+    let bump_seed = assert_derivation(program_id, account_info, gate_usage_seeds)?;
+
+    if !account_info.data_is_empty() {
+        return GateUsage::unpack(&account_info.data.borrow());
+    }
+
+    // Create GateUsage account on-chain
+    create_account::<GateUsage>(
+        program_id,
+        user_wallet.clone(),
+        account_info.clone(),
+        &[&[gate_usage_seeds, &[&[bump_seed]]].concat()],
+        rent,
+    )?;
+
+    let mut data = GateUsage::unpack_unchecked(&account_info.data.borrow())?;
+
+    data.init(InitGateUsageParams {
+        pack_set: *pack_set,
+        mint: *mint,
+    });
+
+    Ok(data)
+}
@@ -0,0 +1,83 @@
+//! Request cards to redeem instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    instruction::{RequestCardToRedeemArgs, RequestCardsToRedeemArgs},
+    processor::request_card_to_redeem::{next_user_token_account, request_card},
+    state::PackSetView,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+/// Process RequestCardsForRedeem instruction
+pub fn request_cards_for_redeem(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RequestCardsToRedeemArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let pack_config_account = next_account_info(account_info_iter)?;
+    let store_account = next_account_info(account_info_iter)?;
+    let edition_data_account = next_account_info(account_info_iter)?;
+    let edition_mint_account = next_account_info(account_info_iter)?;
+    let voucher_account = next_account_info(account_info_iter)?;
+    let proving_process_account = next_account_info(account_info_iter)?;
+    let user_wallet_account = next_account_info(account_info_iter)?;
+    let randomness_oracle_account = next_account_info(account_info_iter)?;
+    let clock_info = next_account_info(account_info_iter)?;
+    let clock = Clock::from_account_info(clock_info)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let rent = &Rent::from_account_info(rent_info)?;
+    let _spl_token_account_info = next_account_info(account_info_iter)?;
+    let _system_account_info = next_account_info(account_info_iter)?;
+    let user_token_account = next_user_token_account(account_info_iter);
+    let wallet_record_account = account_info_iter.next();
+
+    {
+        let pack_set_data = pack_set_account.data.borrow();
+        let pack_set = PackSetView::load(&pack_set_data)?;
+        if args.count == 0 || args.count > pack_set.allowed_amount_to_redeem() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Legacy pack config is updated with CleanUp, so it can't be drawn from again in the same instruction
+        if args.count > 1 && pack_set.pack_config() == Pubkey::default() {
+            return Err(NFTPacksError::MultipleDrawsWithLegacyPackConfig.into());
+        }
+    }
+
+    for i in 0..args.count {
+        request_card(
+            program_id,
+            pack_set_account,
+            pack_config_account,
+            store_account,
+            edition_data_account,
+            edition_mint_account,
+            voucher_account,
+            proving_process_account,
+            user_wallet_account,
+            randomness_oracle_account,
+            wallet_record_account,
+            &clock,
+            rent,
+            // Voucher is burned on the first draw only
+            if i == 0 { user_token_account } else { None },
+            // Gate accounts are read on the first draw only
+            account_info_iter,
+            RequestCardToRedeemArgs {
+                index: args.index,
+                allowlist_proof: args.allowlist_proof.clone(),
+            },
+        )?;
+    }
+
+    Ok(())
+}
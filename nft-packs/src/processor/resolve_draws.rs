@@ -0,0 +1,133 @@
+//! Resolve draws instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    math::SafeMath,
+    processor::request_card_to_redeem::draw_card,
+    state::{PackConfig, PackSetView, ProvingProcess, RandomnessRound, WalletRecord},
+    utils::*,
+};
+use metaplex_token_metadata::utils::assert_derivation;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Process ResolveDraws instruction
+pub fn resolve_draws(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let pack_config_account = next_account_info(account_info_iter)?;
+    let proving_process_account = next_account_info(account_info_iter)?;
+    let wallet_record_account = next_account_info(account_info_iter)?;
+    let randomness_round_account = next_account_info(account_info_iter)?;
+    let slot_hashes_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+    assert_owned_by(pack_config_account, program_id)?;
+    assert_owned_by(proving_process_account, program_id)?;
+    assert_owned_by(randomness_round_account, program_id)?;
+
+    let pack_set_data = pack_set_account.data.borrow();
+    let pack_set = PackSetView::load(&pack_set_data)?;
+    assert_account_key(pack_config_account, &pack_set.pack_config())?;
+
+    let mut pack_config_data = pack_config_account.data.borrow_mut();
+    let mut pack_config = PackConfig::load(&mut pack_config_data, pack_set_account.key)?;
+    let mut proving_process = ProvingProcess::unpack(&proving_process_account.data.borrow())?;
+
+    assert_account_key(pack_set_account, &proving_process.pack_set)?;
+    assert_derivation(
+        program_id,
+        proving_process_account,
+        &[
+            ProvingProcess::PREFIX.as_bytes(),
+            pack_set_account.key.as_ref(),
+            proving_process.wallet_key.as_ref(),
+            proving_process.voucher_mint.as_ref(),
+        ],
+    )?;
+
+    if proving_process.pending_draws == 0 {
+        return Err(NFTPacksError::NoPendingDraws.into());
+    }
+
+    let mut randomness_round = RandomnessRound::unpack(&randomness_round_account.data.borrow())?;
+    assert_account_key(pack_set_account, &randomness_round.pack_set)?;
+    assert_derivation(
+        program_id,
+        randomness_round_account,
+        &[
+            RandomnessRound::PREFIX.as_bytes(),
+            pack_set_account.key.as_ref(),
+            &proving_process.pending_round.to_le_bytes(),
+        ],
+    )?;
+
+    // The first resolve after reveal mixes secret with hash of reveal slot, it's unknown to
+    // authority at the moment of reveal, so nobody can predict draws of the round
+    let seed = match randomness_round.seed {
+        Some(seed) => seed,
+        None => {
+            if randomness_round.secret.is_none() {
+                return Err(NFTPacksError::RandomnessNotRevealed.into());
+            }
+
+            let reveal_slot_hash =
+                get_slot_hash(slot_hashes_account, randomness_round.reveal_slot)?;
+            let seed = randomness_round.seal(&reveal_slot_hash)?;
+            RandomnessRound::pack(
+                randomness_round.clone(),
+                *randomness_round_account.data.borrow_mut(),
+            )?;
+
+            seed
+        }
+    };
+
+    // Wallet record is created on draw request if pack has pity rule
+    let mut wallet_record = if pack_set.pity_rule().is_some() {
+        assert_owned_by(wallet_record_account, program_id)?;
+        assert_derivation(
+            program_id,
+            wallet_record_account,
+            &[
+                WalletRecord::PREFIX.as_bytes(),
+                pack_set_account.key.as_ref(),
+                proving_process.wallet_key.as_ref(),
+            ],
+        )?;
+
+        Some(WalletRecord::unpack(&wallet_record_account.data.borrow())?)
+    } else {
+        None
+    };
+
+    while proving_process.pending_draws > 0 {
+        let random_value = derive_random_value(
+            &seed,
+            randomness_round.reveal_slot,
+            pack_set_account.key,
+            &proving_process.voucher_mint,
+            proving_process.draw_counter,
+        );
+
+        draw_card(
+            &pack_set,
+            &mut pack_config,
+            &mut proving_process,
+            wallet_record.as_mut(),
+            random_value,
+        )?;
+        proving_process.pending_draws = proving_process.pending_draws.error_decrement()?;
+    }
+
+    ProvingProcess::pack(proving_process, *proving_process_account.data.borrow_mut())?;
+    if let Some(wallet_record) = wallet_record {
+        WalletRecord::pack(wallet_record, *wallet_record_account.data.borrow_mut())?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Reveal randomness instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    instruction::RevealRandomnessArgs,
+    state::{PackSet, RandomnessRound},
+    utils::*,
+};
+use metaplex_token_metadata::utils::assert_derivation;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{self, Sysvar},
+};
+
+/// Process RevealRandomness instruction
+pub fn reveal_randomness(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RevealRandomnessArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+    let randomness_round_account = next_account_info(account_info_iter)?;
+    let slot_hashes_account = next_account_info(account_info_iter)?;
+    let clock = Clock::get()?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+    assert_owned_by(randomness_round_account, program_id)?;
+    assert_account_key(slot_hashes_account, &sysvar::slot_hashes::id())?;
+
+    assert_signer(&authority_account)?;
+
+    let mut pack_set = PackSet::unpack(&pack_set_account.data.borrow_mut())?;
+    assert_account_key(authority_account, &pack_set.authority)?;
+    pack_set.assert_able_to_reveal()?;
+
+    let mut randomness_round = RandomnessRound::unpack(&randomness_round_account.data.borrow())?;
+    assert_account_key(pack_set_account, &randomness_round.pack_set)?;
+    assert_derivation(
+        program_id,
+        randomness_round_account,
+        &[
+            RandomnessRound::PREFIX.as_bytes(),
+            pack_set_account.key.as_ref(),
+            &randomness_round.index.to_le_bytes(),
+        ],
+    )?;
+
+    // Revealed round can be revealed again only if nobody sealed it while hash of reveal slot was available
+    if randomness_round.secret.is_some()
+        && (randomness_round.seed.is_some()
+            || clock.slot <= randomness_round.reveal_slot
+            || get_slot_hash(slot_hashes_account, randomness_round.reveal_slot).is_ok())
+    {
+        return Err(NFTPacksError::RandomnessAlreadyRevealed.into());
+    }
+
+    randomness_round.reveal(args.secret, clock.slot)?;
+    pack_set.close_randomness_round(randomness_round.index);
+
+    RandomnessRound::pack(
+        randomness_round,
+        *randomness_round_account.data.borrow_mut(),
+    )?;
+    PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! Set holder gate instruction processing
+
+use crate::{instruction::SetHolderGateArgs, state::PackSet, utils::*};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Process SetHolderGate instruction
+pub fn set_holder_gate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetHolderGateArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+
+    assert_signer(&authority_account)?;
+
+    let mut pack_set = PackSet::unpack(&pack_set_account.data.borrow_mut())?;
+    assert_account_key(authority_account, &pack_set.authority)?;
+
+    pack_set.set_holder_gate(args.holder_gate)?;
+
+    PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! Set rarity tiers instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    instruction::SetRarityTiersArgs,
+    state::{PackConfig, PackSet},
+    utils::*,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Process SetRarityTiers instruction
+pub fn set_rarity_tiers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetRarityTiersArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+    let pack_config_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+
+    assert_signer(&authority_account)?;
+
+    let mut pack_set = PackSet::unpack(&pack_set_account.data.borrow_mut())?;
+    assert_account_key(authority_account, &pack_set.authority)?;
+
+    pack_set.set_rarity_tiers(args.tiers)?;
+
+    // Layout of pack config trees is fixed with the first card and stays after its cards are deleted
+    assert_account_key(pack_config_account, &pack_set.pack_config)?;
+    if PackConfig::is_initialized(&pack_config_account.data.borrow()) {
+        return Err(NFTPacksError::PackConfigInitialized.into());
+    }
+
+    PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
+
+    Ok(())
+}
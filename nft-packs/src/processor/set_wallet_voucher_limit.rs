@@ -0,0 +1,33 @@
+//! Set wallet voucher limit instruction processing
+
+use crate::{instruction::SetWalletVoucherLimitArgs, state::PackSet, utils::*};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+/// Process SetWalletVoucherLimit instruction
+pub fn set_wallet_voucher_limit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetWalletVoucherLimitArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+
+    assert_signer(&authority_account)?;
+
+    let mut pack_set = PackSet::unpack(&pack_set_account.data.borrow_mut())?;
+    assert_account_key(authority_account, &pack_set.authority)?;
+
+    pack_set.set_max_vouchers_per_wallet(args.max_vouchers_per_wallet)?;
+
+    PackSet::pack(pack_set, *pack_set_account.data.borrow_mut())?;
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Withdraw treasury instruction processing
+
+use crate::{
+    error::NFTPacksError,
+    find_treasury_program_address, find_treasury_token_program_address,
+    math::SafeMath,
+    state::{PackSet, TreasuryRecipient, MAX_TREASURY_SHARE, TREASURY_PREFIX},
+    utils::*,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use spl_token::state::Account;
+
+/// Process WithdrawTreasury instruction
+pub fn withdraw_treasury(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let pack_set_account = next_account_info(account_info_iter)?;
+    let authority_account = next_account_info(account_info_iter)?;
+    let treasury_account = next_account_info(account_info_iter)?;
+    let system_program_account = next_account_info(account_info_iter)?;
+
+    assert_owned_by(pack_set_account, program_id)?;
+
+    assert_signer(&authority_account)?;
+
+    let pack_set = PackSet::unpack(&pack_set_account.data.borrow())?;
+    assert_account_key(authority_account, &pack_set.authority)?;
+
+    let (treasury_key, treasury_bump_seed) =
+        find_treasury_program_address(program_id, pack_set_account.key);
+    assert_account_key(treasury_account, &treasury_key)?;
+
+    let treasury_signer_seeds = &[
+        TREASURY_PREFIX.as_bytes(),
+        &pack_set_account.key.to_bytes()[..32],
+        &[treasury_bump_seed],
+    ];
+
+    match pack_set.payment_mint {
+        Some(payment_mint) => {
+            let treasury_token_account = next_account_info(account_info_iter)?;
+            let authority_token_account = next_account_info(account_info_iter)?;
+            let _token_program_account = next_account_info(account_info_iter)?;
+
+            let (treasury_token_key, _) = find_treasury_token_program_address(
+                program_id,
+                pack_set_account.key,
+                &payment_mint,
+            );
+            assert_account_key(treasury_token_account, &treasury_token_key)?;
+
+            let mut remaining = Account::unpack(&treasury_token_account.data.borrow())?.amount;
+            let total = remaining;
+
+            for recipient in pack_set.treasury_recipients.iter() {
+                let recipient_token_account = next_account_info(account_info_iter)?;
+                let recipient_token = Account::unpack(&recipient_token_account.data.borrow())?;
+                if recipient_token.owner != recipient.address
+                    || recipient_token.mint != payment_mint
+                {
+                    return Err(NFTPacksError::TreasuryRecipientMismatch.into());
+                }
+
+                let amount = get_recipient_amount(total, recipient)?;
+                if amount == 0 {
+                    continue;
+                }
+
+                spl_token_transfer(
+                    treasury_token_account.clone(),
+                    recipient_token_account.clone(),
+                    treasury_account.clone(),
+                    amount,
+                    &[treasury_signer_seeds],
+                )?;
+                remaining = remaining.error_sub(amount)?;
+            }
+
+            if remaining > 0 {
+                spl_token_transfer(
+                    treasury_token_account.clone(),
+                    authority_token_account.clone(),
+                    treasury_account.clone(),
+                    remaining,
+                    &[treasury_signer_seeds],
+                )?;
+            }
+        }
+        None => {
+            let mut remaining = treasury_account.lamports();
+            let total = remaining;
+
+            for recipient in pack_set.treasury_recipients.iter() {
+                let recipient_account = next_account_info(account_info_iter)?;
+                if *recipient_account.key != recipient.address {
+                    return Err(NFTPacksError::TreasuryRecipientMismatch.into());
+                }
+
+                let amount = get_recipient_amount(total, recipient)?;
+                if amount == 0 {
+                    continue;
+                }
+
+                transfer_lamports(
+                    treasury_account,
+                    recipient_account,
+                    system_program_account,
+                    amount,
+                    treasury_signer_seeds,
+                )?;
+                remaining = remaining.error_sub(amount)?;
+            }
+
+            if remaining > 0 {
+                transfer_lamports(
+                    treasury_account,
+                    authority_account,
+                    system_program_account,
+                    remaining,
+                    treasury_signer_seeds,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Calculate recipient's part of treasury
+fn get_recipient_amount(total: u64, recipient: &TreasuryRecipient) -> Result<u64, ProgramError> {
+    let amount = (total as u128)
+        .error_mul(recipient.share as u128)?
+        .error_div(MAX_TREASURY_SHARE as u128)?;
+
+    Ok(amount as u64)
+}
+
+/// Transfer lamports from treasury account
+fn transfer_lamports<'a>(
+    treasury: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &system_instruction::transfer(treasury.key, destination.key, amount),
+        &[
+            treasury.clone(),
+            destination.clone(),
+            system_program.clone(),
+        ],
+        &[signer_seeds],
+    )
+}
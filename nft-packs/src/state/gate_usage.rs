@@ -0,0 +1,105 @@
+//! Gate usage definitions
+
+use super::*;
+use crate::{error::NFTPacksError, math::SafeMath, PROGRAM_VERSION};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Uses of NFT which passed pack holder gate, see `HolderGate`.
+/// PDA (["gate_usage", pack_key, nft_mint_key], program_id)
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct GateUsage {
+    /// Account type - GateUsage
+    pub account_type: AccountType,
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// Gating NFT mint
+    pub mint: Pubkey,
+    /// Vouchers opened with NFT
+    pub uses: u32,
+    /// Layout version
+    pub version: u8,
+}
+
+impl GateUsage {
+    /// Prefix used to generate account
+    pub const PREFIX: &'static str = "gate_usage";
+
+    /// Initialize a GateUsage
+    pub fn init(&mut self, params: InitGateUsageParams) {
+        self.account_type = AccountType::GateUsage;
+        self.pack_set = params.pack_set;
+        self.mint = params.mint;
+        self.uses = 0;
+        self.version = PROGRAM_VERSION;
+    }
+
+    /// Count voucher opened with NFT, it can't be used more than `max_uses` times
+    pub fn add_use(&mut self, max_uses: u32) -> Result<(), ProgramError> {
+        if self.uses >= max_uses {
+            return Err(NFTPacksError::GateUsesExhausted.into());
+        }
+
+        self.uses = self.uses.error_increment()?;
+
+        Ok(())
+    }
+}
+
+/// Initialize a GateUsage params
+pub struct InitGateUsageParams {
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// Gating NFT mint
+    pub mint: Pubkey,
+}
+
+impl Sealed for GateUsage {}
+
+impl Pack for GateUsage {
+    // 1 + 32 + 32 + 4 + version 1 + reserved 32
+    const LEN: usize = 102;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_versioned(self, dst).unwrap()
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_versioned(src, Self::LEN)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for GateUsage {
+    // account was added with versioning
+    const LEGACY_LEN: usize = Self::LEN;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl IsInitialized for GateUsage {
+    fn is_initialized(&self) -> bool {
+        self.account_type != AccountType::Uninitialized
+            && self.account_type == AccountType::GateUsage
+    }
+}
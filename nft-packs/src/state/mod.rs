@@ -1,27 +1,62 @@
 //! State types
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{msg, program_error::ProgramError, pubkey::Pubkey};
+use std::convert::TryInto;
 
+mod gate_usage;
 mod pack_card;
 mod pack_config;
 mod pack_set;
+mod pack_set_view;
 mod pack_voucher;
 mod proving_process;
+mod randomness_round;
+mod voucher_lookup;
+mod wallet_record;
 
+pub use gate_usage::*;
 pub use pack_card::*;
 pub use pack_config::*;
 pub use pack_set::*;
+pub use pack_set_view::*;
 pub use pack_voucher::*;
 pub use proving_process::*;
+pub use randomness_round::*;
+pub use voucher_lookup::*;
+pub use wallet_record::*;
 
 /// Global prefix for program addresses
 pub const PREFIX: &str = "packs";
 
+/// Prefix for pack treasury addresses
+pub const TREASURY_PREFIX: &str = "treasury";
+
 /// Max count of slots for lag
 pub const MAX_LAG_SLOTS: u64 = 5;
 
-/// Max pack cards amount
-pub const MAX_PACK_CARDS_AMOUNT: u32 = 100;
+/// Max pack cards amount, pack can hold less if its config account isn't big enough
+pub const MAX_PACK_CARDS_AMOUNT: u32 = 10_000;
+
+/// Max allowed amount to redeem, proving process holds a record for each drawn card
+pub const MAX_CARDS_TO_REDEEM: u32 = 100;
+
+/// Max treasury recipients amount
+pub const MAX_TREASURY_RECIPIENTS: usize = 5;
+
+/// Treasury recipient shares are set in basis points
+pub const MAX_TREASURY_SHARE: u16 = 10000;
+
+/// Max rarity tiers amount
+pub const MAX_RARITY_TIERS: usize = 10;
+
+/// Rarity tier probabilities are set in basis points
+pub const MAX_TIER_PROBABILITY: u16 = 10000;
+
+/// Max pack slots amount
+pub const MAX_PACK_SLOTS: usize = 10;
+
+/// Max pack phases amount
+pub const MAX_PACK_PHASES: usize = 5;
 
 /// Max len of pack URI
 pub const MAX_URI_LENGTH: usize = 200;
@@ -44,6 +79,16 @@ pub enum AccountType {
     ProvingProcess,
     /// Pack config
     PackConfig,
+    /// Wallet record
+    WalletRecord,
+    /// Pack set in fixed layout, see `PackSetView`
+    PackSetV2,
+    /// Voucher lookup
+    VoucherLookup,
+    /// Gate usage
+    GateUsage,
+    /// Randomness round
+    RandomnessRound,
 }
 
 impl Default for AccountType {
@@ -69,3 +114,102 @@ pub trait MasterEditionHolder {
     /// Increment total supply
     fn decrement_supply(&mut self) -> Result<(), ProgramError>;
 }
+
+/// Account with version of its layout. Version byte follows the fields and is followed by
+/// reserved space, so new fields can be added without changing size of accounts
+pub trait Versioned {
+    /// Account size before versioning, such accounts have no reserved space and are resized
+    /// to `Pack::LEN` by `MigrateAccount`
+    const LEGACY_LEN: usize;
+
+    /// Returns layout version, 0 if account was created before versioning
+    fn get_version(&self) -> u8;
+
+    /// Set layout version
+    fn set_version(&mut self, version: u8);
+}
+
+/// Check that account data has current size or size of account created before versioning
+pub(crate) fn assert_versioned_len(
+    len: usize,
+    current_len: usize,
+    legacy_len: usize,
+) -> Result<(), ProgramError> {
+    if len != current_len && len != legacy_len {
+        msg!("Wrong account size");
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Deserialize versioned account. Account created before versioning doesn't have version byte
+/// and fields added after it if its fields take all the space, they are read as zeros then
+pub(crate) fn unpack_versioned<T: BorshDeserialize>(
+    src: &[u8],
+    len: usize,
+) -> Result<T, ProgramError> {
+    let result = T::deserialize(&mut &src[..]).or_else(|_| {
+        let mut data = src.to_vec();
+        data.resize(len.max(src.len() + 1), 0);
+        T::deserialize(&mut data.as_slice())
+    });
+
+    result.map_err(|_| {
+        msg!("Failed to deserialize");
+        ProgramError::InvalidAccountData
+    })
+}
+
+/// Serialize versioned account. Account created before versioning may have no space for
+/// version byte and fields added after it, they're skipped only while they are zero,
+/// so account can't use new fields until it's resized by `MigrateAccount`
+pub(crate) fn pack_versioned<T: BorshSerialize>(
+    value: &T,
+    dst: &mut [u8],
+) -> Result<(), ProgramError> {
+    let data = value.try_to_vec()?;
+
+    if data.len() > dst.len() && data[dst.len()..].iter().any(|byte| *byte != 0) {
+        msg!("Account has no space for new fields, it should be migrated");
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    let len = data.len().min(dst.len());
+    dst[..len].copy_from_slice(&data[..len]);
+    for byte in dst[len..].iter_mut() {
+        *byte = 0;
+    }
+
+    Ok(())
+}
+
+/// Read little endian u16 from account data in place
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+/// Write little endian u16 to account data in place
+pub(crate) fn write_u16(data: &mut [u8], offset: usize, value: u16) {
+    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Read little endian u32 from account data in place
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+/// Write little endian u32 to account data in place
+pub(crate) fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Read little endian u64 from account data in place
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// Write little endian u64 to account data in place
+pub(crate) fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+    data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+}
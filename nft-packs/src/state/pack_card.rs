@@ -1,10 +1,9 @@
 //! Pack card definitions
 
 use super::*;
-use crate::math::SafeMath;
+use crate::{math::SafeMath, PROGRAM_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
@@ -28,6 +27,10 @@ pub struct PackCard {
     pub max_supply: u32,
     /// Fixed probability, should be filled if PackSet distribution_type is "fixed"
     pub weight: u16,
+    /// Rarity tier index, set if pack set has rarity tiers
+    pub tier: Option<u8>,
+    /// Layout version
+    pub version: u8,
 }
 
 impl PackCard {
@@ -43,6 +46,8 @@ impl PackCard {
         self.token_account = params.token_account;
         self.max_supply = params.max_supply;
         self.weight = params.weight;
+        self.tier = params.tier;
+        self.version = PROGRAM_VERSION;
     }
 
     /// Decrement supply value
@@ -66,24 +71,45 @@ pub struct InitPackCardParams {
     pub max_supply: u32,
     /// Fixed probability, should be filled if PackSet distribution_type is "fixed"
     pub weight: u16,
+    /// Rarity tier index
+    pub tier: Option<u8>,
 }
 
 impl Sealed for PackCard {}
 
 impl Pack for PackCard {
-    const LEN: usize = 145;
+    // 1 + 32 + 32 + 32 + 32 + 4 + 2 + (1 + 1) + version 1 + reserved 32
+    const LEN: usize = 170;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let mut slice = dst;
-        self.serialize(&mut slice).unwrap()
+        pack_versioned(self, dst).unwrap()
     }
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let mut src_mut = src;
-        Self::deserialize(&mut src_mut).map_err(|_| {
-            msg!("Failed to deserialize");
-            ProgramError::InvalidAccountData
-        })
+        unpack_versioned(src, Self::LEN)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for PackCard {
+    // 1 + 32 + 32 + 32 + 32 + 4 + 2 + unused 10
+    const LEGACY_LEN: usize = 145;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
     }
 }
 
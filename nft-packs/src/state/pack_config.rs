@@ -1,5 +1,10 @@
 //! Pack config definitions
-use crate::{error::NFTPacksError, math::SafeMath};
+use crate::{
+    error::NFTPacksError,
+    math::SafeMath,
+    utils::{next_random_value, random_in_range},
+    PROGRAM_VERSION,
+};
 
 use super::*;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -10,10 +15,672 @@ use solana_program::{
     program_pack::{IsInitialized, Pack, Sealed},
 };
 
-/// Pack config. PDA (["config", pack_key], program_id)
+/// Pack config. Account is created by pack authority with `PackConfig::get_len` bytes
+/// for the amount of cards it's going to hold and its key is saved to pack set on `InitPack`.
+///
+/// Data isn't serialized, it's read and written in place:
+/// - header, `PackConfig::HEADER_LEN` bytes, its last bytes after version are reserved
+/// - card entries, `PackConfig::CARD_ENTRY_LEN` bytes each: u32 value, u32 max_supply, u8 tier
+/// - Fenwick trees of card values, `u64` node for every card: one tree for every rarity tier
+/// and one more tree for rare cards of every tier if pack has pity rule
+///
+/// Card value is its weight, or supply left for `PackDistributionType::MaxSupply`,
+/// 0 means card can't be drawn anymore. Trees keep prefix sums of values so draws
+/// and supply changes take O(log n).
+pub struct PackConfig<'a> {
+    data: &'a mut [u8],
+}
+
+/// Restrictions of cards which can be drawn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CardFilter {
+    /// Indexes of cards which can't be drawn, e.g. cards already drawn with unique cards option
+    pub excluded_cards: Vec<u32>,
+    /// Bit mask of rarity tiers cards can be drawn from, all tiers if not set
+    pub tiers: Option<u16>,
+    /// If true only cards with value under rare weight threshold can be drawn
+    pub rare_only: bool,
+}
+
+impl CardFilter {
+    /// Check if filter allows cards of rarity tier
+    pub fn has_tier(&self, tier: u8) -> bool {
+        self.tiers.map_or(true, |tiers| tiers & (1 << tier) != 0)
+    }
+}
+
+const ACCOUNT_TYPE_OFFSET: usize = 0;
+const PACK_SET_OFFSET: usize = 1;
+const CAPACITY_OFFSET: usize = 33;
+const CARDS_OFFSET: usize = 37;
+const CARDS_LEFT_OFFSET: usize = 41;
+const TIERS_OFFSET: usize = 45;
+const HAS_RARE_TREES_OFFSET: usize = 46;
+const RARE_WEIGHT_THRESHOLD_OFFSET: usize = 47;
+const VERSION_OFFSET: usize = 51;
+
+const TREE_NODE_LEN: usize = 8;
+
+impl<'a> PackConfig<'a> {
+    /// Size of header
+    pub const HEADER_LEN: usize = 56;
+
+    /// Size of card entry
+    pub const CARD_ENTRY_LEN: usize = 9;
+
+    /// Account size required to hold `capacity` cards
+    pub fn get_len(capacity: u32, tiers_amount: usize, has_pity_rule: bool) -> usize {
+        Self::HEADER_LEN
+            + capacity as usize
+                * (Self::CARD_ENTRY_LEN + trees_amount(tiers_amount, has_pity_rule) * TREE_NODE_LEN)
+    }
+
+    /// Initialize pack config in account data. Layout of trees is fixed here so
+    /// rarity tiers and pity rule can't be changed once pack has cards
+    pub fn init(
+        data: &'a mut [u8],
+        pack_set: &Pubkey,
+        tiers_amount: usize,
+        rare_weight_threshold: Option<u32>,
+    ) -> Result<Self, ProgramError> {
+        if data.len() < Self::HEADER_LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        if data[ACCOUNT_TYPE_OFFSET] != AccountType::Uninitialized as u8 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let trees = trees_amount(tiers_amount, rare_weight_threshold.is_some());
+        let capacity = ((data.len() - Self::HEADER_LEN)
+            / (Self::CARD_ENTRY_LEN + trees * TREE_NODE_LEN))
+            .min(MAX_PACK_CARDS_AMOUNT as usize);
+
+        if capacity == 0 {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        // entries and trees are zeroed by system program on account creation
+        data[ACCOUNT_TYPE_OFFSET] = AccountType::PackConfig as u8;
+        data[PACK_SET_OFFSET..CAPACITY_OFFSET].copy_from_slice(pack_set.as_ref());
+        write_u32(data, CAPACITY_OFFSET, capacity as u32);
+        data[TIERS_OFFSET] = tiers_amount.max(1) as u8;
+        if let Some(threshold) = rare_weight_threshold {
+            data[HAS_RARE_TREES_OFFSET] = 1;
+            write_u32(data, RARE_WEIGHT_THRESHOLD_OFFSET, threshold);
+        }
+        data[VERSION_OFFSET] = PROGRAM_VERSION;
+
+        Ok(Self { data })
+    }
+
+    /// Load initialized pack config of pack set
+    pub fn load(data: &'a mut [u8], pack_set: &Pubkey) -> Result<Self, ProgramError> {
+        let pack_config = Self::load_unchecked(data)?;
+
+        if pack_config.pack_set() != *pack_set {
+            return Err(NFTPacksError::WrongPackConfig.into());
+        }
+
+        Ok(pack_config)
+    }
+
+    /// Load initialized pack config without checking its pack set
+    pub fn load_unchecked(data: &'a mut [u8]) -> Result<Self, ProgramError> {
+        if !Self::is_initialized(data) {
+            msg!("Pack config isn't initialized");
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        let pack_config = Self { data };
+
+        let len = Self::get_len(
+            pack_config.capacity(),
+            pack_config.tiers() as usize,
+            pack_config.rare_weight_threshold().is_some(),
+        );
+        if pack_config.data.len() < len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(pack_config)
+    }
+
+    /// Check if account data holds initialized pack config
+    pub fn is_initialized(data: &[u8]) -> bool {
+        data.len() >= Self::HEADER_LEN && data[ACCOUNT_TYPE_OFFSET] == AccountType::PackConfig as u8
+    }
+
+    /// Pack set key
+    pub fn pack_set(&self) -> Pubkey {
+        Pubkey::new(&self.data[PACK_SET_OFFSET..CAPACITY_OFFSET])
+    }
+
+    /// Max amount of cards
+    pub fn capacity(&self) -> u32 {
+        read_u32(self.data, CAPACITY_OFFSET)
+    }
+
+    /// Amount of card entries
+    pub fn cards(&self) -> u32 {
+        read_u32(self.data, CARDS_OFFSET)
+    }
+
+    /// Amount of cards which can be drawn
+    pub fn cards_left(&self) -> u32 {
+        read_u32(self.data, CARDS_LEFT_OFFSET)
+    }
+
+    /// Amount of rarity tiers, cards of pack without tiers are in one tier
+    pub fn tiers(&self) -> u8 {
+        self.data[TIERS_OFFSET]
+    }
+
+    /// Layout version, 0 if config was created before versioning
+    pub fn version(&self) -> u8 {
+        self.data[VERSION_OFFSET]
+    }
+
+    /// Set layout version
+    pub fn set_version(&mut self, version: u8) {
+        self.data[VERSION_OFFSET] = version;
+    }
+
+    /// Max value of rare card, set if pack has pity rule
+    pub fn rare_weight_threshold(&self) -> Option<u32> {
+        if self.data[HAS_RARE_TREES_OFFSET] == 0 {
+            None
+        } else {
+            Some(read_u32(self.data, RARE_WEIGHT_THRESHOLD_OFFSET))
+        }
+    }
+
+    /// Add card entry, cards should be added with consecutive indexes starting from 1
+    pub fn add_card(
+        &mut self,
+        index: u32,
+        value: u32,
+        max_supply: u32,
+        tier: Option<u8>,
+    ) -> Result<(), ProgramError> {
+        if index > self.capacity() {
+            return Err(NFTPacksError::PackIsFullWithCards.into());
+        }
+
+        if index != self.cards().error_increment()? {
+            return Err(NFTPacksError::InvalidWeightPosition.into());
+        }
+
+        let tier = tier.unwrap_or(0);
+        if tier >= self.tiers() {
+            return Err(NFTPacksError::WrongRarityTier.into());
+        }
+
+        write_u32(self.data, CARDS_OFFSET, index);
+
+        let offset = self.card_offset(index);
+        write_u32(self.data, offset + 4, max_supply);
+        self.data[offset + 8] = tier;
+
+        self.set_value(index, value)
+    }
+
+    /// Returns card entry as (u32 card_index, u32 value, u32 max_supply)
+    pub fn get_card(&self, index: u32) -> Result<(u32, u32, u32), ProgramError> {
+        if index == 0 || index > self.cards() {
+            return Err(NFTPacksError::InvalidWeightPosition.into());
+        }
+
+        let offset = self.card_offset(index);
+
+        Ok((
+            index,
+            read_u32(self.data, offset),
+            read_u32(self.data, offset + 4),
+        ))
+    }
+
+    /// Returns card rarity tier
+    pub fn get_card_tier(&self, index: u32) -> Result<u8, ProgramError> {
+        self.get_card(index)?;
+
+        Ok(self.data[self.card_offset(index) + 8])
+    }
+
+    /// Check if card value is under rare weight threshold
+    pub fn is_rare(&self, value: u32) -> bool {
+        self.rare_weight_threshold()
+            .map_or(false, |threshold| value != 0 && value <= threshold)
+    }
+
+    /// Change card value and update trees, 0 removes card from draws
+    pub fn set_value(&mut self, index: u32, value: u32) -> Result<(), ProgramError> {
+        let (_, old_value, _) = self.get_card(index)?;
+        let tier = self.get_card_tier(index)?;
+
+        write_u32(self.data, self.card_offset(index), value);
+
+        if old_value == 0 && value != 0 {
+            let cards_left = self.cards_left().error_increment()?;
+            write_u32(self.data, CARDS_LEFT_OFFSET, cards_left);
+        } else if old_value != 0 && value == 0 {
+            let cards_left = self.cards_left().error_decrement()?;
+            write_u32(self.data, CARDS_LEFT_OFFSET, cards_left);
+        }
+
+        self.update_tree(self.tree(tier, false), index, old_value, value);
+
+        if self.rare_weight_threshold().is_some() {
+            let old_rare_value = if self.is_rare(old_value) {
+                old_value
+            } else {
+                0
+            };
+            let rare_value = if self.is_rare(value) { value } else { 0 };
+            self.update_tree(self.tree(tier, true), index, old_rare_value, rare_value);
+        }
+
+        Ok(())
+    }
+
+    /// Change card max supply
+    pub fn set_max_supply(&mut self, index: u32, max_supply: u32) -> Result<(), ProgramError> {
+        self.get_card(index)?;
+        write_u32(self.data, self.card_offset(index) + 4, max_supply);
+
+        Ok(())
+    }
+
+    /// Remove card entry, the last card is moved to its index so indexes stay consecutive
+    pub fn remove_card(&mut self, index: u32) -> Result<(), ProgramError> {
+        let last_index = self.cards();
+        self.get_card(index)?;
+        let (_, last_value, last_max_supply) = self.get_card(last_index)?;
+        let last_tier = self.get_card_tier(last_index)?;
+
+        // values are cleared first so trees are updated in tiers cards were in
+        self.set_value(index, 0)?;
+        self.set_value(last_index, 0)?;
+
+        if index != last_index {
+            self.data[self.card_offset(index) + 8] = last_tier;
+            self.set_max_supply(index, last_max_supply)?;
+            self.set_value(index, last_value)?;
+        }
+
+        let last_offset = self.card_offset(last_index);
+        self.data[last_offset..last_offset + Self::CARD_ENTRY_LEN].fill(0);
+        write_u32(self.data, CARDS_OFFSET, last_index - 1);
+
+        Ok(())
+    }
+
+    /// Decrement supply of drawn card, card is removed from draws once it runs out of supply
+    pub fn decrement_supply(
+        &mut self,
+        index: u32,
+        distribution_type: &PackDistributionType,
+    ) -> Result<(), ProgramError> {
+        let (_, value, max_supply) = self.get_card(index)?;
+
+        match distribution_type {
+            PackDistributionType::MaxSupply => {
+                self.set_value(index, value.error_decrement()?)?;
+            }
+            PackDistributionType::Fixed => {
+                let max_supply = max_supply.error_decrement()?;
+                self.set_max_supply(index, max_supply)?;
+                if max_supply == 0 {
+                    self.set_value(index, 0)?;
+                }
+            }
+            PackDistributionType::Unlimited => {
+                // do nothing because we shouldn't change any values here
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Amount of cards which can be drawn and aren't excluded
+    pub fn count_cards_left(&self, excluded_cards: &[u32]) -> Result<u32, ProgramError> {
+        let mut cards_left = self.cards_left();
+
+        for index in excluded_cards {
+            if self.get_card(*index)?.1 != 0 {
+                cards_left = cards_left.error_decrement()?;
+            }
+        }
+
+        Ok(cards_left)
+    }
+
+    /// Check if any card matches filter
+    pub fn has_cards(&self, filter: &CardFilter) -> Result<bool, ProgramError> {
+        for tier in 0..self.tiers() {
+            if filter.has_tier(tier) && self.get_tier_weight(tier, filter)? != 0 {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Select a random choice with weights, legacy version used by `RandomnessVersion::V0`.
+    ///
+    /// Cards are walked in the same order as sorted weights of `LegacyPackConfig`,
+    /// so the same random value selects the same card as before trees were introduced
+    pub fn select_weighted_random(
+        &self,
+        rand: u16,
+        weight_sum: u64,
+    ) -> Result<(u32, u32, u32), ProgramError> {
+        let mut weights = Vec::new();
+        for index in 1..=self.cards() {
+            let card = self.get_card(index)?;
+            if card.1 != 0 {
+                weights.push(card);
+            }
+        }
+        sort_weights(&mut weights);
+
+        select_sorted_weighted_random(&weights, rand, weight_sum)
+    }
+
+    /// Select a random choice with weights using only integer arithmetic.
+    ///
+    /// Random value is mapped uniformly onto `[0, sum of weights)` and card is selected
+    /// by cumulative weights, so each card is selected with probability `weight / sum of weights`.
+    pub fn select_weighted_random_exact(&self, rand: u64) -> Result<(u32, u32, u32), ProgramError> {
+        self.select_weighted_random_filtered(rand, &CardFilter::default())
+    }
+
+    /// Same as `select_weighted_random_exact` but only cards matching `filter` can be selected
+    pub fn select_weighted_random_filtered(
+        &self,
+        rand: u64,
+        filter: &CardFilter,
+    ) -> Result<(u32, u32, u32), ProgramError> {
+        let mut tier_weights = Vec::new();
+        for tier in 0..self.tiers() {
+            if filter.has_tier(tier) {
+                tier_weights.push((tier, self.get_tier_weight(tier, filter)?));
+            }
+        }
+
+        let weight_sum = tier_weights
+            .iter()
+            .try_fold(0u64, |sum, i| sum.error_add(i.1))?;
+
+        if weight_sum == 0 {
+            return Err(NFTPacksError::MissingEditionsInPack.into());
+        }
+
+        let mut bound = random_in_range(rand, weight_sum)?;
+        for (tier, weight) in tier_weights {
+            if bound < weight {
+                return self.get_card(self.find_card(tier, bound, filter)?);
+            }
+            bound -= weight;
+        }
+
+        Err(NFTPacksError::InvalidWeightPosition.into())
+    }
+
+    /// Select rarity tier with its probability and then a card within this tier with card weights.
+    ///
+    /// Tiers which don't have cards left are skipped and their probability is split between
+    /// the rest of tiers proportionally, so the result depends only on random value and pack config.
+    pub fn select_tiered_random(
+        &self,
+        rand: u64,
+        tiers: &[RarityTier],
+    ) -> Result<(u32, u32, u32), ProgramError> {
+        self.select_tiered_random_filtered(rand, tiers, &CardFilter::default())
+    }
+
+    /// Same as `select_tiered_random` but only cards matching `filter` can be selected,
+    /// tier is treated as empty if none of its cards match
+    pub fn select_tiered_random_filtered(
+        &self,
+        rand: u64,
+        tiers: &[RarityTier],
+        filter: &CardFilter,
+    ) -> Result<(u32, u32, u32), ProgramError> {
+        let mut available_tiers = Vec::new();
+        for (index, tier) in tiers.iter().enumerate().take(self.tiers() as usize) {
+            let index = index as u8;
+            if !filter.has_tier(index) {
+                continue;
+            }
+
+            let weight = self.get_tier_weight(index, filter)?;
+            if weight != 0 {
+                available_tiers.push((index, tier.probability as u64, weight));
+            }
+        }
+
+        let probability_sum = available_tiers
+            .iter()
+            .try_fold(0u64, |sum, i| sum.error_add(i.1))?;
+
+        if probability_sum == 0 {
+            return Err(NFTPacksError::MissingEditionsInPack.into());
+        }
+
+        let mut bound = random_in_range(rand, probability_sum)?;
+        for (index, probability, weight) in available_tiers {
+            if bound < probability {
+                // card is selected with next value so it doesn't correlate with tier selection
+                let bound = random_in_range(next_random_value(rand), weight)?;
+                return self.get_card(self.find_card(index, bound, filter)?);
+            }
+            bound -= probability;
+        }
+
+        Err(NFTPacksError::InvalidWeightPosition.into())
+    }
+
+    /// Sum of values of tier cards matching filter
+    fn get_tier_weight(&self, tier: u8, filter: &CardFilter) -> Result<u64, ProgramError> {
+        let tree = self.tree_for_filter(tier, filter)?;
+        let mut weight = self.prefix_sum(tree, self.capacity());
+
+        for (_, value) in self.excluded_values(tree, tier, filter)? {
+            weight = weight.error_sub(value as u64)?;
+        }
+
+        Ok(weight)
+    }
+
+    /// Find card of tier matching filter by cumulative weight,
+    /// `bound` should be less than `get_tier_weight`
+    fn find_card(&self, tier: u8, bound: u64, filter: &CardFilter) -> Result<u32, ProgramError> {
+        let tree = self.tree_for_filter(tier, filter)?;
+        let excluded = self.excluded_values(tree, tier, filter)?;
+
+        // Search is repeated with bound shifted by values of excluded cards before found position,
+        // it moves forward only when a new excluded card is passed
+        let mut shifted_bound = bound;
+        loop {
+            let index = self.search(tree, shifted_bound)?;
+            let excluded_sum = excluded
+                .iter()
+                .filter(|(excluded_index, _)| *excluded_index <= index)
+                .try_fold(0u64, |sum, i| sum.error_add(i.1 as u64))?;
+
+            let next_bound = bound.error_add(excluded_sum)?;
+            if next_bound == shifted_bound {
+                return Ok(index);
+            }
+            shifted_bound = next_bound;
+        }
+    }
+
+    /// Excluded cards which have value in tree
+    fn excluded_values(
+        &self,
+        tree: usize,
+        tier: u8,
+        filter: &CardFilter,
+    ) -> Result<Vec<(u32, u32)>, ProgramError> {
+        let mut excluded = Vec::new();
+
+        for index in filter.excluded_cards.iter().copied() {
+            let (_, value, _) = self.get_card(index)?;
+            if value == 0 || self.get_card_tier(index)? != tier {
+                continue;
+            }
+
+            if self.tree(tier, true) == tree && !self.is_rare(value) {
+                continue;
+            }
+
+            excluded.push((index, value));
+        }
+
+        Ok(excluded)
+    }
+
+    fn tree_for_filter(&self, tier: u8, filter: &CardFilter) -> Result<usize, ProgramError> {
+        if tier >= self.tiers() {
+            return Err(NFTPacksError::WrongRarityTier.into());
+        }
+
+        if filter.rare_only && self.rare_weight_threshold().is_none() {
+            return Err(NFTPacksError::WrongPityRule.into());
+        }
+
+        Ok(self.tree(tier, filter.rare_only))
+    }
+
+    fn tree(&self, tier: u8, rare: bool) -> usize {
+        if rare {
+            self.tiers() as usize + tier as usize
+        } else {
+            tier as usize
+        }
+    }
+
+    fn card_offset(&self, index: u32) -> usize {
+        Self::HEADER_LEN + (index as usize - 1) * Self::CARD_ENTRY_LEN
+    }
+
+    fn node_offset(&self, tree: usize, index: u32) -> usize {
+        let capacity = self.capacity() as usize;
+
+        Self::HEADER_LEN
+            + capacity * Self::CARD_ENTRY_LEN
+            + (tree * capacity + index as usize - 1) * TREE_NODE_LEN
+    }
+
+    fn read_node(&self, tree: usize, index: u32) -> u64 {
+        read_u64(self.data, self.node_offset(tree, index))
+    }
+
+    /// Replace card value in tree, sums can't overflow because values are u32
+    fn update_tree(&mut self, tree: usize, index: u32, old_value: u32, value: u32) {
+        if old_value == value {
+            return;
+        }
+
+        let capacity = self.capacity();
+        let mut i = index;
+        while i <= capacity {
+            let offset = self.node_offset(tree, i);
+            let node = read_u64(self.data, offset) - old_value as u64 + value as u64;
+            write_u64(self.data, offset, node);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum of values of cards from 1 to index
+    fn prefix_sum(&self, tree: usize, index: u32) -> u64 {
+        let mut sum = 0;
+        let mut i = index;
+        while i > 0 {
+            sum += self.read_node(tree, i);
+            i -= i & i.wrapping_neg();
+        }
+
+        sum
+    }
+
+    /// Smallest card index with prefix sum greater than bound
+    fn search(&self, tree: usize, bound: u64) -> Result<u32, ProgramError> {
+        let capacity = self.capacity();
+        let mut position = 0u32;
+        let mut remaining = bound;
+        let mut step = 1u32 << (31 - capacity.leading_zeros());
+
+        while step > 0 {
+            let next = position + step;
+            if next <= capacity {
+                let node = self.read_node(tree, next);
+                if node <= remaining {
+                    position = next;
+                    remaining -= node;
+                }
+            }
+            step >>= 1;
+        }
+
+        if position >= self.cards() {
+            return Err(NFTPacksError::InvalidWeightPosition.into());
+        }
+
+        Ok(position + 1)
+    }
+}
+
+fn trees_amount(tiers_amount: usize, has_pity_rule: bool) -> usize {
+    let trees = tiers_amount.max(1);
+    if has_pity_rule {
+        trees * 2
+    } else {
+        trees
+    }
+}
+
+/// Sort weights in descending order, cards with equal weights keep their order
+fn sort_weights(weights: &mut [(u32, u32, u32)]) {
+    weights.sort_by(|a, b| b.1.cmp(&a.1));
+}
+
+/// Select a random choice from weights sorted in descending order.
+/// `weight_sum` is pack set total passed by caller, it isn't recalculated from weights
+/// so the same random value selects the same card as before
+fn select_sorted_weighted_random(
+    weights: &[(u32, u32, u32)],
+    rand: u16,
+    weight_sum: u64,
+) -> Result<(u32, u32, u32), ProgramError> {
+    let selected = *weights.last().ok_or(NFTPacksError::MissingEditionsInPack)?;
+
+    let mut bound = if weight_sum == 0 {
+        rand as u64 / weights.len() as u64
+    } else {
+        let rndp = rand as f64 / u16::MAX as f64;
+        (rndp * weight_sum as f64).round().to_u64().unwrap()
+    };
+    for i in weights.iter() {
+        bound = bound.saturating_sub(i.1 as u64);
+        if bound == 0 {
+            return Ok(*i);
+        }
+    }
+
+    Ok(selected)
+}
+
+/// Pack config of pack sets created before pack config key was stored in pack set.
+/// PDA (["config", pack_key], program_id)
+///
+/// Weights are kept sorted, draw saves supply change to `action_to_do` and it's applied
+/// with `CleanUp` instruction before the next draw
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
-pub struct PackConfig {
+pub struct LegacyPackConfig {
     /// account type - PackConfig
     pub account_type: AccountType,
     /// weights; Vec<u32 card_index, u32 either max_supply or weight, u32 max_supply for weighted cards>
@@ -35,19 +702,12 @@ pub enum CleanUpActions {
     None,
 }
 
-impl PackConfig {
+impl LegacyPackConfig {
     /// Prefix used to generate account
     pub const PREFIX: &'static str = "config";
 
-    /// Initialize a PackConfig
-    pub fn init(&mut self) {
-        self.account_type = AccountType::PackConfig;
-        self.weights = Vec::new();
-        self.action_to_do = CleanUpActions::None;
-    }
-
     /// Assert cleaned up
-    pub fn assert_cleaned_up(&mut self) -> Result<(), ProgramError> {
+    pub fn assert_cleaned_up(&self) -> Result<(), ProgramError> {
         if self.action_to_do != CleanUpActions::None {
             return Err(NFTPacksError::WeightsNotCleanedUp.into());
         }
@@ -56,87 +716,55 @@ impl PackConfig {
 
     /// Sort the weights vec
     pub fn sort(&mut self) {
-        self.weights.sort_by(|a, b| b.1.cmp(&a.1));
+        sort_weights(&mut self.weights);
     }
 
     /// Remove a weight
     pub fn remove_at(&mut self, index: u32) {
-        let idx = self.weights.iter().position(|x| x.0 == index);
-        idx.map(|x| self.weights.remove(x));
+        if let Some(idx) = self.weights.iter().position(|x| x.0 == index) {
+            self.weights.remove(idx);
+        }
     }
 
-    /// Change weight
+    /// Change weight, weights stay sorted
     pub fn change_weight(&mut self, index: u32, new_value: u32) -> Result<(), ProgramError> {
-        let idx = self
+        let elem = self
             .weights
-            .iter()
-            .position(|x| x.0 == index)
+            .iter_mut()
+            .find(|x| x.0 == index)
             .ok_or(NFTPacksError::InvalidWeightPosition)?;
+        elem.1 = new_value;
 
-        if let Some(elem) = self.weights.get_mut(idx) {
-            *elem = (elem.0, new_value, elem.2);
-        }
-
-        if idx != self.weights.len() - 1 {
-            let next_value_idx = (idx as u32).error_increment()? as usize;
-            let next_value = self
-                .weights
-                .get_mut(next_value_idx)
-                .ok_or(NFTPacksError::InvalidWeightPosition)?;
-
-            if next_value.1 > new_value {
-                self.weights.swap(next_value_idx, index as usize);
-            }
-        }
+        self.sort();
 
         Ok(())
     }
 
     /// Change supply
     pub fn change_supply(&mut self, index: u32, new_value: u32) -> Result<(), ProgramError> {
-        let idx = self
+        let elem = self
             .weights
-            .iter()
-            .position(|x| x.0 == index)
+            .iter_mut()
+            .find(|x| x.0 == index)
             .ok_or(NFTPacksError::InvalidWeightPosition)?;
-
-        if let Some(elem) = self.weights.get_mut(idx) {
-            *elem = (elem.0, elem.1, new_value);
-        }
+        elem.2 = new_value;
 
         Ok(())
     }
 
     /// Select a random choice with weights
     pub fn select_weighted_random(
-        &mut self,
+        &self,
         rand: u16,
         weight_sum: u64,
     ) -> Result<(u32, u32, u32), ProgramError> {
-        let selected = self.weights.last().unwrap();
-        let mut bound = if weight_sum == 0 {
-            let max = rand / self.weights.len() as u16;
-            rand.clamp(0, max) as u32
-        } else {
-            let rndp = rand as f64 / u16::MAX as f64;
-            (rndp * weight_sum as f64).round().to_u32().unwrap()
-        };
-        for i in self.weights.iter() {
-            bound = match bound.error_sub(i.1) {
-                Ok(num) => num,
-                Err(_) => 0,
-            };
-            if bound <= 0 {
-                return Ok(i.clone());
-            }
-        }
-        return Ok(selected.clone());
+        select_sorted_weighted_random(&self.weights, rand, weight_sum)
     }
 }
 
-impl Sealed for PackConfig {}
+impl Sealed for LegacyPackConfig {}
 
-impl Pack for PackConfig {
+impl Pack for LegacyPackConfig {
     /// Max size of config to hold max allowed amount of cards - 100
     const LEN: usize = 1205;
 
@@ -154,9 +782,8 @@ impl Pack for PackConfig {
     }
 }
 
-impl IsInitialized for PackConfig {
+impl IsInitialized for LegacyPackConfig {
     fn is_initialized(&self) -> bool {
-        self.account_type != AccountType::Uninitialized
-            && self.account_type == AccountType::PackConfig
+        self.account_type == AccountType::PackConfig
     }
 }
@@ -4,13 +4,16 @@ use super::*;
 use crate::{
     error::NFTPacksError,
     math::SafeMath,
-    state::{MAX_DESCRIPTION_LEN, MAX_URI_LENGTH},
-    MAX_WEIGHT_VALUE,
+    state::{
+        MAX_PACK_PHASES, MAX_PACK_SLOTS, MAX_RARITY_TIERS, MAX_TIER_PROBABILITY,
+        MAX_TREASURY_RECIPIENTS, MAX_TREASURY_SHARE,
+    },
+    MAX_WEIGHT_VALUE, PROGRAM_VERSION,
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use metaplex_token_metadata::state::{MasterEdition, MasterEditionV2};
 use solana_program::{
-    borsh::try_from_slice_unchecked,
+    hash::hashv,
     msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
@@ -53,11 +56,225 @@ impl Default for PackDistributionType {
     }
 }
 
+/// Version of random value derivation used to draw cards
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum RandomnessVersion {
+    /// Legacy u16 value mixed with DefaultHasher
+    V0,
+    /// u64 value derived with sha256, see `utils::derive_random_value`
+    V1,
+}
+
+impl Default for RandomnessVersion {
+    fn default() -> Self {
+        Self::V0
+    }
+}
+
+impl RandomnessVersion {
+    /// Max random value which can be derived
+    pub fn max_random_value(&self) -> u64 {
+        match self {
+            Self::V0 => u16::MAX as u64,
+            Self::V1 => u64::MAX,
+        }
+    }
+}
+
+/// Source of random values used to draw cards
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum RandomnessSource {
+    /// RandomOracle program value read at the moment of draw
+    Oracle,
+    /// Authority secret committed before activation and revealed to resolve pending draws
+    CommitReveal,
+    /// Value signed by off-chain server key, signature is checked with Ed25519 program instruction
+    SignedValue {
+        /// Server key which signs random values
+        signer: Pubkey,
+    },
+}
+
+impl Default for RandomnessSource {
+    fn default() -> Self {
+        Self::Oracle
+    }
+}
+
+/// Treasury recipient
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct TreasuryRecipient {
+    /// Recipient wallet
+    pub address: Pubkey,
+    /// Share of treasury in basis points
+    pub share: u16,
+}
+
+/// Rarity tier, cards are assigned to tier by its position in pack set tiers list
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct RarityTier {
+    /// Probability to draw a card from this tier in basis points
+    pub probability: u16,
+}
+
+/// Pack slot, defines rarity tiers card of this slot can be drawn from
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct PackSlot {
+    /// Bit mask of allowed rarity tiers, bit `i` is set if card can be drawn from tier `i`
+    pub tiers: u16,
+}
+
+impl PackSlot {
+    /// Check if card can be drawn from tier
+    pub fn has_tier(&self, tier: u8) -> bool {
+        (tier as usize) < MAX_RARITY_TIERS && self.tiers & (1 << tier) != 0
+    }
+}
+
+/// Bad luck protection rule. Draws without rare card are counted for every wallet
+/// and after `max_misses` of them next draw picks only from rare cards
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct PityRule {
+    /// Card is rare if its weight in pack config is less or equal to threshold
+    pub rare_weight_threshold: u32,
+    /// Amount of draws in a row without rare card which triggers the rule
+    pub max_misses: u32,
+}
+
+impl PityRule {
+    /// Check if pack config entry is a rare card
+    pub fn is_rare(&self, card: &(u32, u32, u32)) -> bool {
+        card.1 <= self.rare_weight_threshold
+    }
+}
+
+/// Gate of pack phase, defines which wallets can open vouchers while phase is active
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum PhaseGate {
+    /// Any voucher holder can open it
+    Open,
+    /// Wallet should hold allowlist token, one token is burned for every opened voucher
+    Allowlist {
+        /// Allowlist token mint
+        mint: Pubkey,
+    },
+    /// Wallet should hold at least one token of mint
+    TokenHolder {
+        /// Token mint
+        mint: Pubkey,
+    },
+}
+
+impl Default for PhaseGate {
+    fn default() -> Self {
+        Self::Open
+    }
+}
+
+/// Redemption phase. If pack has phases vouchers can be opened only while one of them is active
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct PackPhase {
+    /// Date when phase starts
+    pub start_date: u64,
+    /// Date when phase ends, if not set phase lasts until the next one starts
+    pub end_date: Option<u64>,
+    /// Gate which wallet should pass to open voucher
+    pub gate: PhaseGate,
+    /// Max amount of vouchers one wallet can open during phase
+    pub max_per_wallet: Option<u32>,
+}
+
+impl PackPhase {
+    /// Check if phase is active, phases should be ordered by start date
+    pub fn is_active(&self, next_phase: Option<&PackPhase>, current_timestamp: u64) -> bool {
+        current_timestamp >= self.start_date
+            && self
+                .end_date
+                .map_or(true, |end_date| current_timestamp <= end_date)
+            && next_phase.map_or(true, |next_phase| current_timestamp < next_phase.start_date)
+    }
+}
+
+/// Proof that wallet is in pack allowlist, allowlist is a merkle tree of wallets with their quotas
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct AllowlistProof {
+    /// Amount of vouchers wallet can open, `None` if it isn't limited
+    pub quota: Option<u32>,
+    /// Sibling hashes on the path from wallet leaf to the root
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl AllowlistProof {
+    /// Allowlist tree leaf, sha256 of wallet key and quota serialized with borsh
+    pub fn leaf(wallet: &Pubkey, quota: Option<u32>) -> [u8; 32] {
+        match quota {
+            Some(quota) => hashv(&[wallet.as_ref(), &[1], &quota.to_le_bytes()]),
+            None => hashv(&[wallet.as_ref(), &[0]]),
+        }
+        .to_bytes()
+    }
+
+    /// Check if proof leads from wallet leaf to the root, every pair of nodes is hashed in sorted order
+    pub fn verify(&self, root: &[u8; 32], wallet: &Pubkey) -> bool {
+        let node = self
+            .proof
+            .iter()
+            .fold(Self::leaf(wallet, self.quota), |node, sibling| {
+                if node <= *sibling {
+                    hashv(&[&node, sibling]).to_bytes()
+                } else {
+                    hashv(&[sibling, &node]).to_bytes()
+                }
+            });
+
+        node == *root
+    }
+}
+
+/// NFTs whose holders can open vouchers of gated pack
+#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum HolderGateKind {
+    /// NFT should belong to verified Metaplex collection
+    Collection {
+        /// Collection NFT mint
+        mint: Pubkey,
+    },
+    /// NFT should have verified creator
+    VerifiedCreator {
+        /// Creator address
+        creator: Pubkey,
+    },
+}
+
+impl Default for HolderGateKind {
+    fn default() -> Self {
+        Self::VerifiedCreator {
+            creator: Pubkey::default(),
+        }
+    }
+}
+
+/// Gate which requires wallet to hold qualifying NFT to open voucher
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct HolderGate {
+    /// NFTs which qualify
+    pub kind: HolderGateKind,
+    /// How many vouchers can be opened with one NFT, uses are counted in its `GateUsage`
+    pub max_uses: u32,
+}
+
 /// Pack set
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
 pub struct PackSet {
-    /// Account type - PackSet
+    /// Account type - PackSet, stored as `AccountType::PackSetV2` in fixed layout
     pub account_type: AccountType,
     /// Store
     pub store: Pubkey,
@@ -89,6 +306,48 @@ pub struct PackSet {
     pub redeem_start_date: u64,
     /// Date when pack set becomes inactive
     pub redeem_end_date: Option<u64>,
+    /// Voucher price for primary sale, pack isn't for sale if it's not set
+    pub price: Option<u64>,
+    /// Mint of SPL token accepted as payment, if not set price is in lamports
+    pub payment_mint: Option<Pubkey>,
+    /// Treasury recipients, what's left after their shares goes to authority
+    pub treasury_recipients: Vec<TreasuryRecipient>,
+    /// Version of random value derivation
+    pub randomness_version: RandomnessVersion,
+    /// Source of random values
+    pub randomness_source: RandomnessSource,
+    /// Hash of authority secret of the open randomness round, pack with
+    /// `RandomnessSource::CommitReveal` accepts draw requests only while round is open
+    pub randomness_commitment: Option<[u8; 32]>,
+    /// Amount of committed randomness rounds, see `RandomnessRound`
+    pub randomness_rounds: u32,
+    /// Rarity tiers, if set draw picks a tier first and then a card within it
+    pub rarity_tiers: Vec<RarityTier>,
+    /// Slot layout, n-th slot is used for draw when proving process has n cards to redeem
+    pub pack_slots: Vec<PackSlot>,
+    /// If true every card drawn with one voucher is distinct
+    pub unique_cards: bool,
+    /// Bad luck protection rule, if set every wallet has to pass its `WalletRecord` to draw cards
+    pub pity_rule: Option<PityRule>,
+    /// Pack config account
+    pub pack_config: Pubkey,
+    /// Layout version
+    pub version: u8,
+    /// Redemption phases ordered by start date, stored only in fixed layout
+    #[borsh_skip]
+    pub phases: Vec<PackPhase>,
+    /// Merkle root of wallets allowed to open vouchers, stored only in fixed layout
+    #[borsh_skip]
+    pub allowlist_root: Option<[u8; 32]>,
+    /// Gate which requires wallet to hold qualifying NFT, stored only in fixed layout
+    #[borsh_skip]
+    pub holder_gate: Option<HolderGate>,
+    /// Max amount of vouchers one wallet can open, stored only in fixed layout
+    #[borsh_skip]
+    pub max_vouchers_per_wallet: Option<u32>,
+    /// Amount of card changes made to restock deactivated pack, stored only in fixed layout
+    #[borsh_skip]
+    pub restocks: u32,
 }
 
 impl PackSet {
@@ -110,6 +369,24 @@ impl PackSet {
         self.allowed_amount_to_redeem = params.allowed_amount_to_redeem;
         self.redeem_start_date = params.redeem_start_date;
         self.redeem_end_date = params.redeem_end_date;
+        self.price = params.price;
+        self.payment_mint = params.payment_mint;
+        self.treasury_recipients = Vec::new();
+        self.randomness_version = RandomnessVersion::V1;
+        self.randomness_source = params.randomness_source;
+        self.randomness_commitment = None;
+        self.randomness_rounds = 0;
+        self.rarity_tiers = Vec::new();
+        self.pack_slots = Vec::new();
+        self.unique_cards = params.unique_cards;
+        self.pity_rule = None;
+        self.pack_config = params.pack_config;
+        self.version = PROGRAM_VERSION;
+        self.phases = Vec::new();
+        self.allowlist_root = None;
+        self.holder_gate = None;
+        self.max_vouchers_per_wallet = None;
+        self.restocks = 0;
     }
 
     /// Increase pack cards counter
@@ -120,7 +397,7 @@ impl PackSet {
 
     /// Increase pack voucher counter
     pub fn add_pack_voucher(&mut self) -> Result<(), ProgramError> {
-        self.pack_vouchers += self.pack_vouchers.error_increment()?;
+        self.pack_vouchers = self.pack_vouchers.error_increment()?;
         Ok(())
     }
 
@@ -139,6 +416,284 @@ impl PackSet {
         Ok(())
     }
 
+    /// Returns voucher price if pack is for sale
+    pub fn get_price(&self) -> Result<u64, ProgramError> {
+        self.price
+            .ok_or_else(|| NFTPacksError::PackNotForSale.into())
+    }
+
+    /// Set treasury recipients, pack should be in not activated state
+    pub fn set_treasury_recipients(
+        &mut self,
+        recipients: Vec<TreasuryRecipient>,
+    ) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if recipients.len() > MAX_TREASURY_RECIPIENTS {
+            return Err(NFTPacksError::TooManyTreasuryRecipients.into());
+        }
+
+        let mut total_share: u16 = 0;
+        for (i, recipient) in recipients.iter().enumerate() {
+            if recipient.share == 0 {
+                return Err(NFTPacksError::WrongTreasuryShares.into());
+            }
+
+            if recipients[..i]
+                .iter()
+                .any(|r| r.address == recipient.address)
+            {
+                return Err(NFTPacksError::DuplicateTreasuryRecipient.into());
+            }
+
+            total_share = total_share.error_add(recipient.share)?;
+        }
+
+        if total_share > MAX_TREASURY_SHARE {
+            return Err(NFTPacksError::WrongTreasuryShares.into());
+        }
+
+        self.treasury_recipients = recipients;
+
+        Ok(())
+    }
+
+    /// Set rarity tiers, pack should be in not activated state and shouldn't have cards yet.
+    /// Tier probabilities should add up to 100%, empty list removes tiers
+    pub fn set_rarity_tiers(&mut self, tiers: Vec<RarityTier>) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if self.pack_cards != 0 {
+            return Err(NFTPacksError::PackSetHasCards.into());
+        }
+
+        if self.randomness_version == RandomnessVersion::V0 {
+            return Err(NFTPacksError::UnsupportedRandomnessVersion.into());
+        }
+
+        if tiers.len() > MAX_RARITY_TIERS {
+            return Err(NFTPacksError::WrongRarityTiers.into());
+        }
+
+        let mut total_probability: u16 = 0;
+        for tier in tiers.iter() {
+            if tier.probability == 0 {
+                return Err(NFTPacksError::WrongRarityTiers.into());
+            }
+
+            total_probability = total_probability.error_add(tier.probability)?;
+        }
+
+        if !tiers.is_empty() && total_probability != MAX_TIER_PROBABILITY {
+            return Err(NFTPacksError::WrongRarityTiers.into());
+        }
+
+        // Slots can't refer to removed tiers
+        assert_pack_slots(&self.pack_slots, tiers.len())?;
+
+        self.rarity_tiers = tiers;
+
+        Ok(())
+    }
+
+    /// Set slot layout, pack should be in not activated state and have rarity tiers.
+    /// Empty list removes layout
+    pub fn set_pack_slots(&mut self, slots: Vec<PackSlot>) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if slots.len() > MAX_PACK_SLOTS {
+            return Err(NFTPacksError::WrongPackSlots.into());
+        }
+
+        assert_pack_slots(&slots, self.rarity_tiers.len())?;
+
+        self.pack_slots = slots;
+
+        Ok(())
+    }
+
+    /// Returns slot for the next draw, `None` if draw isn't limited with slot layout
+    pub fn get_pack_slot(&self, cards_to_redeem: usize) -> Option<&PackSlot> {
+        self.pack_slots.get(cards_to_redeem)
+    }
+
+    /// Set redemption phases, pack should be in not activated state. Empty list removes phases
+    pub fn set_phases(&mut self, phases: Vec<PackPhase>) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if phases.len() > MAX_PACK_PHASES {
+            return Err(NFTPacksError::WrongPackPhases.into());
+        }
+
+        assert_pack_phases(&phases)?;
+
+        self.phases = phases;
+
+        Ok(())
+    }
+
+    /// Set merkle root of allowlist, pack should be in not activated state. `None` removes allowlist
+    pub fn set_allowlist_root(&mut self, root: Option<[u8; 32]>) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        self.allowlist_root = root;
+
+        Ok(())
+    }
+
+    /// Set NFT holder gate, pack should be in not activated state. `None` removes the gate
+    pub fn set_holder_gate(&mut self, holder_gate: Option<HolderGate>) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if let Some(holder_gate) = &holder_gate {
+            if holder_gate.max_uses == 0 {
+                return Err(NFTPacksError::WrongHolderGate.into());
+            }
+        }
+
+        self.holder_gate = holder_gate;
+
+        Ok(())
+    }
+
+    /// Set max amount of vouchers one wallet can open, pack should be in not activated state.
+    /// `None` removes the limit
+    pub fn set_max_vouchers_per_wallet(
+        &mut self,
+        max_vouchers_per_wallet: Option<u32>,
+    ) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if max_vouchers_per_wallet == Some(0) {
+            return Err(NFTPacksError::WrongWalletVoucherLimit.into());
+        }
+
+        self.max_vouchers_per_wallet = max_vouchers_per_wallet;
+
+        Ok(())
+    }
+
+    /// Set bad luck protection rule, pack should be in not activated state and shouldn't have cards
+    /// because pack config keeps rare cards separately. `None` removes the rule
+    pub fn set_pity_rule(&mut self, pity_rule: Option<PityRule>) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if self.randomness_version == RandomnessVersion::V0 {
+            return Err(NFTPacksError::UnsupportedRandomnessVersion.into());
+        }
+
+        if let Some(rule) = &pity_rule {
+            if rule.max_misses == 0 {
+                return Err(NFTPacksError::WrongPityRule.into());
+            }
+
+            // Card rarity is its weight in pack config, which is supply left for MaxSupply distribution
+            if self.distribution_type == PackDistributionType::MaxSupply {
+                return Err(NFTPacksError::PityRuleWithMaxSupply.into());
+            }
+        }
+
+        if self.pack_cards != 0 {
+            return Err(NFTPacksError::PackSetHasCards.into());
+        }
+
+        self.pity_rule = pity_rule;
+
+        Ok(())
+    }
+
+    /// Check card tier matches pack rarity tiers
+    pub fn assert_card_tier(&self, tier: Option<u8>) -> Result<(), ProgramError> {
+        let is_valid = match tier {
+            Some(tier) => (tier as usize) < self.rarity_tiers.len(),
+            None => self.rarity_tiers.is_empty(),
+        };
+
+        if !is_valid {
+            return Err(NFTPacksError::WrongRarityTier.into());
+        }
+
+        Ok(())
+    }
+
+    /// Open new randomness round with hash of authority secret, previous round should be revealed.
+    /// Returns index of the new round
+    pub fn commit_randomness(&mut self, commitment: [u8; 32]) -> Result<u32, ProgramError> {
+        if self.randomness_source != RandomnessSource::CommitReveal {
+            return Err(NFTPacksError::WrongRandomnessSource.into());
+        }
+
+        if self.pack_state == PackSetState::Ended {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if self.randomness_commitment.is_some() {
+            return Err(NFTPacksError::RandomnessAlreadyCommitted.into());
+        }
+
+        let index = self.randomness_rounds;
+        self.randomness_commitment = Some(commitment);
+        self.randomness_rounds = self.randomness_rounds.error_increment()?;
+
+        Ok(index)
+    }
+
+    /// Check if pack has enough cards to give distinct card for every draw of voucher
+    pub fn assert_enough_distinct_cards(&self) -> Result<(), ProgramError> {
+        if self.unique_cards && self.pack_cards < self.allowed_amount_to_redeem {
+            return Err(NFTPacksError::NotEnoughDistinctCards.into());
+        }
+
+        Ok(())
+    }
+
+    /// Check if pack is able to be activated with its randomness source
+    pub fn assert_randomness_committed(&self) -> Result<(), ProgramError> {
+        if self.randomness_source == RandomnessSource::CommitReveal
+            && self.randomness_commitment.is_none()
+        {
+            return Err(NFTPacksError::RandomnessNotCommitted.into());
+        }
+
+        Ok(())
+    }
+
+    /// Check if authority is able to reveal randomness of the pack
+    pub fn assert_able_to_reveal(&self) -> Result<(), ProgramError> {
+        if self.randomness_source != RandomnessSource::CommitReveal {
+            return Err(NFTPacksError::WrongRandomnessSource.into());
+        }
+
+        if self.pack_state == PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        Ok(())
+    }
+
+    /// Close round after its secret is revealed, new draws wait for the next commitment
+    pub fn close_randomness_round(&mut self, index: u32) {
+        if index.saturating_add(1) == self.randomness_rounds {
+            self.randomness_commitment = None;
+        }
+    }
+
     /// Check if pack is in ended state
     pub fn assert_ended(&self) -> Result<(), ProgramError> {
         if self.pack_state != PackSetState::Ended {
@@ -161,6 +716,128 @@ impl PackSet {
         Ok(())
     }
 
+    /// Set allowed amount to redeem, pack should be in not activated state
+    pub fn set_allowed_amount_to_redeem(
+        &mut self,
+        allowed_amount_to_redeem: u32,
+    ) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::NotActivated {
+            return Err(NFTPacksError::WrongPackState.into());
+        }
+
+        if allowed_amount_to_redeem == self.allowed_amount_to_redeem {
+            return Err(NFTPacksError::CantSetTheSameValue.into());
+        }
+
+        if allowed_amount_to_redeem == 0 || allowed_amount_to_redeem > MAX_CARDS_TO_REDEEM {
+            return Err(NFTPacksError::WrongAllowedAmountToRedeem.into());
+        }
+
+        self.allowed_amount_to_redeem = allowed_amount_to_redeem;
+
+        Ok(())
+    }
+
+    /// Change redeem dates, start date can be changed only in not activated state
+    /// and end date can be only extended in deactivated state
+    pub fn set_redeem_dates(
+        &mut self,
+        redeem_start_date: Option<u64>,
+        redeem_end_date: Option<u64>,
+        current_timestamp: u64,
+    ) -> Result<(), ProgramError> {
+        if let Some(redeem_start_date) = redeem_start_date {
+            if self.pack_state != PackSetState::NotActivated {
+                return Err(NFTPacksError::WrongPackState.into());
+            }
+
+            if redeem_start_date == self.redeem_start_date {
+                return Err(NFTPacksError::CantSetTheSameValue.into());
+            }
+
+            if redeem_start_date < current_timestamp {
+                return Err(NFTPacksError::WrongRedeemDate.into());
+            }
+
+            self.redeem_start_date = redeem_start_date;
+        }
+
+        if let Some(redeem_end_date) = redeem_end_date {
+            if Some(redeem_end_date) == self.redeem_end_date {
+                return Err(NFTPacksError::CantSetTheSameValue.into());
+            }
+
+            match self.pack_state {
+                PackSetState::NotActivated => {}
+                PackSetState::Deactivated => {
+                    // pack without end date can't be shortened
+                    let can_extend = self
+                        .redeem_end_date
+                        .map_or(false, |end_date| redeem_end_date > end_date);
+                    if !can_extend {
+                        return Err(NFTPacksError::WrongRedeemDate.into());
+                    }
+                }
+                _ => return Err(NFTPacksError::WrongPackState.into()),
+            }
+
+            if redeem_end_date <= current_timestamp {
+                return Err(NFTPacksError::WrongRedeemDate.into());
+            }
+
+            self.redeem_end_date = Some(redeem_end_date);
+        }
+
+        if let Some(redeem_end_date) = self.redeem_end_date {
+            if redeem_end_date <= self.redeem_start_date {
+                return Err(NFTPacksError::WrongRedeemDate.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if pack cards can be edited, pack should be in not activated state or deactivated and mutable
+    pub fn assert_able_to_edit_cards(&self) -> Result<(), ProgramError> {
+        match self.pack_state {
+            PackSetState::NotActivated => Ok(()),
+            PackSetState::Deactivated => {
+                if !self.mutable {
+                    return Err(NFTPacksError::ImmutablePackSet.into());
+                }
+
+                Ok(())
+            }
+            _ => Err(NFTPacksError::WrongPackState.into()),
+        }
+    }
+
+    /// Count card change made in deactivated pack and log it, so users can see pack odds changed since activation
+    pub fn record_restock(
+        &mut self,
+        index: u32,
+        max_supply: u32,
+        weight: u16,
+    ) -> Result<(), ProgramError> {
+        if self.pack_state != PackSetState::Deactivated {
+            return Ok(());
+        }
+
+        self.restocks = self.restocks.error_increment()?;
+
+        msg!(
+            "Restock {}: card {} max supply {} weight {}, pack total editions {} total weight {}",
+            self.restocks,
+            index,
+            max_supply,
+            weight,
+            self.total_editions,
+            self.total_weight
+        );
+
+        Ok(())
+    }
+
     /// Add new card volume to pack
     pub fn add_card_volume(
         &mut self,
@@ -224,22 +901,69 @@ impl PackSet {
         Ok(())
     }
 
-    /// fill unused bytes with zeroes
-    pub fn puff_out_data_fields(&mut self) {
-        let mut array_of_zeroes = vec![];
-        while array_of_zeroes.len() < MAX_URI_LENGTH - self.uri.len() {
-            array_of_zeroes.push(0u8);
+    /// Remove card volume from pack
+    pub fn remove_card_volume(
+        &mut self,
+        card_weight: u32,
+        card_supply: u32,
+    ) -> Result<(), ProgramError> {
+        match self.distribution_type {
+            PackDistributionType::Unlimited => {
+                self.total_weight = self.total_weight.error_sub(card_weight as u64)?;
+            }
+            PackDistributionType::MaxSupply => {
+                self.total_editions = self.total_editions.error_sub(card_supply as u64)?;
+            }
+            PackDistributionType::Fixed => {
+                self.total_editions = self.total_editions.error_sub(card_supply as u64)?;
+                self.total_weight = self.total_weight.error_sub(card_weight as u64)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if pack set data is serialized with borsh instead of fixed layout
+    pub fn is_legacy(data: &[u8]) -> bool {
+        data.first() == Some(&(AccountType::PackSet as u8))
+    }
+}
+
+/// Check every slot allows at least one of existing rarity tiers and only them
+fn assert_pack_slots(slots: &[PackSlot], tiers_amount: usize) -> Result<(), ProgramError> {
+    let all_tiers = ((1u32 << tiers_amount) - 1) as u16;
+
+    for slot in slots {
+        if slot.tiers == 0 || slot.tiers & !all_tiers != 0 {
+            return Err(NFTPacksError::WrongPackSlots.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Check phases are ordered by start date and don't overlap
+fn assert_pack_phases(phases: &[PackPhase]) -> Result<(), ProgramError> {
+    for (i, phase) in phases.iter().enumerate() {
+        if phase.max_per_wallet == Some(0) {
+            return Err(NFTPacksError::WrongPackPhases.into());
         }
-        self.uri = self.uri.clone() + std::str::from_utf8(&array_of_zeroes).unwrap();
 
-        let mut array_of_zeroes = vec![];
+        if let Some(end_date) = phase.end_date {
+            if end_date <= phase.start_date {
+                return Err(NFTPacksError::WrongPackPhases.into());
+            }
+        }
 
-        while array_of_zeroes.len() < MAX_DESCRIPTION_LEN - self.description.len() {
-            array_of_zeroes.push(0u8);
+        if let Some(next_phase) = phases.get(i + 1) {
+            let phase_end = phase.end_date.unwrap_or(phase.start_date);
+            if next_phase.start_date <= phase_end {
+                return Err(NFTPacksError::WrongPackPhases.into());
+            }
         }
-        self.description =
-            self.description.clone() + std::str::from_utf8(&array_of_zeroes).unwrap();
     }
+
+    Ok(())
 }
 
 /// Initialize a PackSet params
@@ -264,29 +988,72 @@ pub struct InitPackSetParams {
     pub redeem_start_date: u64,
     /// Redeem end date
     pub redeem_end_date: Option<u64>,
+    /// Voucher price
+    pub price: Option<u64>,
+    /// Payment mint
+    pub payment_mint: Option<Pubkey>,
+    /// Randomness source
+    pub randomness_source: RandomnessSource,
+    /// If true every card drawn with one voucher is distinct
+    pub unique_cards: bool,
+    /// Pack config account
+    pub pack_config: Pubkey,
 }
 
 impl Sealed for PackSet {}
 
 impl Pack for PackSet {
-    const LEN: usize = 853;
+    /// Pack set with space for fields added after `LEGACY_LEN`, the rest is reserved
+    const LEN: usize = 2048;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let mut slice = dst;
-        self.serialize(&mut slice).unwrap()
+        PackSetView::new_unchecked(dst).write(self).unwrap()
     }
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        if (src[0] != AccountType::PackSet as u8 && src[0] != AccountType::Uninitialized as u8)
-            || src.len() != Self::LEN
-        {
+        if src.len() != Self::LEN && src.len() != Self::LEGACY_LEN {
+            msg!("Failed to deserialize");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if Self::is_legacy(src) {
+            let mut result: Self = unpack_versioned(src, Self::LEN)?;
+            // Data after borsh fields isn't zeroed in accounts serialized with borsh
+            result.version = 0;
+
+            return Ok(result);
+        }
+
+        if src[0] != AccountType::PackSetV2 as u8 && src[0] != AccountType::Uninitialized as u8 {
             msg!("Failed to deserialize");
             return Err(ProgramError::InvalidAccountData);
         }
 
-        let result: Self = try_from_slice_unchecked(src)?;
+        PackSetView::new_unchecked(src).to_pack_set()
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        PackSetView::new_unchecked(dst).write(&src)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for PackSet {
+    /// Size of pack set serialized with borsh before fixed layout, description and uri are padded
+    /// to their max length. Fixed layout fields up to `redeem_end_date` fit in the same size so
+    /// accounts are converted in place, fields added after them are read as empty and can't be set
+    const LEGACY_LEN: usize = 853;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
 
-        Ok(result)
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
     }
 }
 
@@ -0,0 +1,905 @@
+//! Pack set zero-copy view
+
+use super::*;
+use crate::{error::NFTPacksError, math::SafeMath};
+use solana_program::{msg, program_error::ProgramError, program_pack::Pack};
+use std::borrow::Cow;
+
+const ACCOUNT_TYPE_OFFSET: usize = 0;
+const STORE_OFFSET: usize = ACCOUNT_TYPE_OFFSET + 1;
+const AUTHORITY_OFFSET: usize = STORE_OFFSET + 32;
+const DESCRIPTION_OFFSET: usize = AUTHORITY_OFFSET + 32;
+const URI_OFFSET: usize = DESCRIPTION_OFFSET + MAX_DESCRIPTION_LEN;
+const NAME_OFFSET: usize = URI_OFFSET + MAX_URI_LENGTH;
+const PACK_CARDS_OFFSET: usize = NAME_OFFSET + 32;
+const PACK_VOUCHERS_OFFSET: usize = PACK_CARDS_OFFSET + 4;
+const TOTAL_WEIGHT_OFFSET: usize = PACK_VOUCHERS_OFFSET + 4;
+const TOTAL_EDITIONS_OFFSET: usize = TOTAL_WEIGHT_OFFSET + 8;
+const MUTABLE_OFFSET: usize = TOTAL_EDITIONS_OFFSET + 8;
+const PACK_STATE_OFFSET: usize = MUTABLE_OFFSET + 1;
+const DISTRIBUTION_TYPE_OFFSET: usize = PACK_STATE_OFFSET + 1;
+const ALLOWED_AMOUNT_TO_REDEEM_OFFSET: usize = DISTRIBUTION_TYPE_OFFSET + 1;
+const REDEEM_START_DATE_OFFSET: usize = ALLOWED_AMOUNT_TO_REDEEM_OFFSET + 4;
+const REDEEM_END_DATE_OFFSET: usize = REDEEM_START_DATE_OFFSET + 8;
+const LEGACY_LAYOUT_LEN: usize = REDEEM_END_DATE_OFFSET + 1 + 8;
+// Fields below are stored only in pack sets of `PackSet::LEN` size
+const PRICE_OFFSET: usize = LEGACY_LAYOUT_LEN;
+const PAYMENT_MINT_OFFSET: usize = PRICE_OFFSET + 1 + 8;
+const TREASURY_RECIPIENTS_OFFSET: usize = PAYMENT_MINT_OFFSET + 1 + 32;
+const RANDOMNESS_VERSION_OFFSET: usize =
+    TREASURY_RECIPIENTS_OFFSET + 1 + MAX_TREASURY_RECIPIENTS * TREASURY_RECIPIENT_LEN;
+const RANDOMNESS_SOURCE_OFFSET: usize = RANDOMNESS_VERSION_OFFSET + 1;
+const RANDOMNESS_COMMITMENT_OFFSET: usize = RANDOMNESS_SOURCE_OFFSET + 1 + 32;
+const RANDOMNESS_ROUNDS_OFFSET: usize = RANDOMNESS_COMMITMENT_OFFSET + 1 + 32;
+const RARITY_TIERS_OFFSET: usize = RANDOMNESS_ROUNDS_OFFSET + 4;
+const PACK_SLOTS_OFFSET: usize = RARITY_TIERS_OFFSET + 1 + MAX_RARITY_TIERS * 2;
+const UNIQUE_CARDS_OFFSET: usize = PACK_SLOTS_OFFSET + 1 + MAX_PACK_SLOTS * 2;
+const PITY_RULE_OFFSET: usize = UNIQUE_CARDS_OFFSET + 1;
+const PACK_CONFIG_OFFSET: usize = PITY_RULE_OFFSET + 1 + 8;
+const VERSION_OFFSET: usize = PACK_CONFIG_OFFSET + 32;
+const PHASES_OFFSET: usize = VERSION_OFFSET + 1;
+const ALLOWLIST_ROOT_OFFSET: usize = PHASES_OFFSET + 1 + MAX_PACK_PHASES * PHASE_LEN;
+const HOLDER_GATE_OFFSET: usize = ALLOWLIST_ROOT_OFFSET + 1 + 32;
+const MAX_VOUCHERS_PER_WALLET_OFFSET: usize = HOLDER_GATE_OFFSET + 1 + HOLDER_GATE_LEN;
+const RESTOCKS_OFFSET: usize = MAX_VOUCHERS_PER_WALLET_OFFSET + 1 + 4;
+
+const TREASURY_RECIPIENT_LEN: usize = 32 + 2;
+// start date 8 + end date 1 + 8 + gate 1 + 32 + max per wallet 1 + 4
+const PHASE_LEN: usize = 55;
+// kind 1 + 32 + max uses 4
+const HOLDER_GATE_LEN: usize = 37;
+
+/// Zero-copy view of pack set account data.
+///
+/// Pack set is stored in fixed layout where every field has its own offset, so instructions
+/// which draw and claim cards read and write only fields they need instead of deserializing
+/// the whole account. Strings are padded with zero bytes, options are stored as flag byte
+/// followed by the value and vectors as length byte followed by space for max amount of items.
+///
+/// Accounts serialized with borsh before are marked with `AccountType::PackSet`, they can still
+/// be read and are converted to fixed layout on the next write. Such accounts have
+/// `PackSet::LEGACY_LEN` size which holds only fields up to `redeem_end_date`, fields added
+/// after it are read as empty.
+pub struct PackSetView<D> {
+    data: D,
+}
+
+impl<D: AsRef<[u8]>> PackSetView<D> {
+    /// Size of fixed layout, the rest of `PackSet::LEN` bytes is reserved for new fields
+    pub const LAYOUT_LEN: usize = RESTOCKS_OFFSET + 4;
+
+    /// View of pack set data in fixed layout
+    pub fn new(data: D) -> Result<Self, ProgramError> {
+        let view = Self::new_unchecked(data);
+
+        let len = view.data().len();
+        if (len != PackSet::LEN && len != PackSet::LEGACY_LEN)
+            || view.data()[ACCOUNT_TYPE_OFFSET] != AccountType::PackSetV2 as u8
+        {
+            msg!("Pack set isn't stored in fixed layout");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(view)
+    }
+
+    /// View of data without layout checks, used to serialize `PackSet`
+    pub(crate) fn new_unchecked(data: D) -> Self {
+        Self { data }
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// Check if pack set was created with old size and has only fields up to `redeem_end_date`
+    pub fn is_legacy_size(&self) -> bool {
+        self.data().len() < Self::LAYOUT_LEN
+    }
+
+    /// Store
+    pub fn store(&self) -> Pubkey {
+        read_pubkey(self.data(), STORE_OFFSET)
+    }
+
+    /// Pack authority
+    pub fn authority(&self) -> Pubkey {
+        read_pubkey(self.data(), AUTHORITY_OFFSET)
+    }
+
+    /// Description
+    pub fn description(&self) -> Result<String, ProgramError> {
+        read_string(self.data(), DESCRIPTION_OFFSET, MAX_DESCRIPTION_LEN)
+    }
+
+    /// Link to pack set image
+    pub fn uri(&self) -> Result<String, ProgramError> {
+        read_string(self.data(), URI_OFFSET, MAX_URI_LENGTH)
+    }
+
+    /// Name
+    pub fn name(&self) -> [u8; 32] {
+        read_array(self.data(), NAME_OFFSET)
+    }
+
+    /// Card masters counter
+    pub fn pack_cards(&self) -> u32 {
+        read_u32(self.data(), PACK_CARDS_OFFSET)
+    }
+
+    /// Pack voucher counter
+    pub fn pack_vouchers(&self) -> u32 {
+        read_u32(self.data(), PACK_VOUCHERS_OFFSET)
+    }
+
+    /// Total weight
+    pub fn total_weight(&self) -> u64 {
+        read_u64(self.data(), TOTAL_WEIGHT_OFFSET)
+    }
+
+    /// Total amount of editions pack can mint
+    pub fn total_editions(&self) -> u64 {
+        read_u64(self.data(), TOTAL_EDITIONS_OFFSET)
+    }
+
+    /// If true authority can make changes at deactivated phase
+    pub fn mutable(&self) -> bool {
+        self.data()[MUTABLE_OFFSET] != 0
+    }
+
+    /// Pack state
+    pub fn pack_state(&self) -> Result<PackSetState, ProgramError> {
+        match self.data()[PACK_STATE_OFFSET] {
+            0 => Ok(PackSetState::NotActivated),
+            1 => Ok(PackSetState::Activated),
+            2 => Ok(PackSetState::Deactivated),
+            3 => Ok(PackSetState::Ended),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Distribution type
+    pub fn distribution_type(&self) -> Result<PackDistributionType, ProgramError> {
+        match self.data()[DISTRIBUTION_TYPE_OFFSET] {
+            0 => Ok(PackDistributionType::MaxSupply),
+            1 => Ok(PackDistributionType::Fixed),
+            2 => Ok(PackDistributionType::Unlimited),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Count of cards user can try to redeem
+    pub fn allowed_amount_to_redeem(&self) -> u32 {
+        read_u32(self.data(), ALLOWED_AMOUNT_TO_REDEEM_OFFSET)
+    }
+
+    /// Date when users can start to redeem cards
+    pub fn redeem_start_date(&self) -> u64 {
+        read_u64(self.data(), REDEEM_START_DATE_OFFSET)
+    }
+
+    /// Date when pack set becomes inactive
+    pub fn redeem_end_date(&self) -> Option<u64> {
+        read_option(self.data(), REDEEM_END_DATE_OFFSET).map(|offset| read_u64(self.data(), offset))
+    }
+
+    /// Voucher price for primary sale, pack set of legacy size isn't for sale
+    pub fn price(&self) -> Option<u64> {
+        if self.is_legacy_size() {
+            return None;
+        }
+
+        read_option(self.data(), PRICE_OFFSET).map(|offset| read_u64(self.data(), offset))
+    }
+
+    /// Mint of SPL token accepted as payment
+    pub fn payment_mint(&self) -> Option<Pubkey> {
+        if self.is_legacy_size() {
+            return None;
+        }
+
+        read_option(self.data(), PAYMENT_MINT_OFFSET).map(|offset| read_pubkey(self.data(), offset))
+    }
+
+    /// Treasury recipients
+    pub fn treasury_recipients(&self) -> Result<Vec<TreasuryRecipient>, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(Vec::new());
+        }
+
+        let len = read_len(
+            self.data(),
+            TREASURY_RECIPIENTS_OFFSET,
+            MAX_TREASURY_RECIPIENTS,
+        )?;
+
+        Ok((0..len)
+            .map(|i| {
+                let offset = TREASURY_RECIPIENTS_OFFSET + 1 + i * TREASURY_RECIPIENT_LEN;
+                TreasuryRecipient {
+                    address: read_pubkey(self.data(), offset),
+                    share: read_u16(self.data(), offset + 32),
+                }
+            })
+            .collect())
+    }
+
+    /// Version of random value derivation, pack set of legacy size uses `V0`
+    pub fn randomness_version(&self) -> Result<RandomnessVersion, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(RandomnessVersion::V0);
+        }
+
+        match self.data()[RANDOMNESS_VERSION_OFFSET] {
+            0 => Ok(RandomnessVersion::V0),
+            1 => Ok(RandomnessVersion::V1),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Source of random values, pack set of legacy size uses oracle
+    pub fn randomness_source(&self) -> Result<RandomnessSource, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(RandomnessSource::Oracle);
+        }
+
+        match self.data()[RANDOMNESS_SOURCE_OFFSET] {
+            0 => Ok(RandomnessSource::Oracle),
+            1 => Ok(RandomnessSource::CommitReveal),
+            2 => Ok(RandomnessSource::SignedValue {
+                signer: read_pubkey(self.data(), RANDOMNESS_SOURCE_OFFSET + 1),
+            }),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Hash of authority secret of the open randomness round
+    pub fn randomness_commitment(&self) -> Option<[u8; 32]> {
+        if self.is_legacy_size() {
+            return None;
+        }
+
+        read_option(self.data(), RANDOMNESS_COMMITMENT_OFFSET)
+            .map(|offset| read_array(self.data(), offset))
+    }
+
+    /// NFT holder gate, pack set of legacy size has no gate
+    pub fn holder_gate(&self) -> Result<Option<HolderGate>, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(None);
+        }
+
+        read_option(self.data(), HOLDER_GATE_OFFSET)
+            .map(|offset| read_holder_gate(self.data(), offset))
+            .transpose()
+    }
+
+    /// Max amount of vouchers one wallet can open, pack set of legacy size has no limit
+    pub fn max_vouchers_per_wallet(&self) -> Option<u32> {
+        if self.is_legacy_size() {
+            return None;
+        }
+
+        read_option(self.data(), MAX_VOUCHERS_PER_WALLET_OFFSET)
+            .map(|offset| read_u32(self.data(), offset))
+    }
+
+    /// Amount of card changes made to restock deactivated pack, pack set of legacy size has none
+    pub fn restocks(&self) -> u32 {
+        if self.is_legacy_size() {
+            return 0;
+        }
+
+        read_u32(self.data(), RESTOCKS_OFFSET)
+    }
+
+    /// Amount of committed randomness rounds, the last one is open while commitment is set
+    pub fn randomness_rounds(&self) -> u32 {
+        if self.is_legacy_size() {
+            return 0;
+        }
+
+        read_u32(self.data(), RANDOMNESS_ROUNDS_OFFSET)
+    }
+
+    /// Index of randomness round which accepts draw requests
+    pub fn open_randomness_round(&self) -> Option<u32> {
+        self.randomness_commitment()
+            .map(|_| self.randomness_rounds().saturating_sub(1))
+    }
+
+    /// Rarity tiers
+    pub fn rarity_tiers(&self) -> Result<Vec<RarityTier>, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(Vec::new());
+        }
+
+        let len = read_len(self.data(), RARITY_TIERS_OFFSET, MAX_RARITY_TIERS)?;
+
+        Ok((0..len)
+            .map(|i| RarityTier {
+                probability: read_u16(self.data(), RARITY_TIERS_OFFSET + 1 + i * 2),
+            })
+            .collect())
+    }
+
+    /// Slot layout
+    pub fn pack_slots(&self) -> Result<Vec<PackSlot>, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(Vec::new());
+        }
+
+        let len = read_len(self.data(), PACK_SLOTS_OFFSET, MAX_PACK_SLOTS)?;
+
+        Ok((0..len)
+            .map(|i| PackSlot {
+                tiers: read_u16(self.data(), PACK_SLOTS_OFFSET + 1 + i * 2),
+            })
+            .collect())
+    }
+
+    /// Returns slot for the next draw, `None` if draw isn't limited with slot layout
+    pub fn get_pack_slot(&self, cards_to_redeem: usize) -> Result<Option<PackSlot>, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(None);
+        }
+
+        let len = read_len(self.data(), PACK_SLOTS_OFFSET, MAX_PACK_SLOTS)?;
+
+        if cards_to_redeem >= len {
+            return Ok(None);
+        }
+
+        Ok(Some(PackSlot {
+            tiers: read_u16(self.data(), PACK_SLOTS_OFFSET + 1 + cards_to_redeem * 2),
+        }))
+    }
+
+    /// If true every card drawn with one voucher is distinct
+    pub fn unique_cards(&self) -> bool {
+        if self.is_legacy_size() {
+            return false;
+        }
+
+        self.data()[UNIQUE_CARDS_OFFSET] != 0
+    }
+
+    /// Bad luck protection rule
+    pub fn pity_rule(&self) -> Option<PityRule> {
+        if self.is_legacy_size() {
+            return None;
+        }
+
+        read_option(self.data(), PITY_RULE_OFFSET).map(|offset| PityRule {
+            rare_weight_threshold: read_u32(self.data(), offset),
+            max_misses: read_u32(self.data(), offset + 4),
+        })
+    }
+
+    /// Pack config account, pack set of legacy size uses legacy pack config PDA
+    pub fn pack_config(&self) -> Pubkey {
+        if self.is_legacy_size() {
+            return Pubkey::default();
+        }
+
+        read_pubkey(self.data(), PACK_CONFIG_OFFSET)
+    }
+
+    /// Layout version, pack set of legacy size isn't migrated
+    pub fn version(&self) -> u8 {
+        if self.is_legacy_size() {
+            return 0;
+        }
+
+        self.data()[VERSION_OFFSET]
+    }
+
+    /// Redemption phases, pack set of legacy size has no phases
+    pub fn phases(&self) -> Result<Vec<PackPhase>, ProgramError> {
+        if self.is_legacy_size() {
+            return Ok(Vec::new());
+        }
+
+        let len = read_len(self.data(), PHASES_OFFSET, MAX_PACK_PHASES)?;
+
+        (0..len)
+            .map(|i| read_phase(self.data(), PHASES_OFFSET + 1 + i * PHASE_LEN))
+            .collect()
+    }
+
+    /// Merkle root of allowlist, pack set of legacy size has no allowlist
+    pub fn allowlist_root(&self) -> Option<[u8; 32]> {
+        if self.is_legacy_size() {
+            return None;
+        }
+
+        read_option(self.data(), ALLOWLIST_ROOT_OFFSET)
+            .map(|offset| read_array(self.data(), offset))
+    }
+
+    /// Returns index of active phase and the phase, `None` if pack doesn't have phases
+    pub fn get_active_phase(
+        &self,
+        current_timestamp: u64,
+    ) -> Result<Option<(usize, PackPhase)>, ProgramError> {
+        let phases = self.phases()?;
+        if phases.is_empty() {
+            return Ok(None);
+        }
+
+        let active_phase = phases
+            .iter()
+            .enumerate()
+            .find(|(i, phase)| phase.is_active(phases.get(i + 1), current_timestamp))
+            .map(|(i, phase)| (i, phase.clone()))
+            .ok_or(NFTPacksError::PackPhaseNotActive)?;
+
+        Ok(Some(active_phase))
+    }
+
+    /// Check if pack is in activated state
+    pub fn assert_activated(&self) -> Result<(), ProgramError> {
+        if self.pack_state()? != PackSetState::Activated {
+            return Err(NFTPacksError::PackSetNotActivated.into());
+        }
+
+        Ok(())
+    }
+
+    /// Read all the fields
+    pub fn to_pack_set(&self) -> Result<PackSet, ProgramError> {
+        let account_type = match self.data()[ACCOUNT_TYPE_OFFSET] {
+            0 => AccountType::Uninitialized,
+            _ => AccountType::PackSet,
+        };
+
+        Ok(PackSet {
+            account_type,
+            store: self.store(),
+            authority: self.authority(),
+            description: self.description()?,
+            uri: self.uri()?,
+            name: self.name(),
+            pack_cards: self.pack_cards(),
+            pack_vouchers: self.pack_vouchers(),
+            total_weight: self.total_weight(),
+            total_editions: self.total_editions(),
+            mutable: self.mutable(),
+            pack_state: self.pack_state()?,
+            distribution_type: self.distribution_type()?,
+            allowed_amount_to_redeem: self.allowed_amount_to_redeem(),
+            redeem_start_date: self.redeem_start_date(),
+            redeem_end_date: self.redeem_end_date(),
+            price: self.price(),
+            payment_mint: self.payment_mint(),
+            treasury_recipients: self.treasury_recipients()?,
+            randomness_version: self.randomness_version()?,
+            randomness_source: self.randomness_source()?,
+            randomness_commitment: self.randomness_commitment(),
+            randomness_rounds: self.randomness_rounds(),
+            rarity_tiers: self.rarity_tiers()?,
+            pack_slots: self.pack_slots()?,
+            unique_cards: self.unique_cards(),
+            pity_rule: self.pity_rule(),
+            pack_config: self.pack_config(),
+            version: self.version(),
+            phases: self.phases()?,
+            allowlist_root: self.allowlist_root(),
+            holder_gate: self.holder_gate()?,
+            max_vouchers_per_wallet: self.max_vouchers_per_wallet(),
+            restocks: self.restocks(),
+        })
+    }
+}
+
+impl<D: AsRef<[u8]> + AsMut<[u8]>> PackSetView<D> {
+    fn data_mut(&mut self) -> &mut [u8] {
+        self.data.as_mut()
+    }
+
+    /// Decrement total editions pack can mint
+    pub fn decrement_supply(&mut self) -> Result<(), ProgramError> {
+        let total_editions = self.total_editions().error_decrement()?;
+        write_u64(self.data_mut(), TOTAL_EDITIONS_OFFSET, total_editions);
+
+        Ok(())
+    }
+
+    /// Take edition for a draw from pack supply, pack with unlimited distribution has no supply.
+    /// Edition is taken on request, so draw resolved later always has a card left
+    pub fn reserve_edition(&mut self) -> Result<(), ProgramError> {
+        if self.distribution_type()? == PackDistributionType::Unlimited {
+            return Ok(());
+        }
+
+        if self.total_editions() == 0 {
+            return Err(NFTPacksError::MissingEditionsInPack.into());
+        }
+
+        self.decrement_supply()
+    }
+
+    /// Write all the fields
+    pub fn write(&mut self, pack_set: &PackSet) -> Result<(), ProgramError> {
+        let data = self.data_mut();
+
+        if data.len() < LEGACY_LAYOUT_LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        data[ACCOUNT_TYPE_OFFSET] = match pack_set.account_type {
+            AccountType::Uninitialized => AccountType::Uninitialized as u8,
+            AccountType::PackSet => AccountType::PackSetV2 as u8,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        write_bytes(data, STORE_OFFSET, 32, pack_set.store.as_ref())?;
+        write_bytes(data, AUTHORITY_OFFSET, 32, pack_set.authority.as_ref())?;
+        write_bytes(
+            data,
+            DESCRIPTION_OFFSET,
+            MAX_DESCRIPTION_LEN,
+            pack_set.description.as_bytes(),
+        )?;
+        write_bytes(data, URI_OFFSET, MAX_URI_LENGTH, pack_set.uri.as_bytes())?;
+        write_bytes(data, NAME_OFFSET, 32, &pack_set.name)?;
+        write_u32(data, PACK_CARDS_OFFSET, pack_set.pack_cards);
+        write_u32(data, PACK_VOUCHERS_OFFSET, pack_set.pack_vouchers);
+        write_u64(data, TOTAL_WEIGHT_OFFSET, pack_set.total_weight);
+        write_u64(data, TOTAL_EDITIONS_OFFSET, pack_set.total_editions);
+        data[MUTABLE_OFFSET] = pack_set.mutable as u8;
+        data[PACK_STATE_OFFSET] = pack_set.pack_state.clone() as u8;
+        data[DISTRIBUTION_TYPE_OFFSET] = pack_set.distribution_type.clone() as u8;
+        write_u32(
+            data,
+            ALLOWED_AMOUNT_TO_REDEEM_OFFSET,
+            pack_set.allowed_amount_to_redeem,
+        );
+        write_u64(data, REDEEM_START_DATE_OFFSET, pack_set.redeem_start_date);
+        write_option(
+            data,
+            REDEEM_END_DATE_OFFSET,
+            8,
+            pack_set.redeem_end_date.map(u64::to_le_bytes).as_ref(),
+        )?;
+
+        // clear data left from borsh serialization and reserved space
+        for byte in data[LEGACY_LAYOUT_LEN..].iter_mut() {
+            *byte = 0;
+        }
+
+        if data.len() < Self::LAYOUT_LEN {
+            if has_new_fields(pack_set) {
+                return Err(NFTPacksError::PackSetTooSmall.into());
+            }
+
+            return Ok(());
+        }
+
+        write_option(
+            data,
+            PRICE_OFFSET,
+            8,
+            pack_set.price.map(u64::to_le_bytes).as_ref(),
+        )?;
+        write_option(
+            data,
+            PAYMENT_MINT_OFFSET,
+            32,
+            pack_set.payment_mint.as_ref(),
+        )?;
+
+        write_len(
+            data,
+            TREASURY_RECIPIENTS_OFFSET,
+            MAX_TREASURY_RECIPIENTS * TREASURY_RECIPIENT_LEN,
+            pack_set.treasury_recipients.len(),
+            MAX_TREASURY_RECIPIENTS,
+        )?;
+        for (i, recipient) in pack_set.treasury_recipients.iter().enumerate() {
+            let offset = TREASURY_RECIPIENTS_OFFSET + 1 + i * TREASURY_RECIPIENT_LEN;
+            write_bytes(data, offset, 32, recipient.address.as_ref())?;
+            write_u16(data, offset + 32, recipient.share);
+        }
+
+        data[RANDOMNESS_VERSION_OFFSET] = pack_set.randomness_version.clone() as u8;
+        let (source, signer) = match &pack_set.randomness_source {
+            RandomnessSource::Oracle => (0, None),
+            RandomnessSource::CommitReveal => (1, None),
+            RandomnessSource::SignedValue { signer } => (2, Some(signer)),
+        };
+        data[RANDOMNESS_SOURCE_OFFSET] = source;
+        write_bytes(
+            data,
+            RANDOMNESS_SOURCE_OFFSET + 1,
+            32,
+            signer.map_or(&[][..], |signer| signer.as_ref()),
+        )?;
+        write_option(
+            data,
+            RANDOMNESS_COMMITMENT_OFFSET,
+            32,
+            pack_set.randomness_commitment.as_ref(),
+        )?;
+        write_u32(data, RANDOMNESS_ROUNDS_OFFSET, pack_set.randomness_rounds);
+
+        write_len(
+            data,
+            RARITY_TIERS_OFFSET,
+            MAX_RARITY_TIERS * 2,
+            pack_set.rarity_tiers.len(),
+            MAX_RARITY_TIERS,
+        )?;
+        for (i, tier) in pack_set.rarity_tiers.iter().enumerate() {
+            write_u16(data, RARITY_TIERS_OFFSET + 1 + i * 2, tier.probability);
+        }
+
+        write_len(
+            data,
+            PACK_SLOTS_OFFSET,
+            MAX_PACK_SLOTS * 2,
+            pack_set.pack_slots.len(),
+            MAX_PACK_SLOTS,
+        )?;
+        for (i, slot) in pack_set.pack_slots.iter().enumerate() {
+            write_u16(data, PACK_SLOTS_OFFSET + 1 + i * 2, slot.tiers);
+        }
+
+        data[UNIQUE_CARDS_OFFSET] = pack_set.unique_cards as u8;
+        let pity_rule = pack_set.pity_rule.as_ref().map(|rule| {
+            let mut value = [0u8; 8];
+            value[..4].copy_from_slice(&rule.rare_weight_threshold.to_le_bytes());
+            value[4..].copy_from_slice(&rule.max_misses.to_le_bytes());
+            value
+        });
+        write_option(data, PITY_RULE_OFFSET, 8, pity_rule.as_ref())?;
+        write_bytes(data, PACK_CONFIG_OFFSET, 32, pack_set.pack_config.as_ref())?;
+        data[VERSION_OFFSET] = pack_set.version;
+
+        write_len(
+            data,
+            PHASES_OFFSET,
+            MAX_PACK_PHASES * PHASE_LEN,
+            pack_set.phases.len(),
+            MAX_PACK_PHASES,
+        )?;
+        for (i, phase) in pack_set.phases.iter().enumerate() {
+            write_phase(data, PHASES_OFFSET + 1 + i * PHASE_LEN, phase)?;
+        }
+        write_option(
+            data,
+            ALLOWLIST_ROOT_OFFSET,
+            32,
+            pack_set.allowlist_root.as_ref(),
+        )?;
+        data[HOLDER_GATE_OFFSET] = pack_set.holder_gate.is_some() as u8;
+        if let Some(holder_gate) = &pack_set.holder_gate {
+            write_holder_gate(data, HOLDER_GATE_OFFSET + 1, holder_gate);
+        }
+        write_option(
+            data,
+            MAX_VOUCHERS_PER_WALLET_OFFSET,
+            4,
+            pack_set
+                .max_vouchers_per_wallet
+                .map(u32::to_le_bytes)
+                .as_ref(),
+        )?;
+        write_u32(data, RESTOCKS_OFFSET, pack_set.restocks);
+
+        Ok(())
+    }
+}
+
+impl<'a> PackSetView<&'a mut [u8]> {
+    /// Load pack set to update it in place, pack set serialized with borsh is converted to fixed layout
+    pub fn load_mut(data: &'a mut [u8]) -> Result<Self, ProgramError> {
+        if PackSet::is_legacy(data) {
+            let pack_set = PackSet::unpack(data)?;
+            PackSetView::new_unchecked(&mut *data).write(&pack_set)?;
+        }
+
+        Self::new(data)
+    }
+}
+
+impl<'a> PackSetView<Cow<'a, [u8]>> {
+    /// Load pack set to read it, pack set serialized with borsh is converted to fixed layout
+    /// in a copy of data so account can be passed as readonly
+    pub fn load(data: &'a [u8]) -> Result<Self, ProgramError> {
+        if PackSet::is_legacy(data) {
+            let pack_set = PackSet::unpack(data)?;
+            let mut buffer = vec![0; data.len()];
+            PackSetView::new_unchecked(&mut buffer[..]).write(&pack_set)?;
+
+            return Self::new(Cow::Owned(buffer));
+        }
+
+        Self::new(Cow::Borrowed(data))
+    }
+}
+
+/// Check if pack set has fields which pack set of legacy size has no space for, its version isn't
+/// stored and is read as 0
+fn has_new_fields(pack_set: &PackSet) -> bool {
+    pack_set.price.is_some()
+        || pack_set.payment_mint.is_some()
+        || !pack_set.treasury_recipients.is_empty()
+        || pack_set.randomness_version != RandomnessVersion::V0
+        || pack_set.randomness_source != RandomnessSource::Oracle
+        || pack_set.randomness_commitment.is_some()
+        || pack_set.randomness_rounds != 0
+        || !pack_set.rarity_tiers.is_empty()
+        || !pack_set.pack_slots.is_empty()
+        || pack_set.unique_cards
+        || pack_set.pity_rule.is_some()
+        || pack_set.pack_config != Pubkey::default()
+        || !pack_set.phases.is_empty()
+        || pack_set.allowlist_root.is_some()
+        || pack_set.holder_gate.is_some()
+        || pack_set.max_vouchers_per_wallet.is_some()
+        || pack_set.restocks != 0
+}
+
+fn read_phase(data: &[u8], offset: usize) -> Result<PackPhase, ProgramError> {
+    let gate = match data[offset + 17] {
+        0 => PhaseGate::Open,
+        1 => PhaseGate::Allowlist {
+            mint: read_pubkey(data, offset + 18),
+        },
+        2 => PhaseGate::TokenHolder {
+            mint: read_pubkey(data, offset + 18),
+        },
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+
+    Ok(PackPhase {
+        start_date: read_u64(data, offset),
+        end_date: read_option(data, offset + 8).map(|offset| read_u64(data, offset)),
+        gate,
+        max_per_wallet: read_option(data, offset + 50).map(|offset| read_u32(data, offset)),
+    })
+}
+
+fn write_phase(data: &mut [u8], offset: usize, phase: &PackPhase) -> Result<(), ProgramError> {
+    write_u64(data, offset, phase.start_date);
+    write_option(
+        data,
+        offset + 8,
+        8,
+        phase.end_date.map(u64::to_le_bytes).as_ref(),
+    )?;
+    let (gate, mint) = match &phase.gate {
+        PhaseGate::Open => (0, None),
+        PhaseGate::Allowlist { mint } => (1, Some(mint)),
+        PhaseGate::TokenHolder { mint } => (2, Some(mint)),
+    };
+    data[offset + 17] = gate;
+    write_bytes(
+        data,
+        offset + 18,
+        32,
+        mint.map_or(&[][..], |mint| mint.as_ref()),
+    )?;
+    write_option(
+        data,
+        offset + 50,
+        4,
+        phase.max_per_wallet.map(u32::to_le_bytes).as_ref(),
+    )
+}
+
+fn read_holder_gate(data: &[u8], offset: usize) -> Result<HolderGate, ProgramError> {
+    let kind = match data[offset] {
+        0 => HolderGateKind::Collection {
+            mint: read_pubkey(data, offset + 1),
+        },
+        1 => HolderGateKind::VerifiedCreator {
+            creator: read_pubkey(data, offset + 1),
+        },
+        _ => return Err(ProgramError::InvalidAccountData),
+    };
+
+    Ok(HolderGate {
+        kind,
+        max_uses: read_u32(data, offset + 33),
+    })
+}
+
+fn write_holder_gate(data: &mut [u8], offset: usize, holder_gate: &HolderGate) {
+    let (kind, key) = match &holder_gate.kind {
+        HolderGateKind::Collection { mint } => (0, mint),
+        HolderGateKind::VerifiedCreator { creator } => (1, creator),
+    };
+    data[offset] = kind;
+    data[offset + 1..offset + 33].copy_from_slice(key.as_ref());
+    write_u32(data, offset + 33, holder_gate.max_uses);
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(read_array(data, offset))
+}
+
+fn read_array(data: &[u8], offset: usize) -> [u8; 32] {
+    let mut value = [0u8; 32];
+    value.copy_from_slice(&data[offset..offset + 32]);
+    value
+}
+
+fn read_string(data: &[u8], offset: usize, max_len: usize) -> Result<String, ProgramError> {
+    let bytes = &data[offset..offset + max_len];
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+
+    String::from_utf8(bytes[..len].to_vec()).map_err(|_| ProgramError::InvalidAccountData)
+}
+
+/// Returns offset of the value if option is set
+fn read_option(data: &[u8], offset: usize) -> Option<usize> {
+    if data[offset] != 0 {
+        Some(offset + 1)
+    } else {
+        None
+    }
+}
+
+fn read_len(data: &[u8], offset: usize, max_len: usize) -> Result<usize, ProgramError> {
+    let len = data[offset] as usize;
+
+    if len > max_len {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(len)
+}
+
+/// Write bytes to the field of `len` bytes, the rest of it is filled with zeroes
+fn write_bytes(
+    data: &mut [u8],
+    offset: usize,
+    len: usize,
+    value: &[u8],
+) -> Result<(), ProgramError> {
+    if value.len() > len {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    data[offset..offset + value.len()].copy_from_slice(value);
+    for byte in data[offset + value.len()..offset + len].iter_mut() {
+        *byte = 0;
+    }
+
+    Ok(())
+}
+
+fn write_option<T: AsRef<[u8]>>(
+    data: &mut [u8],
+    offset: usize,
+    len: usize,
+    value: Option<&T>,
+) -> Result<(), ProgramError> {
+    data[offset] = value.is_some() as u8;
+    write_bytes(
+        data,
+        offset + 1,
+        len,
+        value.map_or(&[][..], |value| value.as_ref()),
+    )
+}
+
+/// Write vector length and clear space of its items
+fn write_len(
+    data: &mut [u8],
+    offset: usize,
+    items_len: usize,
+    len: usize,
+    max_len: usize,
+) -> Result<(), ProgramError> {
+    if len > max_len {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+
+    data[offset] = len as u8;
+    write_bytes(data, offset + 1, items_len, &[])
+}
@@ -1,9 +1,9 @@
 //! Pack voucher definitions
 
 use super::*;
+use crate::PROGRAM_VERSION;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
@@ -21,6 +21,10 @@ pub struct PackVoucher {
     pub master: Pubkey,
     /// Metadata account
     pub metadata: Pubkey,
+    /// Program token account which holds MasterEdition token, if it's set vouchers can be sold
+    pub token_account: Option<Pubkey>,
+    /// Layout version
+    pub version: u8,
 }
 
 impl PackVoucher {
@@ -33,6 +37,8 @@ impl PackVoucher {
         self.pack_set = params.pack_set;
         self.master = params.master;
         self.metadata = params.metadata;
+        self.token_account = params.token_account;
+        self.version = PROGRAM_VERSION;
     }
 }
 
@@ -44,25 +50,45 @@ pub struct InitPackVoucherParams {
     pub master: Pubkey,
     /// Metadata account
     pub metadata: Pubkey,
+    /// Program token account which holds MasterEdition token
+    pub token_account: Option<Pubkey>,
 }
 
 impl Sealed for PackVoucher {}
 
 impl Pack for PackVoucher {
-    // 1 + 32 + 32 + 32
-    const LEN: usize = 97;
+    // 1 + 32 + 32 + 32 + (1 + 32) + version 1 + reserved 32
+    const LEN: usize = 163;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let mut slice = dst;
-        self.serialize(&mut slice).unwrap()
+        pack_versioned(self, dst).unwrap()
     }
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let mut src_mut = src;
-        Self::deserialize(&mut src_mut).map_err(|_| {
-            msg!("Failed to deserialize");
-            ProgramError::InvalidAccountData
-        })
+        unpack_versioned(src, Self::LEN)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for PackVoucher {
+    // 1 + 32 + 32 + 32
+    const LEGACY_LEN: usize = 97;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
     }
 }
 
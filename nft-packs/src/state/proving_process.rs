@@ -1,10 +1,9 @@
 //! Proving process definitions
 
 use super::*;
+use crate::PROGRAM_VERSION;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
-    borsh::try_from_slice_unchecked,
-    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
@@ -29,6 +28,14 @@ pub struct ProvingProcess {
     pub cards_redeemed: u32,
     /// BTreeMap with cards to redeem and statuses if it's already redeemed
     pub cards_to_redeem: BTreeMap<u32, u32>,
+    /// Counter of cards drawn for this proving process
+    pub draw_counter: u32,
+    /// Draws waiting for revealed randomness, used with `RandomnessSource::CommitReveal`
+    pub pending_draws: u32,
+    /// Randomness round pending draws were requested in, see `RandomnessRound`
+    pub pending_round: u32,
+    /// Layout version
+    pub version: u8,
 }
 
 impl ProvingProcess {
@@ -45,6 +52,10 @@ impl ProvingProcess {
         self.voucher_mint = params.voucher_mint;
         self.pack_set = params.pack_set;
         self.cards_to_redeem = BTreeMap::new();
+        self.draw_counter = 0;
+        self.pending_draws = 0;
+        self.pending_round = 0;
+        self.version = PROGRAM_VERSION;
     }
 }
 
@@ -61,22 +72,54 @@ pub struct InitProvingProcessParams {
 impl Sealed for ProvingProcess {}
 
 impl Pack for ProvingProcess {
-    // 1 + 32 + 1 + 32 + 32 + 4 + BTreeMap size for 100 cards(800)
+    // 1 + 32 + 1 + 32 + 32 + 4 + BTreeMap size for 100 cards(800) + 4 + 4 + 4 + version 1 + reserved 32
     // When calculating size for custom data structures like `BTreeMap` does not
     // include structure header size(in that case is always 24-bytes).
     // Calculate size for underlying(template) types only(u32 + u32 = 8bytes in this case).
-    const LEN: usize = 902;
+    const LEN: usize = 947;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
-        let mut slice = dst;
-        self.serialize(&mut slice).unwrap()
+        pack_versioned(self, dst).unwrap()
     }
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        try_from_slice_unchecked(src).map_err(|_| {
-            msg!("Failed to deserialize");
-            ProgramError::InvalidAccountData
-        })
+        let mut result: Self = unpack_versioned(src, Self::LEN)?;
+
+        // Draws weren't counted before versioning, they are claimed cards and cards left to claim
+        if result.version == 0 {
+            let draws = result
+                .cards_to_redeem
+                .values()
+                .fold(result.cards_redeemed, |draws, amount| {
+                    draws.saturating_add(*amount)
+                });
+            result.draw_counter = result.draw_counter.max(draws);
+        }
+
+        Ok(result)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for ProvingProcess {
+    // 1 + 32 + 1 + 32 + 32 + 4 + BTreeMap size for 100 cards(800)
+    const LEGACY_LEN: usize = 902;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
     }
 }
 
@@ -0,0 +1,131 @@
+//! Randomness round definitions
+
+use super::*;
+use crate::{error::NFTPacksError, PROGRAM_VERSION};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    clock::Slot,
+    hash::hashv,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Round of commit reveal randomness, see `RandomnessSource::CommitReveal`.
+/// Draws requested while round is open are resolved with its seed which is hash of authority secret
+/// and hash of the slot secret was revealed at, so nobody knows the seed at the moment of request.
+/// PDA (["round", pack_key, round_index], program_id)
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct RandomnessRound {
+    /// Account type - RandomnessRound
+    pub account_type: AccountType,
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// Index of round in pack set
+    pub index: u32,
+    /// sha256 hash of authority secret
+    pub commitment: [u8; 32],
+    /// Revealed authority secret
+    pub secret: Option<[u8; 32]>,
+    /// Slot secret was revealed at, its hash is mixed into seed
+    pub reveal_slot: Slot,
+    /// Seed of round draws, set by the first draw resolved after reveal
+    pub seed: Option<[u8; 32]>,
+    /// Layout version
+    pub version: u8,
+}
+
+impl RandomnessRound {
+    /// Prefix used to generate account
+    pub const PREFIX: &'static str = "round";
+
+    /// Initialize a RandomnessRound
+    pub fn init(&mut self, params: InitRandomnessRoundParams) {
+        self.account_type = AccountType::RandomnessRound;
+        self.pack_set = params.pack_set;
+        self.index = params.index;
+        self.commitment = params.commitment;
+        self.secret = None;
+        self.reveal_slot = 0;
+        self.seed = None;
+        self.version = PROGRAM_VERSION;
+    }
+
+    /// Reveal authority secret, its hash should match commitment
+    pub fn reveal(&mut self, secret: [u8; 32], slot: Slot) -> Result<(), ProgramError> {
+        if hashv(&[&secret]).to_bytes() != self.commitment {
+            return Err(NFTPacksError::WrongRandomnessSecret.into());
+        }
+
+        self.secret = Some(secret);
+        self.reveal_slot = slot;
+
+        Ok(())
+    }
+
+    /// Mix revealed secret with hash of reveal slot and save it as round seed
+    pub fn seal(&mut self, reveal_slot_hash: &[u8; 32]) -> Result<[u8; 32], ProgramError> {
+        let secret = self.secret.ok_or(NFTPacksError::RandomnessNotRevealed)?;
+        let seed = hashv(&[&secret, reveal_slot_hash]).to_bytes();
+
+        self.seed = Some(seed);
+
+        Ok(seed)
+    }
+}
+
+/// Initialize a RandomnessRound params
+pub struct InitRandomnessRoundParams {
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// Index of round in pack set
+    pub index: u32,
+    /// sha256 hash of authority secret
+    pub commitment: [u8; 32],
+}
+
+impl Sealed for RandomnessRound {}
+
+impl Pack for RandomnessRound {
+    // 1 + 32 + 4 + 32 + 1 + 32 + 8 + 1 + 32 + version 1 + reserved 32
+    const LEN: usize = 176;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_versioned(self, dst).unwrap()
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_versioned(src, Self::LEN)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for RandomnessRound {
+    // account was added with versioning
+    const LEGACY_LEN: usize = Self::LEN;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl IsInitialized for RandomnessRound {
+    fn is_initialized(&self) -> bool {
+        self.account_type != AccountType::Uninitialized
+            && self.account_type == AccountType::RandomnessRound
+    }
+}
@@ -0,0 +1,101 @@
+//! Voucher lookup definitions
+
+use super::*;
+use crate::PROGRAM_VERSION;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// Index of pack voucher with given master edition, so voucher can be found by edition parent.
+/// PDA (["voucher_master", pack_key, master_key], program_id)
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct VoucherLookup {
+    /// Account type - VoucherLookup
+    pub account_type: AccountType,
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// Master edition account
+    pub master: Pubkey,
+    /// Index of pack voucher, changes when other voucher is deleted
+    pub index: u32,
+    /// Layout version
+    pub version: u8,
+}
+
+impl VoucherLookup {
+    /// Prefix used to generate account
+    pub const PREFIX: &'static str = "voucher_master";
+
+    /// Initialize a VoucherLookup
+    pub fn init(&mut self, params: InitVoucherLookupParams) {
+        self.account_type = AccountType::VoucherLookup;
+        self.pack_set = params.pack_set;
+        self.master = params.master;
+        self.index = params.index;
+        self.version = PROGRAM_VERSION;
+    }
+
+    /// Check if account data holds voucher lookup
+    pub fn is_lookup(data: &[u8]) -> bool {
+        data.first() == Some(&(AccountType::VoucherLookup as u8))
+    }
+}
+
+/// Initialize a VoucherLookup params
+pub struct InitVoucherLookupParams {
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// Master edition account
+    pub master: Pubkey,
+    /// Index of pack voucher
+    pub index: u32,
+}
+
+impl Sealed for VoucherLookup {}
+
+impl Pack for VoucherLookup {
+    // 1 + 32 + 32 + 4 + version 1 + reserved 32
+    const LEN: usize = 102;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_versioned(self, dst).unwrap()
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_versioned(src, Self::LEN)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for VoucherLookup {
+    // account was added with versioning
+    const LEGACY_LEN: usize = Self::LEN;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl IsInitialized for VoucherLookup {
+    fn is_initialized(&self) -> bool {
+        self.account_type != AccountType::Uninitialized
+            && self.account_type == AccountType::VoucherLookup
+    }
+}
@@ -0,0 +1,157 @@
+//! Wallet record definitions
+
+use super::*;
+use crate::{error::NFTPacksError, math::SafeMath, PROGRAM_VERSION};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+/// State of user wallet in pack set. PDA (["wallet", pack_key, wallet_key], program_id)
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, BorshSchema, Default)]
+pub struct WalletRecord {
+    /// Account type - WalletRecord
+    pub account_type: AccountType,
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// User wallet
+    pub wallet: Pubkey,
+    /// Draws since the last rare card, see `PityRule`
+    pub pity_counter: u32,
+    /// Layout version
+    pub version: u8,
+    /// Vouchers opened in every pack phase, see `PackPhase`
+    pub phase_vouchers: [u32; MAX_PACK_PHASES],
+    /// Vouchers opened with allowlist quota, see `AllowlistProof`
+    pub allowlist_vouchers: u32,
+    /// Vouchers of the pack opened by wallet, see `PackSet::max_vouchers_per_wallet`
+    pub opened_vouchers: u32,
+}
+
+impl WalletRecord {
+    /// Prefix used to generate account
+    pub const PREFIX: &'static str = "wallet";
+
+    /// Initialize a WalletRecord
+    pub fn init(&mut self, params: InitWalletRecordParams) {
+        self.account_type = AccountType::WalletRecord;
+        self.pack_set = params.pack_set;
+        self.wallet = params.wallet;
+        self.pity_counter = 0;
+        self.version = PROGRAM_VERSION;
+        self.phase_vouchers = [0; MAX_PACK_PHASES];
+        self.allowlist_vouchers = 0;
+        self.opened_vouchers = 0;
+    }
+
+    /// Reset pity counter if rare card was drawn, increment it otherwise
+    pub fn update_pity_counter(&mut self, is_rare: bool) -> Result<(), ProgramError> {
+        if is_rare {
+            self.pity_counter = 0;
+        } else {
+            self.pity_counter = self.pity_counter.error_increment()?;
+        }
+
+        Ok(())
+    }
+
+    /// Count voucher opened in pack phase, wallet can't open more than `max_per_wallet` vouchers in it
+    pub fn add_phase_voucher(
+        &mut self,
+        phase: usize,
+        max_per_wallet: Option<u32>,
+    ) -> Result<(), ProgramError> {
+        let phase_vouchers = self
+            .phase_vouchers
+            .get_mut(phase)
+            .ok_or(NFTPacksError::WrongPackPhases)?;
+
+        if let Some(max_per_wallet) = max_per_wallet {
+            if *phase_vouchers >= max_per_wallet {
+                return Err(NFTPacksError::PhaseWalletLimitReached.into());
+            }
+        }
+
+        *phase_vouchers = phase_vouchers.error_increment()?;
+
+        Ok(())
+    }
+
+    /// Count voucher opened by allowlisted wallet, wallet can't open more than `quota` vouchers
+    pub fn add_allowlist_voucher(&mut self, quota: u32) -> Result<(), ProgramError> {
+        if self.allowlist_vouchers >= quota {
+            return Err(NFTPacksError::AllowlistQuotaReached.into());
+        }
+
+        self.allowlist_vouchers = self.allowlist_vouchers.error_increment()?;
+
+        Ok(())
+    }
+
+    /// Count voucher opened by wallet, wallet can't open more than `max_vouchers` vouchers of the pack
+    pub fn add_opened_voucher(&mut self, max_vouchers: u32) -> Result<(), ProgramError> {
+        if self.opened_vouchers >= max_vouchers {
+            return Err(NFTPacksError::WalletVoucherLimitReached.into());
+        }
+
+        self.opened_vouchers = self.opened_vouchers.error_increment()?;
+
+        Ok(())
+    }
+}
+
+/// Initialize a WalletRecord params
+pub struct InitWalletRecordParams {
+    /// Pack set
+    pub pack_set: Pubkey,
+    /// User wallet
+    pub wallet: Pubkey,
+}
+
+impl Sealed for WalletRecord {}
+
+impl Pack for WalletRecord {
+    // 1 + 32 + 32 + 4 + version 1 + phase_vouchers 4 * 5 + allowlist_vouchers 4 + opened_vouchers 4 + reserved 4
+    const LEN: usize = 102;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_versioned(self, dst).unwrap()
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_versioned(src, Self::LEN)
+    }
+
+    fn pack(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        assert_versioned_len(dst.len(), Self::LEN, Self::LEGACY_LEN)?;
+        pack_versioned(&src, dst)
+    }
+
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        assert_versioned_len(input.len(), Self::LEN, Self::LEGACY_LEN)?;
+        Self::unpack_from_slice(input)
+    }
+}
+
+impl Versioned for WalletRecord {
+    // account was added with versioning
+    const LEGACY_LEN: usize = Self::LEN;
+
+    fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    fn set_version(&mut self, version: u8) {
+        self.version = version;
+    }
+}
+
+impl IsInitialized for WalletRecord {
+    fn is_initialized(&self) -> bool {
+        self.account_type != AccountType::Uninitialized
+            && self.account_type == AccountType::WalletRecord
+    }
+}
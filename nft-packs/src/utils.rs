@@ -1,19 +1,31 @@
 //! Program utils
 
-use crate::{error::NFTPacksError, math::SafeMath, state::{MAX_LAG_SLOTS, ProvingProcess}};
+use crate::{
+    error::NFTPacksError,
+    find_pack_config_program_address,
+    math::SafeMath,
+    state::{ProvingProcess, RandomnessVersion, MAX_LAG_SLOTS},
+};
 use solana_program::{
     account_info::AccountInfo,
-    clock::Clock,
+    clock::{Clock, Slot},
     entrypoint::ProgramResult,
+    hash::hashv,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
+    sysvar::{
+        self,
+        instructions::{load_current_index, load_instruction_at},
+    },
 };
 use std::collections::hash_map::DefaultHasher;
+use std::convert::TryInto;
 use std::hash::Hasher;
+use std::mem::size_of;
 use borsh::BorshSerialize;
 
 /// Assert uninitialized
@@ -52,6 +64,27 @@ pub fn assert_account_key(account_info: &AccountInfo, key: &Pubkey) -> ProgramRe
     }
 }
 
+/// Assert account is pack config of pack set and return true if it's `LegacyPackConfig`.
+/// Pack sets without stored pack config key use legacy config PDA
+pub fn assert_pack_config(
+    program_id: &Pubkey,
+    pack_set: &Pubkey,
+    pack_config: &Pubkey,
+    pack_config_account: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    assert_owned_by(pack_config_account, program_id)?;
+
+    if *pack_config != Pubkey::default() {
+        assert_account_key(pack_config_account, pack_config)?;
+        return Ok(false);
+    }
+
+    let (legacy_pack_config, _) = find_pack_config_program_address(program_id, pack_set);
+    assert_account_key(pack_config_account, &legacy_pack_config)?;
+
+    Ok(true)
+}
+
 /// Assert account rent exempt
 pub fn assert_rent_exempt(rent: &Rent, account_info: &AccountInfo) -> ProgramResult {
     if !rent.is_exempt(account_info.lamports(), account_info.data_len()) {
@@ -244,12 +277,34 @@ pub fn empty_account_balance(
     Ok(())
 }
 
-/// get random value from oracle account
+/// Resize program account, payer tops up its balance so it stays rent exempt
+pub fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    len: usize,
+) -> ProgramResult {
+    let lamports = rent.minimum_balance(len).saturating_sub(account.lamports());
+
+    if lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    account.realloc(len, true)
+}
+
+/// get random value from oracle account, derivation depends on pack set randomness version
 pub fn get_random_oracle_value(
     randomness_oracle_account: &AccountInfo,
+    pack_set_key: &Pubkey,
+    randomness_version: &RandomnessVersion,
     proving_process: &ProvingProcess,
     clock: &Clock,
-) -> Result<u16, ProgramError> {
+) -> Result<u64, ProgramError> {
     let (oracle_random_value, slot) =
         randomness_oracle_program::read_value(randomness_oracle_account)?;
 
@@ -257,14 +312,276 @@ pub fn get_random_oracle_value(
         return Err(NFTPacksError::RandomOracleOutOfDate.into());
     }
 
-    // Hash random value from the oracle with current slot and proving process data and receive new random u16
-    let mut hasher = DefaultHasher::new();
-    hasher.write(oracle_random_value.as_ref());
-    hasher.write(proving_process.try_to_vec()?.as_ref());
-    hasher.write_u64(clock.slot);
+    match randomness_version {
+        RandomnessVersion::V0 => {
+            // Draw counters and version are the last fields of proving process and aren't hashed to keep legacy values
+            let counters_len = size_of::<u32>() * 3 + size_of::<u8>();
+            let mut proving_process_data = proving_process.try_to_vec()?;
+            proving_process_data.truncate(proving_process_data.len() - counters_len);
+
+            // Hash random value from the oracle with current slot and proving process data and receive new random u16
+            let mut hasher = DefaultHasher::new();
+            hasher.write(oracle_random_value.as_ref());
+            hasher.write(proving_process_data.as_ref());
+            hasher.write_u64(clock.slot);
+
+            let mut random_value: [u8; 2] = [0u8; 2];
+            random_value.copy_from_slice(&hasher.finish().to_le_bytes()[..2]);
+
+            Ok(u16::from_le_bytes(random_value) as u64)
+        }
+        RandomnessVersion::V1 => Ok(derive_random_value(
+            oracle_random_value.as_ref(),
+            clock.slot,
+            pack_set_key,
+            &proving_process.voucher_mint,
+            proving_process.draw_counter,
+        )),
+    }
+}
+
+/// Ed25519 signature verification program
+pub mod ed25519_program {
+    solana_program::declare_id!("Ed25519SigVerify111111111111111111111111111");
+}
+
+/// Size of Ed25519 program signature offsets entry
+const ED25519_SIGNATURE_OFFSETS_LEN: usize = 14;
+
+/// Start of signature offsets in Ed25519 program instruction data
+const ED25519_SIGNATURE_OFFSETS_START: usize = 2;
+
+/// Ed25519 instruction index which points to the Ed25519 instruction itself
+const ED25519_CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Message signed by randomness signer for a draw
+///
+/// `proving_process || draw_counter || value` where draw counter is little endian u32
+pub fn signed_randomness_message(
+    proving_process: &Pubkey,
+    draw_counter: u32,
+    value: &[u8; 32],
+) -> Vec<u8> {
+    [
+        proving_process.as_ref(),
+        &draw_counter.to_le_bytes(),
+        value.as_ref(),
+    ]
+    .concat()
+}
+
+/// get random value signed by pack randomness signer.
+///
+/// Signature is checked by Ed25519 program instruction placed before current instruction in the same transaction.
+/// Signed message is bound to proving process and its draw counter so it can't be replayed.
+pub fn get_signed_random_value(
+    instructions_account: &AccountInfo,
+    signer: &Pubkey,
+    pack_set_key: &Pubkey,
+    proving_process_key: &Pubkey,
+    proving_process: &ProvingProcess,
+) -> Result<u64, ProgramError> {
+    assert_account_key(instructions_account, &sysvar::instructions::id())?;
+
+    let instructions_data = instructions_account.data.borrow();
+    let current_index = load_current_index(&instructions_data);
+
+    for index in 0..current_index {
+        let instruction = load_instruction_at(index as usize, &instructions_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        if instruction.program_id != ed25519_program::id() {
+            continue;
+        }
+
+        for (public_key, message) in read_ed25519_messages(&instruction.data, &instructions_data)? {
+            if public_key != *signer || message.len() < 32 {
+                continue;
+            }
+
+            let mut value = [0u8; 32];
+            value.copy_from_slice(&message[message.len() - 32..]);
+
+            let expected_message = signed_randomness_message(
+                proving_process_key,
+                proving_process.draw_counter,
+                &value,
+            );
+
+            if message == expected_message {
+                // Value is bound to the draw counter so slot isn't mixed in
+                return Ok(derive_random_value(
+                    &value,
+                    0,
+                    pack_set_key,
+                    &proving_process.voucher_mint,
+                    proving_process.draw_counter,
+                ));
+            }
+        }
+    }
+
+    Err(NFTPacksError::SignedRandomValueMissing.into())
+}
+
+/// Read public keys and messages which signatures are verified by Ed25519 program instruction
+fn read_ed25519_messages(
+    instruction_data: &[u8],
+    instructions_data: &[u8],
+) -> Result<Vec<(Pubkey, Vec<u8>)>, ProgramError> {
+    let signatures_count = *instruction_data
+        .first()
+        .ok_or(ProgramError::InvalidInstructionData)? as usize;
+
+    let mut messages = Vec::with_capacity(signatures_count);
+    for i in 0..signatures_count {
+        let start = ED25519_SIGNATURE_OFFSETS_START + i * ED25519_SIGNATURE_OFFSETS_LEN;
+        let offsets = instruction_data
+            .get(start..start + ED25519_SIGNATURE_OFFSETS_LEN)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+        // Offsets are: signature, signature instruction, public key, public key instruction,
+        // message, message size, message instruction
+        let public_key = read_ed25519_data(
+            instruction_data,
+            instructions_data,
+            read_u16(6),
+            read_u16(4) as usize,
+            32,
+        )?;
+        let message = read_ed25519_data(
+            instruction_data,
+            instructions_data,
+            read_u16(12),
+            read_u16(8) as usize,
+            read_u16(10) as usize,
+        )?;
+
+        messages.push((Pubkey::new(&public_key), message));
+    }
+
+    Ok(messages)
+}
+
+/// Read data slice Ed25519 program instruction points to
+fn read_ed25519_data(
+    instruction_data: &[u8],
+    instructions_data: &[u8],
+    instruction_index: u16,
+    offset: usize,
+    size: usize,
+) -> Result<Vec<u8>, ProgramError> {
+    let data = if instruction_index == ED25519_CURRENT_INSTRUCTION {
+        instruction_data.to_vec()
+    } else {
+        load_instruction_at(instruction_index as usize, instructions_data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?
+            .data
+    };
+
+    data.get(offset..offset + size)
+        .map(|data| data.to_vec())
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+/// Size of `SlotHashes` sysvar entry, slot followed by its hash
+const SLOT_HASH_ENTRY_LEN: usize = 8 + 32;
+
+/// Find hash of slot in `SlotHashes` sysvar, it holds hashes of the last 512 slots.
+///
+/// Sysvar is read in place because it's too big to deserialize on chain, entries are
+/// sorted by slot in descending order and prefixed with their little endian u64 count
+pub fn get_slot_hash(
+    slot_hashes_account: &AccountInfo,
+    slot: Slot,
+) -> Result<[u8; 32], ProgramError> {
+    assert_account_key(slot_hashes_account, &sysvar::slot_hashes::id())?;
+
+    let data = slot_hashes_account.data.borrow();
+    let count = data
+        .get(..8)
+        .and_then(|count| count.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or(ProgramError::InvalidAccountData)?;
 
-    let mut random_value: [u8; 2] = [0u8; 2];
-    random_value.copy_from_slice(&hasher.finish().to_le_bytes()[..2]);
+    for entry in data[8..]
+        .chunks_exact(SLOT_HASH_ENTRY_LEN)
+        .take(count as usize)
+    {
+        let entry_slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if entry_slot == slot {
+            return Ok(entry[8..].try_into().unwrap());
+        }
+
+        if entry_slot < slot {
+            break;
+        }
+    }
+
+    Err(NFTPacksError::SlotHashNotFound.into())
+}
+
+/// Derive random value for a draw.
+///
+/// Value is the first 8 bytes of
+/// `sha256(oracle_value || slot || pack_set || voucher_mint || draw_counter)`
+/// read as little endian u64, where slot and draw counter are little endian bytes too.
+/// It can be used off-chain to recompute every draw.
+pub fn derive_random_value(
+    oracle_value: &[u8],
+    slot: u64,
+    pack_set: &Pubkey,
+    voucher_mint: &Pubkey,
+    draw_counter: u32,
+) -> u64 {
+    let hash = hashv(&[
+        oracle_value,
+        &slot.to_le_bytes(),
+        pack_set.as_ref(),
+        voucher_mint.as_ref(),
+        &draw_counter.to_le_bytes(),
+    ]);
+
+    let mut random_value: [u8; 8] = [0u8; 8];
+    random_value.copy_from_slice(&hash.to_bytes()[..8]);
+
+    u64::from_le_bytes(random_value)
+}
+
+/// Derive next random value from the previous one, used to resample rejected values.
+///
+/// Value is the first 8 bytes of `sha256(random_value)` read as little endian u64
+/// where random value is little endian bytes too.
+pub fn next_random_value(random_value: u64) -> u64 {
+    let hash = hashv(&[&random_value.to_le_bytes()]);
+
+    let mut next_value: [u8; 8] = [0u8; 8];
+    next_value.copy_from_slice(&hash.to_bytes()[..8]);
+
+    u64::from_le_bytes(next_value)
+}
+
+/// Map random value uniformly onto `[0, range)` using only integer arithmetic.
+///
+/// Uses multiply-shift (Lemire) method. Values which would bias result are rejected
+/// and resampled with `next_random_value` so every number in range has the same odds.
+pub fn random_in_range(random_value: u64, range: u64) -> Result<u64, ProgramError> {
+    if range == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut random_value = random_value;
+    let mut product = random_value as u128 * range as u128;
+
+    if (product as u64) < range {
+        // 2^64 mod range
+        let threshold = range.wrapping_neg() % range;
+        while (product as u64) < threshold {
+            random_value = next_random_value(random_value);
+            product = random_value as u128 * range as u128;
+        }
+    }
 
-    Ok(u16::from_le_bytes(random_value))
+    Ok((product >> 64) as u64)
 }